@@ -1,8 +1,7 @@
-use anyhow::Result;
-use quick_xml::de::from_str;
-
 use super::client::CodebaseClient;
+use super::error::{Result, from_xml};
 use super::models::*;
+use super::pagination::Paginator;
 
 pub async fn account_activity(
     client: &CodebaseClient,
@@ -12,7 +11,7 @@ pub async fn account_activity(
 ) -> Result<Vec<Event>> {
     let path = build_activity_path("/activity", raw, since, page);
     let xml = client.get(&path).await?;
-    let events: Events = from_str(&xml)?;
+    let events: Events = from_xml(&xml)?;
     Ok(events.events)
 }
 
@@ -25,7 +24,33 @@ pub async fn project_activity(
 ) -> Result<Vec<Event>> {
     let path = build_activity_path(&format!("/{}/activity", project), raw, since, page);
     let xml = client.get(&path).await?;
-    let events: Events = from_str(&xml)?;
+    let events: Events = from_xml(&xml)?;
+    Ok(events.events)
+}
+
+/// Page through the account-wide activity feed.
+pub fn paginate_account_activity(
+    client: &CodebaseClient,
+    raw: bool,
+    since: Option<&str>,
+) -> Paginator<Event> {
+    let path = build_activity_path("/activity", raw, since, None);
+    client.paginate(&path, parse_events)
+}
+
+/// Page through a project's activity feed.
+pub fn paginate_project_activity(
+    client: &CodebaseClient,
+    project: &str,
+    raw: bool,
+    since: Option<&str>,
+) -> Paginator<Event> {
+    let path = build_activity_path(&format!("/{}/activity", project), raw, since, None);
+    client.paginate(&path, parse_events)
+}
+
+fn parse_events(xml: &str) -> Result<Vec<Event>> {
+    let events: Events = from_xml(xml)?;
     Ok(events.events)
 }
 
@@ -0,0 +1,119 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::client::DEFAULT_BASE_URL;
+use super::config::write_private;
+
+/// Ticket statuses, priorities, categories and types rarely change.
+pub const METADATA_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Project assignments change when people join or leave a project.
+pub const USERS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The project list carries ticket counts, so it is only cached briefly.
+pub const PROJECTS_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// On-disk cache of raw XML responses for slow-changing lookup endpoints.
+///
+/// Each cached response is one file named after the request path; an entry
+/// is fresh while its modification time is younger than the caller's TTL.
+/// Failures to read or write the cache are ignored and fall back to the API.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    /// A cache rooted at `dir`, which is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache for one set of credentials below a shared root, so that
+    /// profiles for different accounts or users never see each other's data.
+    pub fn for_user(root: &Path, account: &str, username: &str) -> Self {
+        Self::new(root.join(encode(account)).join(encode(username)))
+    }
+
+    /// Like [`for_user`](Self::for_user), for credentials used against
+    /// `base_url`. Endpoints other than CodebaseHQ's get their own
+    /// directory, so data from a staging server or mock never mixes with
+    /// real data.
+    pub fn for_endpoint(root: &Path, base_url: &str, account: &str, username: &str) -> Self {
+        if base_url.trim_end_matches('/') == DEFAULT_BASE_URL {
+            return Self::for_user(root, account, username);
+        }
+        Self::for_user(
+            &root.join("endpoints").join(encode(base_url)),
+            account,
+            username,
+        )
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cached body for `path`, if present and younger than `ttl`.
+    pub fn get(&self, path: &str, ttl: Duration) -> Option<String> {
+        let file = self.entry(path);
+        let modified = fs::metadata(&file).ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).ok()?;
+        if age >= ttl {
+            return None;
+        }
+        fs::read_to_string(file).ok()
+    }
+
+    /// Store `body` for `path`. Entries are readable only by the current
+    /// user, as are the directories created for them.
+    pub fn put(&self, path: &str, body: &str) {
+        if create_private_dir(&self.dir).is_ok() {
+            let _ = write_private(&self.entry(path), body);
+        }
+    }
+
+    /// Drop the cached body for `path` (after a write that changes it).
+    pub fn invalidate(&self, path: &str) {
+        let _ = fs::remove_file(self.entry(path));
+    }
+
+    /// Remove every entry in this cache.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    }
+
+    fn entry(&self, path: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.xml", encode(path.trim_start_matches('/'))))
+    }
+}
+
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)
+}
+
+/// Make `s` safe as a single file name, keeping it readable and unambiguous.
+fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' => out.push(b as char),
+            b'/' => out.push('~'),
+            _ => out.push_str(&format!("_{:02X}", b)),
+        }
+    }
+    out
+}
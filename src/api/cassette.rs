@@ -0,0 +1,302 @@
+//! Recording API sessions to disk and replaying them without a network.
+//!
+//! With `CB_RECORD=path` every request `cb` sends, and the response it got,
+//! is written to a JSON cassette file when the session ends; requests that
+//! got no response at all are recorded with the error instead, so failures
+//! replay too. With `CB_REPLAY=path` nothing is
+//! sent: each request is answered from the cassette instead, which makes a
+//! real session reproducible offline, e.g. to investigate a bug report or to
+//! write a regression test from it.
+//!
+//! Credentials never reach the file: the `Authorization` header and base URL
+//! aren't recorded, secret elements in bodies are replaced by
+//! [`REDACTED`](super::redact::REDACTED), and so is the API key wherever it
+//! appears.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::error::{CodebaseError, Result};
+use super::redact::{REDACTED, redact_xml};
+use super::transport::{TransportError, TransportErrorKind, TransportResponse};
+
+/// Environment variable naming a cassette file to record to.
+pub const RECORD_ENV: &str = "CB_RECORD";
+
+/// Environment variable naming a cassette file to replay from.
+pub const REPLAY_ENV: &str = "CB_REPLAY";
+
+/// One request and the response it got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    /// Path below the base URL, including any query string.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    /// The response status, or `0` when there was no response.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    #[serde(default)]
+    pub response_body: String,
+    /// Why the request got no response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+}
+
+/// A request that failed without a response, e.g. because the server was
+/// down. Replayed as a [`TransportError`] of the same kind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedError {
+    pub kind: TransportErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug)]
+struct State {
+    interactions: Vec<Interaction>,
+    /// For replay: which interactions have already been answered.
+    used: Vec<bool>,
+    /// For recording: interactions were added since the file was written.
+    unsaved: bool,
+}
+
+/// A cassette being recorded or replayed. Clones share the same recording,
+/// so every clone of a client adds to (or answers from) one file.
+///
+/// A recording is kept in memory and written by [`flush`](Self::flush), or
+/// when the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    path: PathBuf,
+    mode: Mode,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    fn new(path: PathBuf, mode: Mode, interactions: Vec<Interaction>) -> Self {
+        let used = vec![false; interactions.len()];
+        Self {
+            shared: Arc::new(Shared {
+                path,
+                mode,
+                state: Mutex::new(State {
+                    interactions,
+                    used,
+                    unsaved: false,
+                }),
+            }),
+        }
+    }
+
+    /// Record to `path`, replacing anything already there.
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self> {
+        let cassette = Self::new(path.into(), Mode::Record, Vec::new());
+        // Fail now rather than at the end of the session if the file
+        // can't be written.
+        cassette.shared.save(&[])?;
+        Ok(cassette)
+    }
+
+    /// Replay the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let text = fs::read_to_string(&path)
+            .map_err(|e| cassette_error(&path, format!("cannot read: {}", e)))?;
+        let file: CassetteFile = serde_json::from_str(&text)
+            .map_err(|e| cassette_error(&path, format!("invalid cassette: {}", e)))?;
+        Ok(Self::new(path, Mode::Replay, file.interactions))
+    }
+
+    /// The cassette requested by `CB_RECORD` or `CB_REPLAY`, if either is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let record = env::var_os(RECORD_ENV).filter(|v| !v.is_empty());
+        let replay = env::var_os(REPLAY_ENV).filter(|v| !v.is_empty());
+        match (record, replay) {
+            (Some(_), Some(_)) => Err(CodebaseError::Cassette(format!(
+                "{} and {} can't both be set",
+                RECORD_ENV, REPLAY_ENV
+            ))),
+            (Some(path), None) => Self::record(path).map(Some),
+            (None, Some(path)) => Self::replay(path).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    /// Whether requests are answered from the cassette instead of sent.
+    pub fn is_replay(&self) -> bool {
+        self.shared.mode == Mode::Replay
+    }
+
+    /// The interactions recorded, or loaded for replay, so far.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.shared.lock().interactions.clone()
+    }
+
+    /// Write the interactions recorded so far to the file, if any were
+    /// added since it was last written.
+    pub fn flush(&self) -> Result<()> {
+        self.shared.flush()
+    }
+
+    /// Answer `method path` with the first recorded response to it that
+    /// hasn't been used yet. Request bodies aren't compared.
+    pub(crate) fn respond(&self, method: &Method, path: &str) -> Result<TransportResponse> {
+        let mut state = self.shared.lock();
+        let State {
+            interactions, used, ..
+        } = &mut *state;
+        let found = interactions
+            .iter()
+            .zip(used.iter_mut())
+            .find(|(i, used)| !**used && i.method == method.as_str() && i.path == path);
+        let Some((interaction, used)) = found else {
+            return Err(cassette_error(
+                &self.shared.path,
+                format!("no recorded response left for {} {}", method, path),
+            ));
+        };
+        *used = true;
+        to_response(interaction)
+    }
+
+    /// Add the exchange of `method path`, or the error it failed with, to
+    /// the cassette. `secrets` are replaced by [`REDACTED`] wherever they
+    /// appear.
+    pub(crate) fn capture(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Option<&str>,
+        result: &std::result::Result<TransportResponse, TransportError>,
+        secrets: &[&str],
+    ) {
+        let scrub = |s: &str| {
+            secrets
+                .iter()
+                .filter(|secret| !secret.is_empty())
+                .fold(redact_xml(s), |s, secret| s.replace(secret, REDACTED))
+        };
+        let mut interaction = Interaction {
+            method: method.to_string(),
+            path: scrub(path),
+            request_body: body.map(scrub),
+            status: 0,
+            retry_after: None,
+            response_body: String::new(),
+            error: None,
+        };
+        match result {
+            Ok(resp) => {
+                interaction.status = resp.status.as_u16();
+                interaction.retry_after = resp
+                    .headers
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                interaction.response_body = scrub(&resp.body);
+            }
+            Err(e) => {
+                interaction.error = Some(RecordedError {
+                    kind: e.kind(),
+                    message: scrub(&e.to_string()),
+                });
+            }
+        }
+
+        let mut state = self.shared.lock();
+        state.interactions.push(interaction);
+        state.unsaved = true;
+    }
+}
+
+impl Shared {
+    fn flush(&self) -> Result<()> {
+        let mut state = self.lock();
+        if !state.unsaved {
+            return Ok(());
+        }
+        self.save(&state.interactions)?;
+        state.unsaved = false;
+        Ok(())
+    }
+
+    fn save(&self, interactions: &[Interaction]) -> Result<()> {
+        let file = CassetteFile {
+            interactions: interactions.to_vec(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| cassette_error(&self.path, e.to_string()))?;
+        fs::write(&self.path, json + "\n")
+            .map_err(|e| cassette_error(&self.path, format!("cannot write: {}", e)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("{}", e);
+        }
+    }
+}
+
+fn to_response(interaction: &Interaction) -> Result<TransportResponse> {
+    if let Some(error) = &interaction.error {
+        let error = TransportError::new(error.kind, error.message.clone());
+        return Err(CodebaseError::Transport(error));
+    }
+    let status = StatusCode::from_u16(interaction.status)
+        .map_err(|_| CodebaseError::Cassette(format!("invalid status {}", interaction.status)))?;
+    let mut headers = HeaderMap::new();
+    if let Some(retry_after) = &interaction.retry_after {
+        let value = HeaderValue::from_str(retry_after)
+            .map_err(|e| CodebaseError::Cassette(e.to_string()))?;
+        headers.insert(RETRY_AFTER, value);
+    }
+    Ok(TransportResponse {
+        status,
+        headers,
+        body: interaction.response_body.clone(),
+    })
+}
+
+fn is_zero(status: &u16) -> bool {
+    *status == 0
+}
+
+fn cassette_error(path: &Path, message: String) -> CodebaseError {
+    CodebaseError::Cassette(format!("{}: {}", path.display(), message))
+}
@@ -1,10 +1,18 @@
-use anyhow::{Context, Result};
-use reqwest::{Client, Method, Response, StatusCode};
-use std::time::Duration;
+use reqwest::{Certificate, Client, Method, Proxy};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use tracing::{debug, info, warn};
 
-const MAX_RETRIES: u32 = 5;
-const INITIAL_BACKOFF_MS: u64 = 1000;
+use super::cache::ResponseCache;
+use super::cassette::Cassette;
+use super::error::{CodebaseError, Result};
+use super::pagination::Paginator;
+use super::raw::RawCapture;
+use super::redact;
+use super::retry::{RetryPolicy, retry_after};
+use super::throttle::RateLimiter;
+use super::transport::{ReqwestTransport, Transport, TransportRequest, TransportResponse};
 
 #[derive(Debug, Clone)]
 pub struct CodebaseClient {
@@ -12,18 +20,34 @@ pub struct CodebaseClient {
     pub account: String,
     pub username: String,
     pub api_key: String,
-    pub http: Client,
+    /// Sends each attempt of a request; HTTP with `reqwest` by default.
+    pub transport: Arc<dyn Transport>,
+    /// Cache for lookup endpoints read through [`CodebaseClient::get_cached`].
+    pub cache: Option<ResponseCache>,
+    /// Where response bodies are recorded for `--raw-fields`.
+    pub raw: Option<RawCapture>,
+    /// When failed requests are repeated.
+    pub retry: RetryPolicy,
+    /// Paces requests; shared with every clone of this client.
+    pub limiter: RateLimiter,
+    /// Records every exchange to, or answers every request from, a file.
+    pub cassette: Option<Cassette>,
 }
 
+/// The CodebaseHQ API endpoint.
+pub const DEFAULT_BASE_URL: &str = "https://api3.codebasehq.com";
+
+/// How long to wait for a connection to be established.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for the server to send more of a response.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
 impl CodebaseClient {
     pub fn new(account: String, username: String, api_key: String) -> Self {
-        Self {
-            base_url: "https://api3.codebasehq.com".to_string(),
-            account,
-            username,
-            api_key,
-            http: Client::new(),
-        }
+        Self::builder(account, username, api_key)
+            .build()
+            .expect("default HTTP client settings are valid")
     }
 
     /// Create a client with a custom base URL (for testing with mock servers).
@@ -33,12 +57,83 @@ impl CodebaseClient {
         username: String,
         api_key: String,
     ) -> Self {
-        Self {
-            base_url,
+        Self::builder(account, username, api_key)
+            .base_url(base_url)
+            .build()
+            .expect("default HTTP client settings are valid")
+    }
+
+    /// Start building a client with a different base URL, custom HTTP
+    /// settings (timeouts, a proxy, extra root certificates), a cache, a
+    /// retry policy or rate limit other than the defaults, or a transport
+    /// other than HTTP.
+    pub fn builder(account: String, username: String, api_key: String) -> CodebaseClientBuilder {
+        CodebaseClientBuilder {
+            base_url: DEFAULT_BASE_URL.to_string(),
             account,
             username,
             api_key,
-            http: Client::new(),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            read_timeout: Some(DEFAULT_READ_TIMEOUT),
+            timeout: None,
+            proxy: None,
+            root_certificates: Vec::new(),
+            cache: None,
+            raw: None,
+            retry: RetryPolicy::default(),
+            limiter: RateLimiter::default(),
+            cassette: None,
+            transport: None,
+        }
+    }
+
+    /// Serve slow-changing lookup endpoints from `cache` while fresh.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Retry failed requests according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Pace requests with `limiter` instead of the default rate limit.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Record requests to, or replay them from, `cassette`.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    fn replaying(&self) -> bool {
+        self.cassette.as_ref().is_some_and(Cassette::is_replay)
+    }
+
+    /// Record every successful response body in `capture`.
+    pub fn with_raw_capture(mut self, capture: RawCapture) -> Self {
+        self.raw = Some(capture);
+        self
+    }
+
+    /// A copy of this client that doesn't record responses, for requests
+    /// that only support a command (such as name lookups) and so should not
+    /// appear in its `--raw-fields` output.
+    pub fn without_raw_capture(&self) -> Self {
+        Self {
+            raw: None,
+            ..self.clone()
+        }
+    }
+
+    fn record(&self, body: &str) {
+        if let Some(raw) = &self.raw {
+            raw.record(body);
         }
     }
 
@@ -46,98 +141,310 @@ impl CodebaseClient {
         format!("{}{}", self.base_url, path)
     }
 
+    /// Page through a list endpoint, parsing each page with `parse`.
+    pub fn paginate<T>(&self, path: &str, parse: fn(&str) -> Result<Vec<T>>) -> Paginator<T> {
+        Paginator::new(self, path.to_string(), parse)
+    }
+
+    /// Send one attempt of a request, or answer it from a replayed
+    /// cassette. Every attempt is traced: the method, URL, status and
+    /// latency at `INFO`, and the redacted request and response bodies at
+    /// `DEBUG`. Query values are left out of logged URLs.
     async fn send_request(
         &self,
         method: &Method,
-        url: &str,
+        path: &str,
         body: Option<&str>,
-    ) -> Result<Response> {
-        let mut req = self
-            .http
-            .request(method.clone(), url)
-            .basic_auth(&self.username, Some(&self.api_key))
-            .header("Accept", "application/xml")
-            .header("Content-Type", "application/xml");
-
+        attempt: u32,
+    ) -> Result<TransportResponse> {
+        let url = self.url(path);
+        let logged_url = redact::url(&url);
         if let Some(body) = body {
-            req = req.body(body.to_string());
+            debug!(%method, url = logged_url, attempt, body = %redact::for_log(body), "request body");
         }
-
-        req.send().await.context("Failed to send request")
+        let started = Instant::now();
+        let result = match &self.cassette {
+            Some(cassette) if cassette.is_replay() => cassette.respond(method, path),
+            cassette => {
+                let request = TransportRequest {
+                    method: method.clone(),
+                    url,
+                    path: path.to_string(),
+                    username: self.username.clone(),
+                    api_key: self.api_key.clone(),
+                    body: body.map(str::to_string),
+                };
+                self.limiter.acquire().await;
+                let result = self.transport.send(request).await;
+                if let Some(cassette) = cassette {
+                    // Transport errors can quote the URL.
+                    let secrets = [self.api_key.as_str(), self.base_url.as_str()];
+                    cassette.capture(method, path, body, &result, &secrets);
+                }
+                Ok(result?)
+            }
+        };
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let url = logged_url;
+        match &result {
+            Ok(resp) => {
+                let status = resp.status.as_u16();
+                info!(%method, url, attempt, status, elapsed_ms, "response");
+                debug!(%method, url, attempt, status, body = %redact::for_log(&resp.body), "response body");
+            }
+            Err(e) => info!(%method, url, attempt, elapsed_ms, error = %e, "request failed"),
+        }
+        result
     }
 
+    /// Send a request, retrying as the client's [`RetryPolicy`] allows. Each
+    /// retry is announced at `WARN`. The response of the last attempt is
+    /// returned whatever its status.
     pub async fn request(
         &self,
         method: Method,
         path: &str,
         body: Option<String>,
-    ) -> Result<Response> {
-        let url = self.url(path);
+    ) -> Result<TransportResponse> {
         let body_ref = body.as_deref();
+        let policy = &self.retry;
 
-        for attempt in 0..MAX_RETRIES {
-            let resp = self.send_request(&method, &url, body_ref).await?;
-
-            if resp.status() == StatusCode::from_u16(529).unwrap_or(StatusCode::SERVICE_UNAVAILABLE)
-                || resp.status() == StatusCode::TOO_MANY_REQUESTS
-                || resp.status() == StatusCode::SERVICE_UNAVAILABLE
-            {
-                let backoff = Duration::from_millis(INITIAL_BACKOFF_MS * 2u64.pow(attempt));
-                eprintln!(
-                    "Server returned {} — retrying in {}s (attempt {}/{})",
-                    resp.status(),
-                    backoff.as_secs(),
-                    attempt + 1,
-                    MAX_RETRIES
-                );
-                sleep(backoff).await;
-                continue;
+        let max_attempts = policy.max_attempts;
+        let url = redact::url(&self.url(path));
+        let mut retry = 0;
+        loop {
+            let attempt = retry + 1;
+            let last = attempt >= max_attempts;
+            let result = self.send_request(&method, path, body_ref, attempt).await;
+            let delay = match &result {
+                Ok(resp) if !last && policy.retries_status(&method, resp.status) => {
+                    let Some(delay) = policy.delay_after(retry, resp) else {
+                        return result;
+                    };
+                    let status = resp.status.as_u16();
+                    warn!(%method, url, attempt, max_attempts, delay = %seconds(delay), status, "retrying");
+                    delay
+                }
+                Err(CodebaseError::Transport(e)) if !last && policy.retries_error(&method, e) => {
+                    let delay = policy.backoff(retry);
+                    warn!(%method, url, attempt, max_attempts, delay = %seconds(delay), error = %e, "retrying");
+                    delay
+                }
+                _ => return result,
+            };
+            // A replayed session already knows the outcome; don't wait for it.
+            if !self.replaying() {
+                sleep(delay).await;
             }
-
-            return Ok(resp);
+            retry += 1;
         }
-
-        // Final attempt with no retry
-        self.send_request(&method, &url, body_ref).await
     }
 
-    pub async fn get(&self, path: &str) -> Result<String> {
-        let resp = self.request(Method::GET, path, None).await?;
-        let status = resp.status();
-        let text = resp.text().await.context("Failed to read response body")?;
+    /// Send a request and return the body, converting non-success statuses
+    /// into the matching `CodebaseError`.
+    pub async fn request_text(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<String> {
+        let resp = self.request(method, path, body).await?;
+        let status = resp.status;
+        let retry_after = retry_after(&resp.headers);
+        let text = resp.body;
         if !status.is_success() {
-            anyhow::bail!("API error ({}): {}", status, text);
+            return Err(CodebaseError::from_response(status, text, retry_after));
         }
+        self.record(&text);
         Ok(text)
     }
 
-    pub async fn post(&self, path: &str, body: String) -> Result<String> {
-        let resp = self.request(Method::POST, path, Some(body)).await?;
-        let status = resp.status();
-        let text = resp.text().await.context("Failed to read response body")?;
-        if !status.is_success() {
-            anyhow::bail!("API error ({}): {}", status, text);
+    pub async fn get(&self, path: &str) -> Result<String> {
+        self.request_text(Method::GET, path, None).await
+    }
+
+    /// GET `path`, answering from the response cache when it holds an entry
+    /// younger than `ttl`. Without a cache this is the same as [`get`](Self::get).
+    pub async fn get_cached(&self, path: &str, ttl: Duration) -> Result<String> {
+        let Some(cache) = &self.cache else {
+            return self.get(path).await;
+        };
+        if let Some(body) = cache.get(path, ttl) {
+            self.record(&body);
+            return Ok(body);
         }
-        Ok(text)
+        let body = self.get(path).await?;
+        cache.put(path, &body);
+        Ok(body)
     }
 
-    pub async fn put(&self, path: &str, body: String) -> Result<String> {
-        let resp = self.request(Method::PUT, path, Some(body)).await?;
-        let status = resp.status();
-        let text = resp.text().await.context("Failed to read response body")?;
-        if !status.is_success() {
-            anyhow::bail!("API error ({}): {}", status, text);
+    /// Forget any cached response for `path`.
+    pub fn invalidate(&self, path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(path);
         }
-        Ok(text)
+    }
+
+    pub async fn post(&self, path: &str, body: String) -> Result<String> {
+        self.request_text(Method::POST, path, Some(body)).await
+    }
+
+    pub async fn put(&self, path: &str, body: String) -> Result<String> {
+        self.request_text(Method::PUT, path, Some(body)).await
     }
 
     pub async fn delete(&self, path: &str) -> Result<String> {
-        let resp = self.request(Method::DELETE, path, None).await?;
-        let status = resp.status();
-        let text = resp.text().await.context("Failed to read response body")?;
-        if !status.is_success() {
-            anyhow::bail!("API error ({}): {}", status, text);
+        self.request_text(Method::DELETE, path, None).await
+    }
+}
+
+/// Builder for a [`CodebaseClient`] with non-default HTTP settings.
+///
+/// Without [`proxy`](Self::proxy), the `HTTPS_PROXY`, `HTTP_PROXY`,
+/// `ALL_PROXY` and `NO_PROXY` environment variables are honored.
+#[derive(Debug)]
+pub struct CodebaseClientBuilder {
+    base_url: String,
+    account: String,
+    username: String,
+    api_key: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    root_certificates: Vec<Certificate>,
+    cache: Option<ResponseCache>,
+    raw: Option<RawCapture>,
+    retry: RetryPolicy,
+    limiter: RateLimiter,
+    cassette: Option<Cassette>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl CodebaseClientBuilder {
+    /// Send requests to `base_url` instead of [`DEFAULT_BASE_URL`]. A
+    /// trailing `/` is ignored.
+    pub fn base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// See [`CodebaseClient::with_cache`].
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// See [`CodebaseClient::with_raw_capture`].
+    pub fn raw_capture(mut self, capture: RawCapture) -> Self {
+        self.raw = Some(capture);
+        self
+    }
+
+    /// See [`CodebaseClient::with_retry_policy`].
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// See [`CodebaseClient::with_rate_limiter`].
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// See [`CodebaseClient::with_cassette`].
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Send requests with `transport` instead of over HTTP. The timeout,
+    /// proxy and certificate settings only apply to the default transport.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Give up on connecting after `timeout`; `None` waits indefinitely.
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Give up when the server sends nothing for `timeout`; `None` waits
+    /// indefinitely.
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Give up on a request that hasn't completed after `timeout`, however
+    /// steadily the response is arriving. Unlimited by default.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send every request through `proxy` instead of the one from the
+    /// environment.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Trust `cert` in addition to the system's root certificates, e.g. a
+    /// corporate CA that re-signs TLS traffic.
+    pub fn add_root_certificate(mut self, cert: Certificate) -> Self {
+        self.root_certificates.push(cert);
+        self
+    }
+
+    pub fn build(self) -> Result<CodebaseClient> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(self.http_client()?)),
+        };
+        Ok(CodebaseClient {
+            base_url: self.base_url,
+            account: self.account,
+            username: self.username,
+            api_key: self.api_key,
+            transport,
+            cache: self.cache,
+            raw: self.raw,
+            retry: self.retry,
+            limiter: self.limiter,
+            cassette: self.cassette,
+        })
+    }
+
+    fn http_client(&self) -> Result<Client> {
+        let mut http = Client::builder();
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
         }
-        Ok(text)
+        if let Some(timeout) = self.read_timeout {
+            http = http.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            http = http.proxy(proxy.clone());
+        }
+        for cert in &self.root_certificates {
+            http = http.add_root_certificate(cert.clone());
+        }
+        Ok(http.build()?)
+    }
+}
+
+/// `delay` for messages: whole seconds as `2s`, otherwise `0.5s`.
+fn seconds(delay: Duration) -> String {
+    if delay.subsec_millis() == 0 {
+        format!("{}s", delay.as_secs())
+    } else {
+        format!("{:.1}s", delay.as_secs_f64())
     }
 }
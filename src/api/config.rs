@@ -1,12 +1,294 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::{DocumentMut, Item, Table};
 
+use super::client::DEFAULT_BASE_URL;
+use super::http::HttpSettings;
+use super::retry::RetrySettings;
+use super::throttle::RateLimitSettings;
+use crate::git_context;
+
+/// Name used for the credentials stored at the top level of the config file.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Environment variable selecting a profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "CB_PROFILE";
+
+/// Environment variable overriding the API username of the selected profile.
+pub const USERNAME_ENV: &str = "CB_API_USERNAME";
+
+/// Environment variable overriding the API key of the selected profile.
+pub const API_KEY_ENV: &str = "CB_API_KEY";
+
+/// Environment variable overriding the API base URL of the selected profile.
+pub const API_URL_ENV: &str = "CB_API_URL";
+
+/// API username used to replay a cassette when none is configured.
+const REPLAY_USERNAME: &str = "replay/replay";
+
+/// Resolved credentials for a single CodebaseHQ account.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub api_username: String,
     pub api_key: String,
+    /// API endpoint, when not the CodebaseHQ one (see [`Config::base_url`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+/// Credentials stored under `[profiles.<name>]` in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub api_username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Shell command whose stdout is the API key (e.g. `pass show codebase`).
+    /// Takes precedence over `api_key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_command: Option<String>,
+    /// API endpoint for this profile, e.g. a staging server or local mock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+impl Profile {
+    /// The API key, running `credential_command` if one is configured.
+    pub fn resolve_api_key(&self) -> Result<String> {
+        if let Some(cmd) = &self.credential_command {
+            return run_credential_command(cmd);
+        }
+        self.api_key.clone().with_context(|| {
+            format!(
+                "No api_key or credential_command configured for {}",
+                self.api_username
+            )
+        })
+    }
+}
+
+/// Run `cmd` through the platform shell and return its trimmed stdout.
+fn run_credential_command(cmd: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", cmd]).output()
+    } else {
+        Command::new("sh").args(["-c", cmd]).output()
+    }
+    .with_context(|| format!("Failed to run credential_command `{}`", cmd))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "credential_command `{}` exited with {}: {}",
+            cmd,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let key = String::from_utf8(output.stdout)
+        .context("credential_command produced non-UTF-8 output")?
+        .trim()
+        .to_string();
+    if key.is_empty() {
+        anyhow::bail!("credential_command `{}` printed an empty API key", cmd);
+    }
+    Ok(key)
+}
+
+/// The on-disk contents of `~/.config/cb/config.toml`.
+///
+/// Top-level `api_username`/`api_key` (the original single-account layout)
+/// are treated as the `default` profile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Profile used when none is requested and the git remote doesn't pick one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// `[http]`: timeouts, proxy and CA certificates, for every profile.
+    #[serde(default, skip_serializing_if = "HttpSettings::is_empty")]
+    pub http: HttpSettings,
+    /// `[retry]`: how failed requests are retried, for every profile.
+    #[serde(default, skip_serializing_if = "RetrySettings::is_empty")]
+    pub retry: RetrySettings,
+    /// `[rate_limit]`: how fast requests are sent, for every profile.
+    #[serde(default, skip_serializing_if = "RateLimitSettings::is_empty")]
+    pub rate_limit: RateLimitSettings,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl ConfigFile {
+    /// Read the config file, returning an empty config if it does not exist.
+    pub fn load() -> Result<Self> {
+        let path = Config::config_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content).context("Failed to parse config file")
+    }
+
+    /// Write the config file, readable and writable only by the current user.
+    /// Comments and formatting of settings that already exist are kept, as
+    /// are keys this version doesn't know about.
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::config_dir()?;
+        fs::create_dir_all(&dir).context("Failed to create config directory")?;
+        let path = Config::config_path()?;
+        let new: DocumentMut = toml::to_string_pretty(self)
+            .context("Failed to serialize config")?
+            .parse()
+            .context("Failed to serialize config")?;
+        let existing = fs::read_to_string(&path).unwrap_or_default();
+        let mut doc: DocumentMut = existing.parse().unwrap_or_default();
+        // The settings the file held before, as this struct reads them; keys
+        // outside these are not ours to remove.
+        let owned: DocumentMut = toml::from_str::<Self>(&existing)
+            .ok()
+            .and_then(|old| toml::to_string(&old).ok())
+            .and_then(|old| old.parse().ok())
+            .unwrap_or_default();
+        merge_table(doc.as_table_mut(), new.as_table(), owned.as_table());
+        write_private(&path, &doc.to_string()).context("Failed to write config file")?;
+        Ok(())
+    }
+
+    /// Look up a profile by name; `default` also matches the top-level credentials.
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        if let Some(p) = self.profiles.get(name) {
+            return Some(p.clone());
+        }
+        if name == DEFAULT_PROFILE {
+            return self.legacy_profile();
+        }
+        None
+    }
+
+    fn legacy_profile(&self) -> Option<Profile> {
+        if self.api_key.is_none() && self.credential_command.is_none() {
+            return None;
+        }
+        Some(Profile {
+            api_username: self.api_username.clone()?,
+            api_key: self.api_key.clone(),
+            credential_command: self.credential_command.clone(),
+            base_url: self.base_url.clone(),
+        })
+    }
+
+    /// Names of every configured profile, including the top-level `default`.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        if self.legacy_profile().is_some() && !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.insert(0, DEFAULT_PROFILE.to_string());
+        }
+        names
+    }
+
+    /// Store credentials under `name` (the top level for `default`).
+    pub fn set_profile(&mut self, name: &str, profile: Profile) {
+        if name == DEFAULT_PROFILE && !self.profiles.contains_key(DEFAULT_PROFILE) {
+            self.api_username = Some(profile.api_username);
+            self.api_key = profile.api_key;
+            self.credential_command = profile.credential_command;
+            self.base_url = profile.base_url;
+        } else {
+            self.profiles.insert(name.to_string(), profile);
+        }
+    }
+
+    /// Pick the profile to use.
+    ///
+    /// Precedence: the explicit `requested` name, then `CB_PROFILE`, then a
+    /// profile whose account matches the git remote's account, then
+    /// `default_profile`, then the top-level credentials, then the only
+    /// profile if exactly one exists.
+    pub fn select_profile(&self, requested: Option<&str>) -> Result<(String, Profile)> {
+        let requested = requested
+            .map(str::to_string)
+            .or_else(|| env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty()));
+        if let Some(name) = requested {
+            let profile = self.profile(&name).with_context(|| {
+                format!(
+                    "No profile named '{}' in {}. Available profiles: {}",
+                    name,
+                    Config::config_path()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default(),
+                    self.describe_profiles()
+                )
+            })?;
+            return Ok((name, profile));
+        }
+
+        let remote_account = git_context::detect().and_then(|ctx| ctx.account);
+        if let Some(found) = remote_account.and_then(|a| self.profile_for_account(&a)) {
+            return Ok(found);
+        }
+
+        if let Some(name) = &self.default_profile {
+            if let Some(profile) = self.profile(name) {
+                return Ok((name.clone(), profile));
+            }
+            anyhow::bail!("default_profile '{}' does not exist in config", name);
+        }
+
+        if let Some(profile) = self.legacy_profile() {
+            return Ok((DEFAULT_PROFILE.to_string(), profile));
+        }
+
+        if self.profiles.len() == 1 {
+            let (name, profile) = self.profiles.iter().next().expect("one profile");
+            return Ok((name.clone(), profile.clone()));
+        }
+
+        if self.profiles.is_empty() {
+            anyhow::bail!(
+                "No credentials found in {}. Run `cb login` first.",
+                Config::config_path()?.display()
+            );
+        }
+        anyhow::bail!(
+            "Multiple profiles configured ({}) and none selected. \
+             Pass --profile, set {}, or set default_profile in the config file.",
+            self.describe_profiles(),
+            PROFILE_ENV
+        )
+    }
+
+    /// Find the profile whose API username belongs to `account`.
+    pub fn profile_for_account(&self, account: &str) -> Option<(String, Profile)> {
+        self.profile_names().into_iter().find_map(|name| {
+            let profile = self.profile(&name)?;
+            let matches = profile
+                .api_username
+                .split('/')
+                .next()
+                .is_some_and(|a| a.eq_ignore_ascii_case(account));
+            matches.then_some((name, profile))
+        })
+    }
+
+    fn describe_profiles(&self) -> String {
+        let names = self.profile_names();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
 }
 
 impl Config {
@@ -19,24 +301,115 @@ impl Config {
         Ok(Self::config_dir()?.join("config.toml"))
     }
 
+    /// Root of the on-disk response cache (see [`crate::api::cache`]).
+    pub fn cache_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("cache"))
+    }
+
+    /// Save these credentials as the top-level (`default`) profile.
     pub fn save(&self) -> Result<()> {
-        let dir = Self::config_dir()?;
-        fs::create_dir_all(&dir).context("Failed to create config directory")?;
-        let toml = toml::to_string_pretty(self).context("Failed to serialize config")?;
-        fs::write(Self::config_path()?, toml).context("Failed to write config file")?;
-        Ok(())
+        self.save_profile(DEFAULT_PROFILE)
+    }
+
+    /// Save these credentials under the named profile, keeping any others.
+    /// An existing profile's `base_url` is kept unless this config sets one.
+    pub fn save_profile(&self, name: &str) -> Result<()> {
+        let mut file = ConfigFile::load()?;
+        let base_url = self
+            .base_url
+            .clone()
+            .or_else(|| file.profile(name).and_then(|p| p.base_url));
+        file.set_profile(
+            name,
+            Profile {
+                api_username: self.api_username.clone(),
+                api_key: Some(self.api_key.clone()),
+                credential_command: None,
+                base_url,
+            },
+        );
+        file.save()
     }
 
+    /// Load credentials for the automatically selected profile.
     pub fn load() -> Result<Self> {
+        Self::load_profile(None)
+    }
+
+    /// Load credentials for `profile`, or the automatically selected one
+    /// (see [`ConfigFile::select_profile`]).
+    ///
+    /// `CB_API_USERNAME`, `CB_API_KEY` and `CB_API_URL` override the file;
+    /// when the username and key are both set the config file is not
+    /// required at all. An explicitly requested `profile` always uses its
+    /// own credentials, so only `CB_API_URL` applies to it.
+    pub fn load_profile(profile: Option<&str>) -> Result<Self> {
+        let (env_username, env_key) = match profile {
+            Some(_) => (None, None),
+            None => (non_empty_env(USERNAME_ENV), non_empty_env(API_KEY_ENV)),
+        };
+        let env_url = non_empty_env(API_URL_ENV);
+        if let (Some(api_username), Some(api_key)) = (&env_username, &env_key) {
+            return Ok(Config {
+                api_username: api_username.clone(),
+                api_key: api_key.clone(),
+                base_url: env_url,
+            });
+        }
+
         let path = Self::config_path()?;
-        let content = fs::read_to_string(&path).with_context(|| {
-            format!(
-                "No config found at {}. Run `cb login` first.",
-                path.display()
-            )
-        })?;
-        let config: Config = toml::from_str(&content).context("Failed to parse config file")?;
-        Ok(config)
+        if !path.exists() {
+            anyhow::bail!(
+                "No config found at {}. Run `cb login` first, or set {} and {}.",
+                path.display(),
+                USERNAME_ENV,
+                API_KEY_ENV
+            );
+        }
+        let (_, profile) = ConfigFile::load()?.select_profile(profile)?;
+        let api_key = match env_key {
+            Some(key) => key,
+            None => profile.resolve_api_key()?,
+        };
+        Ok(Config {
+            api_username: env_username.unwrap_or(profile.api_username),
+            api_key,
+            base_url: env_url.or(profile.base_url),
+        })
+    }
+
+    /// The credentials to replay a cassette with. Nothing is sent, so
+    /// unlike [`load_profile`](Self::load_profile) a missing username is
+    /// filled in and no API key or `credential_command` is needed. An
+    /// unreadable config file or an unknown profile is still an error.
+    pub fn load_for_replay(profile: Option<&str>) -> Result<Self> {
+        let env_username = match profile {
+            Some(_) => None,
+            None => non_empty_env(USERNAME_ENV),
+        };
+        let file = ConfigFile::load()?;
+        let requested = profile.is_some() || non_empty_env(PROFILE_ENV).is_some();
+        let selected = if requested {
+            Some(file.select_profile(profile)?.1)
+        } else {
+            file.select_profile(None).ok().map(|(_, profile)| profile)
+        };
+        let (username, base_url) = match selected {
+            Some(profile) => (Some(profile.api_username), profile.base_url),
+            None => (None, None),
+        };
+        Ok(Config {
+            api_username: env_username
+                .or(username)
+                .unwrap_or_else(|| REPLAY_USERNAME.to_string()),
+            api_key: String::new(),
+            base_url: non_empty_env(API_URL_ENV).or(base_url),
+        })
+    }
+
+    /// The API endpoint: the configured one, or CodebaseHQ's.
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
     }
 
     /// Parse the account name from the API username (e.g. "sectormobile/samkrishna" -> "sectormobile")
@@ -55,3 +428,54 @@ impl Config {
             .unwrap_or(&self.api_username)
     }
 }
+
+/// Make `old` hold the same settings as `new`, keeping the comments and
+/// layout of the keys and tables both have. Keys missing from `new` are
+/// removed only if they are in `owned`, the settings `old` was read as.
+fn merge_table(old: &mut Table, new: &Table, owned: &Table) {
+    old.retain(|key, _| new.contains_key(key) || !owned.contains_key(key));
+    for (key, item) in new.iter() {
+        match (old.get_mut(key), item) {
+            (Some(Item::Table(old)), Item::Table(new)) => {
+                let empty = Table::new();
+                let owned = owned.get(key).and_then(Item::as_table).unwrap_or(&empty);
+                merge_table(old, new, owned)
+            }
+            (Some(Item::Value(old)), Item::Value(new)) => {
+                let decor = old.decor().clone();
+                *old = new.clone();
+                *old.decor_mut() = decor;
+            }
+            _ => {
+                old.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// Write `contents` to `path` with 0600 permissions on Unix, tightening the
+/// mode of an existing file as well.
+pub(crate) fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents.as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        fs::write(path, contents)
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::lookup::ResolveError;
+use super::transport::TransportError;
+
+pub type Result<T, E = CodebaseError> = std::result::Result<T, E>;
+
+/// Errors returned by `CodebaseClient`, the `api::*` functions and the
+/// `commands::*::run` functions. New variants may be added, so matches need
+/// a wildcard arm.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CodebaseError {
+    /// 401: the API username or key was rejected.
+    #[error("API error ({status}): {body}")]
+    Unauthorized { status: StatusCode, body: String },
+    /// 404: the project, repository, ticket, etc. does not exist.
+    #[error("API error ({status}): {body}")]
+    NotFound { status: StatusCode, body: String },
+    /// 422: the request was rejected; `errors` holds the messages from the
+    /// `<errors>` body (e.g. "Summary can't be blank").
+    #[error("API error ({status}): {}", errors.join("; "))]
+    Validation {
+        status: StatusCode,
+        errors: Vec<String>,
+    },
+    /// 429 (or 503/529) after all retries were exhausted.
+    #[error("API error ({status}): rate limited{}", retry_hint(*retry_after))]
+    RateLimited {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    /// Any other non-success status.
+    #[error("API error ({status}): {body}")]
+    Api { status: StatusCode, body: String },
+    /// The request could not be sent or the response could not be read.
+    #[error("Request failed: {0}")]
+    Transport(#[from] TransportError),
+    /// A request body could not be serialized to XML.
+    #[error("Failed to build request body: {0}")]
+    Encode(#[from] quick_xml::SeError),
+    /// The response body was not the XML we expected.
+    #[error("Failed to parse response: {0}")]
+    Decode(#[from] quick_xml::DeError),
+    /// A `CB_RECORD`/`CB_REPLAY` cassette could not be read or written, or
+    /// holds no response for a request.
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+    /// A status, priority, user, etc. given by name didn't match exactly one.
+    #[error(transparent)]
+    Resolve(#[from] ResolveError),
+    /// A command's input was missing or unusable, e.g. an empty ticket
+    /// summary, an aborted editor session or an unreadable file.
+    #[error("{0}")]
+    Input(String),
+    /// The response cache could not be located or cleared.
+    #[error("Cache error: {0}")]
+    Cache(String),
+}
+
+#[derive(Deserialize)]
+struct ErrorList {
+    #[serde(rename = "error", default)]
+    errors: Vec<String>,
+}
+
+fn retry_hint(retry_after: Option<Duration>) -> String {
+    match retry_after {
+        Some(d) => format!(" (retry after {}s)", d.as_secs()),
+        None => String::new(),
+    }
+}
+
+impl CodebaseError {
+    /// Classify a non-success response by its status code.
+    pub fn from_response(status: StatusCode, body: String, retry_after: Option<Duration>) -> Self {
+        match status.as_u16() {
+            401 => CodebaseError::Unauthorized { status, body },
+            404 => CodebaseError::NotFound { status, body },
+            422 => CodebaseError::Validation {
+                status,
+                errors: parse_validation_errors(&body),
+            },
+            429 | 503 | 529 => CodebaseError::RateLimited {
+                status,
+                retry_after,
+            },
+            _ => CodebaseError::Api { status, body },
+        }
+    }
+
+    /// HTTP status of the failed response, if the server answered at all.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            CodebaseError::Unauthorized { status, .. }
+            | CodebaseError::NotFound { status, .. }
+            | CodebaseError::Validation { status, .. }
+            | CodebaseError::RateLimited { status, .. }
+            | CodebaseError::Api { status, .. } => Some(*status),
+            CodebaseError::Transport(e) => e.status(),
+            CodebaseError::Encode(_)
+            | CodebaseError::Decode(_)
+            | CodebaseError::Cassette(_)
+            | CodebaseError::Resolve(_)
+            | CodebaseError::Input(_)
+            | CodebaseError::Cache(_) => None,
+        }
+    }
+
+    /// Process exit code used by `cb` when a command fails with this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CodebaseError::Api { .. }
+            | CodebaseError::Encode(_)
+            | CodebaseError::Cassette(_)
+            | CodebaseError::Input(_)
+            | CodebaseError::Cache(_) => 1,
+            CodebaseError::Resolve(_) => 2,
+            CodebaseError::Unauthorized { .. } => 3,
+            CodebaseError::NotFound { .. } => 4,
+            CodebaseError::Validation { .. } => 5,
+            CodebaseError::RateLimited { .. } => 6,
+            CodebaseError::Transport(_) => 7,
+            CodebaseError::Decode(_) => 8,
+        }
+    }
+
+    /// An [`Input`](Self::Input) error from a failure of the editor, a file
+    /// read, git remote detection and the like, keeping its causes.
+    pub fn input(err: impl Into<anyhow::Error>) -> Self {
+        CodebaseError::Input(format!("{:#}", err.into()))
+    }
+}
+
+impl From<reqwest::Error> for CodebaseError {
+    fn from(e: reqwest::Error) -> Self {
+        CodebaseError::Transport(e.into())
+    }
+}
+
+/// Extract the messages from a CodebaseHQ `<errors><error>...</error></errors>` body,
+/// falling back to the raw body when it isn't in that shape.
+fn parse_validation_errors(body: &str) -> Vec<String> {
+    match quick_xml::de::from_str::<ErrorList>(body) {
+        Ok(list) if !list.errors.is_empty() => list.errors,
+        _ if body.trim().is_empty() => Vec::new(),
+        _ => vec![body.trim().to_string()],
+    }
+}
+
+/// Deserialize an XML response body, mapping failures to `CodebaseError::Decode`.
+pub fn from_xml<T: serde::de::DeserializeOwned>(xml: &str) -> Result<T> {
+    Ok(quick_xml::de::from_str(xml)?)
+}
+
+/// Serialize a request struct to an XML body, escaping all text content.
+pub fn to_xml<T: serde::Serialize>(body: &T) -> Result<String> {
+    Ok(quick_xml::se::to_string(body)?)
+}
@@ -0,0 +1,125 @@
+//! An in-memory [`Transport`] for tests, enabled by the `test-util` feature.
+//!
+//! [`FakeTransport`] answers requests from a mock-server [`Store`] without
+//! opening a socket, so code built on `api::*` can be tested against
+//! realistic responses:
+//!
+//! ```ignore
+//! let fake = FakeTransport::new(Store::sample());
+//! let client = fake.client();
+//! let ticket = tickets::show_ticket(&client, "widgets", 1).await?;
+//! assert_eq!(fake.requests()[0].path, "/widgets/tickets/1");
+//! ```
+//!
+//! Failures are injected through the store, e.g.
+//! `fake.store().inject_fault(Fault::Timeout)` or
+//! `Fault::Reply(Reply::rate_limited(Some(30)))`.
+
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::future::BoxFuture;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+
+use super::client::CodebaseClient;
+use super::retry::RetryPolicy;
+use super::throttle::RateLimiter;
+use super::transport::{Transport, TransportError, TransportRequest, TransportResponse};
+use crate::mock_server::{Fault, Store};
+
+/// Answers requests from a [`Store`] and remembers them. Clones share the
+/// store and the request log.
+#[derive(Debug, Clone)]
+pub struct FakeTransport {
+    store: Arc<Mutex<Store>>,
+    requests: Arc<Mutex<Vec<TransportRequest>>>,
+}
+
+impl FakeTransport {
+    pub fn new(store: Store) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            requests: Arc::default(),
+        }
+    }
+
+    /// A client signed in as `acme/alice` that sends through this
+    /// transport, with no retries or rate limit.
+    pub fn client(&self) -> CodebaseClient {
+        CodebaseClient::builder(
+            "acme".to_string(),
+            "acme/alice".to_string(),
+            "fake-key".to_string(),
+        )
+        .transport(self.clone())
+        .retry_policy(RetryPolicy::none())
+        .rate_limiter(RateLimiter::unlimited())
+        .build()
+        .expect("fake transport needs no HTTP settings")
+    }
+
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The store, to seed data or inject faults before a test or inspect it
+    /// afterwards.
+    pub fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn answer(&self, request: &TransportRequest) -> Result<TransportResponse, TransportError> {
+        let mut store = self.store();
+        let reply = match store.take_fault() {
+            Some(Fault::Reply(reply)) => reply,
+            Some(Fault::Disconnect) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    "injected disconnect",
+                )
+                .into());
+            }
+            Some(Fault::Timeout) => {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "injected timeout").into());
+            }
+            None => store.respond(
+                &request.method,
+                &request.path,
+                request.body.as_deref().unwrap_or(""),
+                &request.username,
+            ),
+        };
+        let status = StatusCode::from_u16(reply.status).map_err(TransportError::other)?;
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+        for (name, value) in &reply.headers {
+            let name = HeaderName::try_from(name.as_str()).map_err(TransportError::other)?;
+            let value = HeaderValue::try_from(value.as_str()).map_err(TransportError::other)?;
+            headers.append(name, value);
+        }
+        Ok(TransportResponse {
+            status,
+            headers,
+            body: reply.body,
+        })
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, TransportError>> {
+        let result = self.answer(&request);
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request);
+        Box::pin(async move { result })
+    }
+}
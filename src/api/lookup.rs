@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::client::CodebaseClient;
+use super::error::Result;
+use super::models::*;
+use super::{projects, throttle, tickets};
+
+/// A project's ticket statuses, priorities, categories, types and users, used
+/// to turn the IDs returned by the API into names and back.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectLookup {
+    pub statuses: Vec<TicketStatus>,
+    pub priorities: Vec<TicketPriority>,
+    pub categories: Vec<TicketCategory>,
+    pub types: Vec<TicketType>,
+    pub users: Vec<ProjectUser>,
+}
+
+/// Lookup tables requested at the same time.
+const CONCURRENCY: usize = 3;
+
+/// The lookup tables, each from its own endpoint.
+#[derive(Clone, Copy)]
+enum Table {
+    Statuses,
+    Priorities,
+    Categories,
+    Types,
+    Users,
+}
+
+enum Fetched {
+    Statuses(Vec<TicketStatus>),
+    Priorities(Vec<TicketPriority>),
+    Categories(Vec<TicketCategory>),
+    Types(Vec<TicketType>),
+    Users(Vec<ProjectUser>),
+}
+
+impl Table {
+    async fn fetch(self, client: &CodebaseClient, project: &str) -> Result<Fetched> {
+        Ok(match self {
+            Table::Statuses => Fetched::Statuses(tickets::list_statuses(client, project).await?),
+            Table::Priorities => {
+                Fetched::Priorities(tickets::list_priorities(client, project).await?)
+            }
+            Table::Categories => {
+                Fetched::Categories(tickets::list_categories(client, project).await?)
+            }
+            Table::Types => Fetched::Types(tickets::list_types(client, project).await?),
+            Table::Users => Fetched::Users(projects::list_project_users(client, project).await?),
+        })
+    }
+}
+
+/// A name given on the command line that didn't identify exactly one entry.
+#[derive(Debug, thiserror::Error)]
+pub enum ResolveError {
+    #[error("No {kind} matches '{query}'. Available: {}", list_or_none(available))]
+    NotFound {
+        kind: &'static str,
+        query: String,
+        available: Vec<String>,
+    },
+    #[error("'{query}' matches more than one {kind}: {}. Be more specific.", matches.join(", "))]
+    Ambiguous {
+        kind: &'static str,
+        query: String,
+        matches: Vec<String>,
+    },
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+impl ProjectLookup {
+    /// Fetch all lookup tables for `project`, a few at a time. These
+    /// requests are never part of `--raw-fields` output.
+    pub async fn fetch(client: &CodebaseClient, project: &str) -> Result<Self> {
+        let client = &client.without_raw_capture();
+        let tables = [
+            Table::Statuses,
+            Table::Priorities,
+            Table::Categories,
+            Table::Types,
+            Table::Users,
+        ];
+        let fetched =
+            throttle::map_concurrent(tables, CONCURRENCY, |t| t.fetch(client, project)).await?;
+        let mut lookup = ProjectLookup::default();
+        for table in fetched {
+            match table {
+                Fetched::Statuses(v) => lookup.statuses = v,
+                Fetched::Priorities(v) => lookup.priorities = v,
+                Fetched::Categories(v) => lookup.categories = v,
+                Fetched::Types(v) => lookup.types = v,
+                Fetched::Users(v) => lookup.users = v,
+            }
+        }
+        Ok(lookup)
+    }
+
+    pub fn status_name(&self, id: i64) -> Option<&str> {
+        self.statuses
+            .iter()
+            .find(|s| s.id == Some(id))
+            .and_then(|s| s.name.as_deref())
+    }
+
+    pub fn priority_name(&self, id: i64) -> Option<&str> {
+        self.priorities
+            .iter()
+            .find(|p| p.id == Some(id))
+            .and_then(|p| p.name.as_deref())
+    }
+
+    pub fn category_name(&self, id: i64) -> Option<&str> {
+        self.categories
+            .iter()
+            .find(|c| c.id == Some(id))
+            .and_then(|c| c.name.as_deref())
+    }
+
+    /// "First Last", falling back to the username.
+    pub fn user_name(&self, id: i64) -> Option<String> {
+        let user = self.users.iter().find(|u| u.id == Some(id))?;
+        let full = [user.first_name.as_deref(), user.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if full.is_empty() {
+            user.username.clone()
+        } else {
+            Some(full)
+        }
+    }
+
+    /// Resolve a status name (or numeric ID) to its ID.
+    pub fn resolve_status(&self, query: &str) -> Result<i64, ResolveError> {
+        let candidates = self
+            .statuses
+            .iter()
+            .filter_map(|s| Some((s.id?, vec![s.name.clone()?])));
+        resolve("status", query, candidates)
+    }
+
+    /// Resolve a priority name (or numeric ID) to its ID.
+    pub fn resolve_priority(&self, query: &str) -> Result<i64, ResolveError> {
+        let candidates = self
+            .priorities
+            .iter()
+            .filter_map(|p| Some((p.id?, vec![p.name.clone()?])));
+        resolve("priority", query, candidates)
+    }
+
+    /// Resolve a category name (or numeric ID) to its ID.
+    pub fn resolve_category(&self, query: &str) -> Result<i64, ResolveError> {
+        let candidates = self
+            .categories
+            .iter()
+            .filter_map(|c| Some((c.id?, vec![c.name.clone()?])));
+        resolve("category", query, candidates)
+    }
+
+    /// Resolve a ticket type to the exact name the project uses (e.g. "bug" -> "Bug").
+    pub fn resolve_type(&self, query: &str) -> Result<String, ResolveError> {
+        let candidates = self
+            .types
+            .iter()
+            .filter_map(|t| Some((t.id?, vec![t.name.clone()?])));
+        let id = resolve("ticket type", query, candidates)?;
+        Ok(self
+            .types
+            .iter()
+            .find(|t| t.id == Some(id))
+            .and_then(|t| t.name.clone())
+            .unwrap_or_else(|| query.to_string()))
+    }
+
+    /// Resolve a user by username, full name, email address or numeric ID.
+    pub fn resolve_user(&self, query: &str) -> Result<i64, ResolveError> {
+        let candidates = self.users.iter().filter_map(|u| {
+            let mut names: Vec<String> = u.username.iter().cloned().collect();
+            names.extend(self.user_name(u.id?));
+            names.extend(u.email_address.iter().cloned());
+            Some((u.id?, names))
+        });
+        resolve("user", query, candidates)
+    }
+}
+
+/// Pick the single candidate matching `query`.
+///
+/// A numeric query matches a candidate named exactly that, otherwise the
+/// candidate with that ID. Other queries try each tier in turn —
+/// exact (case-insensitive), exact ignoring punctuation, prefix, substring,
+/// then small typos — and the first tier with any match decides: one match
+/// wins, several are reported as ambiguous. Each candidate is an ID and the
+/// names it may be referred to by; the first name is used in messages.
+pub fn resolve(
+    kind: &'static str,
+    query: &str,
+    candidates: impl IntoIterator<Item = (i64, Vec<String>)>,
+) -> Result<i64, ResolveError> {
+    let candidates: Vec<(i64, Vec<String>)> = candidates.into_iter().collect();
+    let not_found = || ResolveError::NotFound {
+        kind,
+        query: query.to_string(),
+        available: candidates
+            .iter()
+            .filter_map(|(_, names)| names.first().cloned())
+            .collect(),
+    };
+    if let Ok(id) = query.trim().parse::<i64>() {
+        let named = candidates
+            .iter()
+            .find(|(_, names)| names.iter().any(|n| n.trim() == query.trim()));
+        return match named {
+            Some((named, _)) => Ok(*named),
+            None if candidates.iter().any(|(c, _)| *c == id) => Ok(id),
+            None => Err(not_found()),
+        };
+    }
+
+    let wanted = normalize(query);
+    let tiers: [&dyn Fn(&str) -> bool; 5] = [
+        &|name| name.eq_ignore_ascii_case(query.trim()),
+        &|name| normalize(name) == wanted,
+        &|name| !wanted.is_empty() && normalize(name).starts_with(&wanted),
+        &|name| !wanted.is_empty() && normalize(name).contains(&wanted),
+        &|name| wanted.len() >= 4 && edit_distance(&normalize(name), &wanted) <= 2,
+    ];
+    for matches_name in tiers {
+        let matched: Vec<&(i64, Vec<String>)> = candidates
+            .iter()
+            .filter(|(_, names)| names.iter().any(|n| matches_name(n)))
+            .collect();
+        match matched.as_slice() {
+            [] => continue,
+            [(id, _)] => return Ok(*id),
+            many => {
+                return Err(ResolveError::Ambiguous {
+                    kind,
+                    query: query.to_string(),
+                    matches: many.iter().map(|(_, names)| names[0].clone()).collect(),
+                });
+            }
+        }
+    }
+
+    Err(not_found())
+}
+
+/// Lowercase and drop everything but letters and digits, so "in-progress",
+/// "In Progress" and "in_progress" compare equal.
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// A single field change recorded on a ticket note.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Transition {
+    pub field: &'static str,
+    /// The value before this note, if known.
+    pub from: Option<String>,
+    pub to: String,
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.from {
+            Some(from) => write!(f, "{}: {} → {}", self.field, from, self.to),
+            None => write!(f, "{} → {}", self.field, self.to),
+        }
+    }
+}
+
+/// A ticket note together with the field changes it made, by name.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub note: TicketNote,
+    pub transitions: Vec<Transition>,
+}
+
+/// Build a ticket's timeline from its notes (oldest first).
+///
+/// The previous value of a field is the one set by the last note that
+/// changed it or, for its first change, the old value in the note's
+/// `updates`. Statuses, priorities, categories and assignees are named from
+/// `lookup`; milestones aren't part of it, so they always show as `#<id>`.
+pub fn timeline(notes: &[TicketNote], lookup: &ProjectLookup) -> Vec<TimelineEntry> {
+    let mut current: HashMap<&'static str, String> = HashMap::new();
+    notes
+        .iter()
+        .map(|note| {
+            let mut previous = previous_values(note, lookup);
+            let transitions = note
+                .changes
+                .as_ref()
+                .map(|changes| describe_changes(changes, lookup))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(field, to)| Transition {
+                    field,
+                    from: current
+                        .insert(field, to.clone())
+                        .or_else(|| previous.remove(field)),
+                    to,
+                })
+                .collect();
+            TimelineEntry {
+                note: note.clone(),
+                transitions,
+            }
+        })
+        .collect()
+}
+
+/// Timeline field names and the keys the API uses for them in `updates`.
+const UPDATE_KEYS: [(&str, &str); 6] = [
+    ("status", "status_id"),
+    ("priority", "priority_id"),
+    ("category", "category_id"),
+    ("assignee", "assignee_id"),
+    ("milestone", "milestone_id"),
+    ("subject", "subject"),
+];
+
+/// The old value of each field in a note's `updates`, by name.
+fn previous_values(note: &TicketNote, lookup: &ProjectLookup) -> HashMap<&'static str, String> {
+    let updates: serde_json::Map<String, serde_json::Value> = note
+        .updates
+        .as_deref()
+        .and_then(|u| serde_json::from_str(u).ok())
+        .unwrap_or_default();
+    UPDATE_KEYS
+        .iter()
+        .filter_map(|&(field, key)| {
+            let old = match updates.get(key)?.get(0)? {
+                serde_json::Value::String(s) if !s.is_empty() => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            if field == "subject" {
+                return Some((field, old));
+            }
+            let id = old.parse().ok()?;
+            Some((field, value_name(field, id, lookup)))
+        })
+        .collect()
+}
+
+/// The name shown for an ID-valued field, falling back to `#<id>` (always,
+/// for milestones).
+fn value_name(field: &str, id: i64, lookup: &ProjectLookup) -> String {
+    let found = match field {
+        "status" => lookup.status_name(id).map(str::to_string),
+        "priority" => lookup.priority_name(id).map(str::to_string),
+        "category" => lookup.category_name(id).map(str::to_string),
+        "assignee" => lookup.user_name(id),
+        _ => None,
+    };
+    found.unwrap_or_else(|| format!("#{}", id))
+}
+
+fn describe_changes(changes: &NoteChanges, lookup: &ProjectLookup) -> Vec<(&'static str, String)> {
+    let ids = [
+        ("status", changes.status_id),
+        ("priority", changes.priority_id),
+        ("category", changes.category_id),
+        ("assignee", changes.assignee_id),
+        ("milestone", changes.milestone_id),
+    ];
+    let mut out: Vec<(&'static str, String)> = ids
+        .into_iter()
+        .filter_map(|(field, id)| Some((field, value_name(field, id?, lookup))))
+        .collect();
+    if let Some(subject) = &changes.subject {
+        out.push(("subject", subject.clone()));
+    }
+    out
+}
@@ -1,15 +1,14 @@
-use anyhow::Result;
-use quick_xml::de::from_str;
-
 use super::client::CodebaseClient;
+use super::error::{Result, from_xml, to_xml};
 use super::models::*;
 
 pub async fn list_milestones(client: &CodebaseClient, project: &str) -> Result<Vec<Milestone>> {
     let xml = client.get(&format!("/{}/milestones", project)).await?;
-    let milestones: Milestones = from_str(&xml)?;
+    let milestones: Milestones = from_xml(&xml)?;
     Ok(milestones.milestones)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_milestone(
     client: &CodebaseClient,
     project: &str,
@@ -21,37 +20,23 @@ pub async fn create_milestone(
     parent_id: Option<i64>,
     status: Option<&str>,
 ) -> Result<Milestone> {
-    let mut fields = String::new();
-    fields.push_str(&format!("<name>{}</name>", name));
-    if let Some(d) = description {
-        fields.push_str(&format!("<description>{}</description>", d));
-    }
-    if let Some(s) = start_at {
-        fields.push_str(&format!("<start-at>{}</start-at>", s));
-    }
-    if let Some(d) = deadline {
-        fields.push_str(&format!("<deadline>{}</deadline>", d));
-    }
-    if let Some(id) = responsible_user_id {
-        fields.push_str(&format!(
-            "<responsible-user-id>{}</responsible-user-id>",
-            id
-        ));
-    }
-    if let Some(id) = parent_id {
-        fields.push_str(&format!("<parent-id>{}</parent-id>", id));
-    }
-    if let Some(s) = status {
-        fields.push_str(&format!("<status>{}</status>", s));
-    }
-    let body = format!("<ticketing-milestone>{}</ticketing-milestone>", fields);
+    let body = to_xml(&MilestoneRequest {
+        name: Some(name.to_string()),
+        description: description.map(str::to_string),
+        start_at: start_at.map(str::to_string),
+        deadline: deadline.map(str::to_string),
+        responsible_user_id,
+        parent_id,
+        status: status.map(str::to_string),
+    })?;
     let xml = client
         .post(&format!("/{}/milestones", project), body)
         .await?;
-    let milestone: Milestone = from_str(&xml)?;
+    let milestone: Milestone = from_xml(&xml)?;
     Ok(milestone)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_milestone(
     client: &CodebaseClient,
     project: &str,
@@ -64,35 +49,18 @@ pub async fn update_milestone(
     parent_id: Option<i64>,
     status: Option<&str>,
 ) -> Result<Milestone> {
-    let mut fields = String::new();
-    if let Some(n) = name {
-        fields.push_str(&format!("<name>{}</name>", n));
-    }
-    if let Some(d) = description {
-        fields.push_str(&format!("<description>{}</description>", d));
-    }
-    if let Some(s) = start_at {
-        fields.push_str(&format!("<start-at>{}</start-at>", s));
-    }
-    if let Some(d) = deadline {
-        fields.push_str(&format!("<deadline>{}</deadline>", d));
-    }
-    if let Some(id) = responsible_user_id {
-        fields.push_str(&format!(
-            "<responsible-user-id>{}</responsible-user-id>",
-            id
-        ));
-    }
-    if let Some(id) = parent_id {
-        fields.push_str(&format!("<parent-id>{}</parent-id>", id));
-    }
-    if let Some(s) = status {
-        fields.push_str(&format!("<status>{}</status>", s));
-    }
-    let body = format!("<ticketing-milestone>{}</ticketing-milestone>", fields);
+    let body = to_xml(&MilestoneRequest {
+        name: name.map(str::to_string),
+        description: description.map(str::to_string),
+        start_at: start_at.map(str::to_string),
+        deadline: deadline.map(str::to_string),
+        responsible_user_id,
+        parent_id,
+        status: status.map(str::to_string),
+    })?;
     let xml = client
         .put(&format!("/{}/milestones/{}", project, milestone_id), body)
         .await?;
-    let milestone: Milestone = from_str(&xml)?;
+    let milestone: Milestone = from_xml(&xml)?;
     Ok(milestone)
 }
@@ -1,8 +1,21 @@
 pub mod activity;
+pub mod cache;
+pub mod cassette;
 pub mod client;
 pub mod config;
+pub mod error;
+#[cfg(feature = "test-util")]
+pub mod fake;
+pub mod http;
+pub mod lookup;
 pub mod milestones;
 pub mod models;
+pub mod pagination;
 pub mod projects;
+pub mod raw;
+pub mod redact;
 pub mod repositories;
+pub mod retry;
+pub mod throttle;
 pub mod tickets;
+pub mod transport;
@@ -361,6 +361,9 @@ pub struct TicketNote {
     #[serde(rename = "time-added")]
     pub time_added: Option<String>,
     pub changes: Option<NoteChanges>,
+    /// JSON object of the fields this note changed, each as `[old, new]`,
+    /// e.g. `{"status_id":[1,2]}`.
+    pub updates: Option<String>,
     #[serde(default, deserialize_with = "deserialize_optional_bool")]
     pub private: Option<bool>,
 }
@@ -370,33 +373,39 @@ pub struct NoteChanges {
     #[serde(
         rename = "status-id",
         default,
+        skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_optional_i64"
     )]
     pub status_id: Option<i64>,
     #[serde(
         rename = "priority-id",
         default,
+        skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_optional_i64"
     )]
     pub priority_id: Option<i64>,
     #[serde(
         rename = "category-id",
         default,
+        skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_optional_i64"
     )]
     pub category_id: Option<i64>,
     #[serde(
         rename = "assignee-id",
         default,
+        skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_optional_i64"
     )]
     pub assignee_id: Option<i64>,
     #[serde(
         rename = "milestone-id",
         default,
+        skip_serializing_if = "Option::is_none",
         deserialize_with = "deserialize_optional_i64"
     )]
     pub milestone_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subject: Option<String>,
 }
 
@@ -475,11 +484,30 @@ pub struct TicketTypes {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "WatcherXml")]
 pub struct Watcher {
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
     pub watcher: Option<i64>,
 }
 
+/// A watcher as the API sends it, `<watcher type="integer">42</watcher>`,
+/// or nested as `<watcher><watcher>42</watcher></watcher>`.
+#[derive(Deserialize)]
+struct WatcherXml {
+    #[serde(rename = "$text", default)]
+    text: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_i64")]
+    watcher: Option<i64>,
+}
+
+impl From<WatcherXml> for Watcher {
+    fn from(xml: WatcherXml) -> Self {
+        let text = xml.text.and_then(|t| t.trim().parse().ok());
+        Self {
+            watcher: xml.watcher.or(text),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Watchers {
     #[serde(rename = "watcher", default)]
@@ -553,3 +581,153 @@ pub struct Events {
     #[serde(rename = "event", default)]
     pub events: Vec<Event>,
 }
+
+// ── Request bodies ──
+//
+// Serialized with `quick_xml::se` so that user-supplied text is always escaped.
+// `None` fields are omitted from the body rather than sent as empty elements.
+
+/// Serializes `true` as `1` and is skipped entirely when false.
+fn serialize_flag<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_u8(u8::from(*value))
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename = "project")]
+pub struct ProjectRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserRef {
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "users")]
+pub struct ProjectAssignmentsRequest {
+    #[serde(rename = "user")]
+    pub users: Vec<UserRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "repository")]
+pub struct NewRepository {
+    pub name: String,
+    pub scm: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "deployment")]
+pub struct NewDeployment {
+    pub branch: String,
+    pub revision: String,
+    pub servers: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "repository-hook")]
+pub struct NewHook {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "merge-request")]
+pub struct NewMergeRequest {
+    #[serde(rename = "source-ref")]
+    pub source_ref: String,
+    #[serde(rename = "target-ref")]
+    pub target_ref: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "merge-request-comment")]
+pub struct NewMergeRequestComment {
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "merge-request")]
+pub struct MergeRequestReassignment {
+    #[serde(rename = "user-id")]
+    pub user_id: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename = "ticket")]
+pub struct NewTicket {
+    pub summary: String,
+    #[serde(rename = "ticket-type")]
+    pub ticket_type: String,
+    #[serde(rename = "priority-id", skip_serializing_if = "Option::is_none")]
+    pub priority_id: Option<i64>,
+    #[serde(rename = "status-id", skip_serializing_if = "Option::is_none")]
+    pub status_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "assignee-id", skip_serializing_if = "Option::is_none")]
+    pub assignee_id: Option<i64>,
+    #[serde(rename = "category-id", skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<i64>,
+    #[serde(rename = "milestone-id", skip_serializing_if = "Option::is_none")]
+    pub milestone_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename = "ticket-note")]
+pub struct NewTicketNote {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(serialize_with = "serialize_flag", skip_serializing_if = "is_false")]
+    pub private: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<NoteChanges>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename = "watchers")]
+pub struct WatchersRequest {
+    #[serde(rename = "watcher")]
+    pub watchers: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename = "ticketing-milestone")]
+pub struct MilestoneRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "start-at", skip_serializing_if = "Option::is_none")]
+    pub start_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    #[serde(
+        rename = "responsible-user-id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub responsible_user_id: Option<i64>,
+    #[serde(rename = "parent-id", skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
@@ -0,0 +1,107 @@
+use futures::stream::{self, Stream, TryStreamExt};
+
+use super::client::CodebaseClient;
+use super::error::{CodebaseError, Result};
+
+/// Walks a paged CodebaseHQ list endpoint by following `page=N` until an
+/// empty page comes back (or an optional item limit is reached).
+pub struct Paginator<T> {
+    client: CodebaseClient,
+    path: String,
+    parse: fn(&str) -> Result<Vec<T>>,
+    page: u32,
+    remaining: Option<usize>,
+    fetched: bool,
+    done: bool,
+}
+
+impl<T> Paginator<T> {
+    pub fn new(client: &CodebaseClient, path: String, parse: fn(&str) -> Result<Vec<T>>) -> Self {
+        Self {
+            client: client.clone(),
+            path,
+            parse,
+            page: 1,
+            remaining: None,
+            fetched: false,
+            done: false,
+        }
+    }
+
+    /// Start from the given page instead of the first one.
+    pub fn start_page(mut self, page: u32) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    /// Stop once `limit` items have been returned in total.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.remaining = Some(limit);
+        self
+    }
+
+    /// The path for the page that will be fetched next.
+    fn page_path(&self) -> String {
+        if self.page == 1 {
+            return self.path.clone();
+        }
+        let sep = if self.path.contains('?') { '&' } else { '?' };
+        format!("{}{}page={}", self.path, sep, self.page)
+    }
+
+    /// Fetch the next page, or `None` once the listing is exhausted.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>> {
+        if self.done || self.remaining == Some(0) {
+            return Ok(None);
+        }
+        // Pages are recorded for `--raw-fields` below, once the limit is known.
+        let xml = match self
+            .client
+            .without_raw_capture()
+            .get(&self.page_path())
+            .await
+        {
+            Ok(xml) => xml,
+            // Some endpoints answer 404 rather than an empty list past the last page.
+            Err(CodebaseError::NotFound { .. }) if self.fetched => {
+                self.done = true;
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        self.fetched = true;
+        let mut items = (self.parse)(&xml)?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            items.truncate(*remaining);
+            *remaining -= items.len();
+        }
+        if let Some(raw) = &self.client.raw {
+            raw.record_page(&xml, items.len());
+        }
+        if items.is_empty() {
+            self.done = true;
+            return Ok(None);
+        }
+        self.page += 1;
+        Ok(Some(items))
+    }
+
+    /// Fetch every remaining page and concatenate the results.
+    pub async fn collect_all(mut self) -> Result<Vec<T>> {
+        let mut all = Vec::new();
+        while let Some(items) = self.next_page().await? {
+            all.extend(items);
+        }
+        Ok(all)
+    }
+
+    /// Turn the paginator into a stream of individual items, fetching pages lazily.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T>> {
+        stream::try_unfold(self, |mut pager| async move {
+            let page = pager.next_page().await?;
+            Ok::<_, CodebaseError>(page.map(|items| (items, pager)))
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
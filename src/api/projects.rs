@@ -1,25 +1,28 @@
-use anyhow::Result;
-use quick_xml::de::from_str;
-
+use super::cache::{PROJECTS_TTL, USERS_TTL};
 use super::client::CodebaseClient;
+use super::error::{Result, from_xml, to_xml};
 use super::models::*;
 
 pub async fn list_projects(client: &CodebaseClient) -> Result<Vec<Project>> {
-    let xml = client.get("/projects").await?;
-    let projects: Projects = from_str(&xml)?;
+    let xml = client.get_cached("/projects", PROJECTS_TTL).await?;
+    let projects: Projects = from_xml(&xml)?;
     Ok(projects.projects)
 }
 
 pub async fn show_project(client: &CodebaseClient, permalink: &str) -> Result<Project> {
     let xml = client.get(&format!("/{}", permalink)).await?;
-    let project: Project = from_str(&xml)?;
+    let project: Project = from_xml(&xml)?;
     Ok(project)
 }
 
 pub async fn create_project(client: &CodebaseClient, name: &str) -> Result<Project> {
-    let body = format!("<project><name>{}</name></project>", name);
+    let body = to_xml(&ProjectRequest {
+        name: Some(name.to_string()),
+        status: None,
+    })?;
     let xml = client.post("/create_project", body).await?;
-    let project: Project = from_str(&xml)?;
+    client.invalidate("/projects");
+    let project: Project = from_xml(&xml)?;
     Ok(project)
 }
 
@@ -29,29 +32,27 @@ pub async fn update_project(
     name: Option<&str>,
     status: Option<&str>,
 ) -> Result<Project> {
-    let mut fields = String::new();
-    if let Some(n) = name {
-        fields.push_str(&format!("<name>{}</name>", n));
-    }
-    if let Some(s) = status {
-        fields.push_str(&format!("<status>{}</status>", s));
-    }
-    let body = format!("<project>{}</project>", fields);
+    let body = to_xml(&ProjectRequest {
+        name: name.map(str::to_string),
+        status: status.map(str::to_string),
+    })?;
     let xml = client
         .put(&format!("/project/{}", project_id), body)
         .await?;
-    let project: Project = from_str(&xml)?;
+    client.invalidate("/projects");
+    let project: Project = from_xml(&xml)?;
     Ok(project)
 }
 
 pub async fn delete_project(client: &CodebaseClient, permalink: &str) -> Result<()> {
     client.delete(&format!("/{}", permalink)).await?;
+    client.invalidate("/projects");
     Ok(())
 }
 
 pub async fn list_project_groups(client: &CodebaseClient) -> Result<Vec<ProjectGroup>> {
-    let xml = client.get("/project_groups").await?;
-    let groups: ProjectGroups = from_str(&xml)?;
+    let xml = client.get_cached("/project_groups", PROJECTS_TTL).await?;
+    let groups: ProjectGroups = from_xml(&xml)?;
     Ok(groups.groups)
 }
 
@@ -59,8 +60,10 @@ pub async fn list_project_users(
     client: &CodebaseClient,
     project: &str,
 ) -> Result<Vec<ProjectUser>> {
-    let xml = client.get(&format!("/{}/assignments", project)).await?;
-    let users: ProjectUsers = from_str(&xml)?;
+    let xml = client
+        .get_cached(&format!("/{}/assignments", project), USERS_TTL)
+        .await?;
+    let users: ProjectUsers = from_xml(&xml)?;
     Ok(users.users)
 }
 
@@ -69,13 +72,11 @@ pub async fn assign_project_users(
     project: &str,
     user_ids: &[i64],
 ) -> Result<()> {
-    let users_xml: String = user_ids
-        .iter()
-        .map(|id| format!("<user><id>{}</id></user>", id))
-        .collect();
-    let body = format!("<users>{}</users>", users_xml);
-    client
-        .post(&format!("/{}/assignments", project), body)
-        .await?;
+    let body = to_xml(&ProjectAssignmentsRequest {
+        users: user_ids.iter().map(|&id| UserRef { id }).collect(),
+    })?;
+    let path = format!("/{}/assignments", project);
+    client.post(&path, body).await?;
+    client.invalidate(&path);
     Ok(())
 }
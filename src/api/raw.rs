@@ -0,0 +1,391 @@
+//! Requests and responses outside `api::models`: raw requests for `cb api`,
+//! and lossless XML-to-JSON conversion for `cb api` and `--raw-fields`.
+
+use std::sync::{Arc, Mutex};
+
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use reqwest::Method;
+use serde_json::{Map, Value};
+
+use super::client::CodebaseClient;
+use super::error::{CodebaseError, Result};
+
+/// Send `method` to `path` (which may include a query string) with an
+/// optional XML body and return the raw response body. Authentication,
+/// retries and error mapping are the same as for the typed API functions.
+pub async fn request(
+    client: &CodebaseClient,
+    method: Method,
+    path: &str,
+    body: Option<String>,
+) -> Result<String> {
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    };
+    client.request_text(method, &path, body).await
+}
+
+/// Split a `key=value` field argument.
+pub fn parse_field(field: &str) -> std::result::Result<(String, String), String> {
+    match field.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected key=value, got '{}'", field)),
+    }
+}
+
+/// Append `fields` to `path` as a URL-encoded query string.
+pub fn with_query(path: &str, fields: &[(String, String)]) -> String {
+    if fields.is_empty() {
+        return path.to_string();
+    }
+    let query: Vec<String> = fields
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+        .collect();
+    let separator = if path.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", path, separator, query.join("&"))
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Build an XML body from `key=value` fields. Keys nest with brackets, so
+/// `ticket[summary]=Hi` and `ticket[ticket-type]=bug` become
+/// `<ticket><summary>Hi</summary><ticket-type>bug</ticket-type></ticket>`.
+/// Values are escaped.
+pub fn fields_to_xml(fields: &[(String, String)]) -> std::result::Result<String, String> {
+    let mut root = Node::default();
+    for (key, value) in fields {
+        let path = parse_key(key)?;
+        root.insert(&path, value);
+    }
+    let mut out = String::new();
+    root.write(&mut out);
+    Ok(out)
+}
+
+/// `a[b][c]` → `["a", "b", "c"]`.
+fn parse_key(key: &str) -> std::result::Result<Vec<String>, String> {
+    let invalid = || format!("invalid field name '{}'", key);
+    let (head, mut rest) = key.split_once('[').map_or((key, ""), |(h, r)| (h, r));
+    let mut path = vec![head.to_string()];
+    while !rest.is_empty() {
+        let (segment, after) = rest.split_once(']').ok_or_else(invalid)?;
+        path.push(segment.to_string());
+        rest = match after {
+            "" => "",
+            a => a.strip_prefix('[').ok_or_else(invalid)?,
+        };
+    }
+    let is_name = |s: &String| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !path.iter().all(is_name) {
+        return Err(invalid());
+    }
+    Ok(path)
+}
+
+/// Elements in insertion order; a leaf holds text.
+#[derive(Default)]
+struct Node {
+    children: Vec<(String, Node)>,
+    text: Option<String>,
+}
+
+impl Node {
+    fn insert(&mut self, path: &[String], value: &str) {
+        let Some((name, rest)) = path.split_first() else {
+            self.text = Some(value.to_string());
+            return;
+        };
+        let index = match self.children.iter().position(|(n, _)| n == name) {
+            Some(i) if !rest.is_empty() => i,
+            _ => {
+                self.children.push((name.clone(), Node::default()));
+                self.children.len() - 1
+            }
+        };
+        self.children[index].1.insert(rest, value);
+    }
+
+    fn write(&self, out: &mut String) {
+        if let Some(text) = &self.text {
+            out.push_str(&escape(text));
+        }
+        for (name, child) in &self.children {
+            out.push_str(&format!("<{}>", name));
+            child.write(out);
+            out.push_str(&format!("</{}>", name));
+        }
+    }
+}
+
+/// Convert an XML document to JSON, keeping every element whether or not
+/// `api::models` knows about it.
+///
+/// The root element becomes the top-level value. An element with child
+/// elements becomes an object, with repeated names collected into an array;
+/// any other element becomes its text. The API's Rails-style type attributes
+/// are honored: `type="array"` always gives an array, `type="integer"`,
+/// `type="float"`/`"decimal"` and `type="boolean"` give numbers and booleans,
+/// and `nil="true"` gives null. Other attributes are dropped.
+pub fn xml_to_json(xml: &str) -> Result<Value> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let (start, end) = match reader.read_event().map_err(decode_error)? {
+            Event::Start(e) => (Some(Element::new(&e)?), false),
+            Event::Empty(e) => (Some(Element::new(&e)?), true),
+            Event::End(_) => (None, true),
+            Event::Text(e) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&e.unescape().map_err(decode_error)?);
+                }
+                continue;
+            }
+            Event::CData(e) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&e));
+                }
+                continue;
+            }
+            Event::Eof => match stack.last() {
+                Some(open) => {
+                    return Err(custom_error(format!("unclosed element <{}>", open.name)));
+                }
+                None => break,
+            },
+            _ => continue,
+        };
+        stack.extend(start);
+        if end {
+            let element = stack.pop().expect("reader checks end tags");
+            let name = element.name.clone();
+            let value = element.into_value();
+            match stack.last_mut() {
+                Some(parent) => parent.add_child(name, value),
+                None => root = Some(value),
+            }
+        }
+    }
+    root.ok_or_else(|| custom_error("no root element".to_string()))
+}
+
+/// The declared type of an element, from its `type` and `nil` attributes.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Plain,
+    Array,
+    Integer,
+    Float,
+    Boolean,
+    Nil,
+}
+
+/// An element being read: its children so far and its text.
+struct Element {
+    name: String,
+    kind: Kind,
+    children: Map<String, Value>,
+    items: Vec<Value>,
+    text: String,
+}
+
+impl Element {
+    fn new(start: &quick_xml::events::BytesStart) -> Result<Self> {
+        let mut kind = Kind::Plain;
+        for attr in start.attributes() {
+            let attr = attr.map_err(decode_error)?;
+            let value = attr.unescape_value().map_err(decode_error)?;
+            match (attr.key.as_ref(), value.as_ref()) {
+                (b"nil", "true") => kind = Kind::Nil,
+                (b"type", _) if kind == Kind::Nil => {}
+                (b"type", "array") => kind = Kind::Array,
+                (b"type", "integer") => kind = Kind::Integer,
+                (b"type", "float" | "decimal") => kind = Kind::Float,
+                (b"type", "boolean") => kind = Kind::Boolean,
+                _ => {}
+            }
+        }
+        Ok(Element {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            kind,
+            children: Map::new(),
+            items: Vec::new(),
+            text: String::new(),
+        })
+    }
+
+    fn add_child(&mut self, name: String, value: Value) {
+        if self.kind == Kind::Array {
+            self.items.push(value);
+        } else {
+            add_child(&mut self.children, name, value);
+        }
+    }
+
+    fn into_value(self) -> Value {
+        let text = self.text.trim();
+        let typed = match self.kind {
+            Kind::Nil => Some(Value::Null),
+            Kind::Array => Some(Value::Array(self.items)),
+            Kind::Integer => text.parse::<i64>().ok().map(Value::from),
+            Kind::Float => text
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            Kind::Boolean => match text {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            Kind::Plain => None,
+        };
+        match typed {
+            Some(value) => value,
+            None if self.children.is_empty() => Value::String(self.text),
+            None => Value::Object(self.children),
+        }
+    }
+}
+
+fn add_child(children: &mut Map<String, Value>, name: String, value: Value) {
+    match children.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            children.insert(name, value);
+        }
+    }
+}
+
+fn decode_error(e: impl Into<quick_xml::Error>) -> CodebaseError {
+    CodebaseError::Decode(quick_xml::DeError::from(e.into()))
+}
+
+fn custom_error(message: String) -> CodebaseError {
+    CodebaseError::Decode(quick_xml::DeError::Custom(message))
+}
+
+/// Response bodies recorded for `--raw-fields`. A client given a capture
+/// with [`CodebaseClient::with_raw_capture`] records every successful
+/// response, and the output layer prints them converted with
+/// [`xml_to_json`] instead of the parsed models. Clones share the same
+/// recording.
+#[derive(Debug, Clone, Default)]
+pub struct RawCapture(Arc<Mutex<Vec<Captured>>>);
+
+/// One recorded body. Pages of a list also hold how many of their items
+/// the listing used, so `--limit` applies to the raw output too.
+#[derive(Debug)]
+struct Captured {
+    body: String,
+    page_items: Option<usize>,
+}
+
+impl RawCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, body: &str) {
+        self.push(body, None);
+    }
+
+    /// Record one page of a list, of which the first `items` were used.
+    pub fn record_page(&self, body: &str, items: usize) {
+        self.push(body, Some(items));
+    }
+
+    fn push(&self, body: &str, page_items: Option<usize>) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Captured {
+                body: body.to_string(),
+                page_items,
+            });
+    }
+
+    fn take_captured(&self) -> Vec<Captured> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Remove and return the recorded bodies, oldest first.
+    pub fn take(&self) -> Vec<String> {
+        self.take_captured().into_iter().map(|c| c.body).collect()
+    }
+
+    /// Remove the recorded bodies and convert them to one JSON value: a single
+    /// response as-is, the pages of a list as one array of their used items,
+    /// and anything else as an array with one value per response. Empty
+    /// bodies (e.g. from a delete) are skipped.
+    pub fn take_json(&self) -> Result<Value> {
+        let mut values: Vec<Value> = Vec::new();
+        let mut in_list = false;
+        for captured in self.take_captured() {
+            if captured.body.trim().is_empty() {
+                continue;
+            }
+            let value = xml_to_json(&captured.body)?;
+            let Some(used) = captured.page_items else {
+                values.push(value);
+                in_list = false;
+                continue;
+            };
+            if !in_list {
+                values.push(Value::Array(Vec::new()));
+                in_list = true;
+            }
+            if let Some(Value::Array(items)) = values.last_mut() {
+                items.extend(list_items(value).into_iter().take(used));
+            }
+        }
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+        Ok(Value::Array(values))
+    }
+}
+
+/// The items of a converted list page. Pages without `type="array"` come
+/// out as `{"ticket": [...]}`, or `{"ticket": {...}}` with a single item,
+/// or `""` when empty.
+fn list_items(page: Value) -> Vec<Value> {
+    match page {
+        Value::Array(items) => items,
+        Value::Object(map) if map.len() == 1 => match map.into_iter().next() {
+            Some((_, Value::Array(items))) => items,
+            Some((_, item)) => vec![item],
+            None => Vec::new(),
+        },
+        Value::String(text) if text.trim().is_empty() => Vec::new(),
+        other => vec![other],
+    }
+}
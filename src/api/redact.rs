@@ -0,0 +1,80 @@
+//! Scrubbing secrets from request and response bodies before they are
+//! logged or written to disk.
+
+/// Replacement for a secret value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Longest body shown in logs; the rest is summarized.
+const MAX_LOGGED_BODY: usize = 4096;
+
+/// Whether an element named `name` holds a secret: any name mentioning a
+/// password, token, secret, API key or private key.
+pub fn is_sensitive(name: &str) -> bool {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    ["password", "token", "secret", "apikey", "privatekey"]
+        .iter()
+        .any(|word| name.contains(word))
+}
+
+/// `xml` with the text of every [sensitive](is_sensitive) element replaced
+/// by [`REDACTED`]. Anything that isn't well-formed is passed through as-is.
+pub fn redact_xml(xml: &str) -> String {
+    let mut out = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        let Some(len) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = &rest[open + 1..open + len];
+        out.push_str(&rest[..open + len + 1]);
+        rest = &rest[open + len + 1..];
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if !is_sensitive(name) {
+            continue;
+        }
+        if let Some(end) = rest.find(&format!("</{}>", name)) {
+            if end > 0 {
+                out.push_str(REDACTED);
+            }
+            rest = &rest[end..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A redacted body for logs, shortened when very long.
+pub fn for_log(body: &str) -> String {
+    let body = redact_xml(body);
+    if body.len() <= MAX_LOGGED_BODY {
+        return body;
+    }
+    let mut end = MAX_LOGGED_BODY;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} more bytes)", &body[..end], body.len() - end)
+}
+
+/// `url` for logs, with the value of every query parameter replaced by
+/// [`REDACTED`]: searches and filters can name customers or carry tokens.
+pub fn url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) => format!("{}={}", name, REDACTED),
+            None => param.to_string(),
+        })
+        .collect();
+    format!("{}?{}", base, params.join("&"))
+}
@@ -1,12 +1,11 @@
-use anyhow::Result;
-use quick_xml::de::from_str;
-
 use super::client::CodebaseClient;
+use super::error::{Result, from_xml, to_xml};
 use super::models::*;
+use super::pagination::Paginator;
 
 pub async fn list_repositories(client: &CodebaseClient, project: &str) -> Result<Vec<Repository>> {
     let xml = client.get(&format!("/{}/repositories", project)).await?;
-    let repos: Repositories = from_str(&xml)?;
+    let repos: Repositories = from_xml(&xml)?;
     Ok(repos.repositories)
 }
 
@@ -16,7 +15,7 @@ pub async fn show_repository(
     repo: &str,
 ) -> Result<Repository> {
     let xml = client.get(&format!("/{}/{}", project, repo)).await?;
-    let repository: Repository = from_str(&xml)?;
+    let repository: Repository = from_xml(&xml)?;
     Ok(repository)
 }
 
@@ -26,14 +25,14 @@ pub async fn create_repository(
     name: &str,
     scm: &str,
 ) -> Result<Repository> {
-    let body = format!(
-        "<repository><name>{}</name><scm>{}</scm></repository>",
-        name, scm
-    );
+    let body = to_xml(&NewRepository {
+        name: name.to_string(),
+        scm: scm.to_string(),
+    })?;
     let xml = client
         .post(&format!("/{}/repositories", project), body)
         .await?;
-    let repository: Repository = from_str(&xml)?;
+    let repository: Repository = from_xml(&xml)?;
     Ok(repository)
 }
 
@@ -53,7 +52,7 @@ pub async fn list_commits(
     let xml = client
         .get(&format!("/{}/{}/commits/{}", project, repo, git_ref))
         .await?;
-    let commits: Commits = from_str(&xml)?;
+    let commits: Commits = from_xml(&xml)?;
     Ok(commits.commits)
 }
 
@@ -70,7 +69,27 @@ pub async fn list_commits_path(
             project, repo, git_ref, path
         ))
         .await?;
-    let commits: Commits = from_str(&xml)?;
+    let commits: Commits = from_xml(&xml)?;
+    Ok(commits.commits)
+}
+
+/// Page through the commit history for a ref, optionally restricted to a path.
+pub fn paginate_commits(
+    client: &CodebaseClient,
+    project: &str,
+    repo: &str,
+    git_ref: &str,
+    path: Option<&str>,
+) -> Paginator<Commit> {
+    let url = match path {
+        Some(p) => format!("/{}/{}/commits/{}/{}", project, repo, git_ref, p),
+        None => format!("/{}/{}/commits/{}", project, repo, git_ref),
+    };
+    client.paginate(&url, parse_commits)
+}
+
+fn parse_commits(xml: &str) -> Result<Vec<Commit>> {
+    let commits: Commits = from_xml(xml)?;
     Ok(commits.commits)
 }
 
@@ -85,14 +104,12 @@ pub async fn create_deployment(
     servers: &str,
     environment: Option<&str>,
 ) -> Result<()> {
-    let mut fields = String::new();
-    fields.push_str(&format!("<branch>{}</branch>", branch));
-    fields.push_str(&format!("<revision>{}</revision>", revision));
-    fields.push_str(&format!("<servers>{}</servers>", servers));
-    if let Some(env) = environment {
-        fields.push_str(&format!("<environment>{}</environment>", env));
-    }
-    let body = format!("<deployment>{}</deployment>", fields);
+    let body = to_xml(&NewDeployment {
+        branch: branch.to_string(),
+        revision: revision.to_string(),
+        servers: servers.to_string(),
+        environment: environment.map(str::to_string),
+    })?;
     client
         .post(&format!("/{}/{}/deployments", project, repo), body)
         .await?;
@@ -118,7 +135,7 @@ pub async fn get_file(
 
 pub async fn list_hooks(client: &CodebaseClient, project: &str, repo: &str) -> Result<Vec<Hook>> {
     let xml = client.get(&format!("/{}/{}/hooks", project, repo)).await?;
-    let hooks: Hooks = from_str(&xml)?;
+    let hooks: Hooks = from_xml(&xml)?;
     Ok(hooks.hooks)
 }
 
@@ -130,19 +147,15 @@ pub async fn create_hook(
     username: Option<&str>,
     password: Option<&str>,
 ) -> Result<Hook> {
-    let mut fields = String::new();
-    fields.push_str(&format!("<url>{}</url>", url));
-    if let Some(u) = username {
-        fields.push_str(&format!("<username>{}</username>", u));
-    }
-    if let Some(p) = password {
-        fields.push_str(&format!("<password>{}</password>", p));
-    }
-    let body = format!("<repository-hook>{}</repository-hook>", fields);
+    let body = to_xml(&NewHook {
+        url: url.to_string(),
+        username: username.map(str::to_string),
+        password: password.map(str::to_string),
+    })?;
     let xml = client
         .post(&format!("/{}/{}/hooks", project, repo), body)
         .await?;
-    let hook: Hook = from_str(&xml)?;
+    let hook: Hook = from_xml(&xml)?;
     Ok(hook)
 }
 
@@ -156,7 +169,7 @@ pub async fn list_branches(
     let xml = client
         .get(&format!("/{}/{}/branches", project, repo))
         .await?;
-    let branches: Branches = from_str(&xml)?;
+    let branches: Branches = from_xml(&xml)?;
     Ok(branches.branches)
 }
 
@@ -170,7 +183,7 @@ pub async fn list_merge_requests(
     let xml = client
         .get(&format!("/{}/{}/merge_requests", project, repo))
         .await?;
-    let mrs: MergeRequests = from_str(&xml)?;
+    let mrs: MergeRequests = from_xml(&xml)?;
     Ok(mrs.merge_requests)
 }
 
@@ -183,7 +196,7 @@ pub async fn show_merge_request(
     let xml = client
         .get(&format!("/{}/{}/merge_requests/{}", project, repo, mr_id))
         .await?;
-    let mr: MergeRequest = from_str(&xml)?;
+    let mr: MergeRequest = from_xml(&xml)?;
     Ok(mr)
 }
 
@@ -195,14 +208,15 @@ pub async fn create_merge_request(
     target_ref: &str,
     subject: &str,
 ) -> Result<MergeRequest> {
-    let body = format!(
-        "<merge-request><source-ref>{}</source-ref><target-ref>{}</target-ref><subject>{}</subject></merge-request>",
-        source_ref, target_ref, subject
-    );
+    let body = to_xml(&NewMergeRequest {
+        source_ref: source_ref.to_string(),
+        target_ref: target_ref.to_string(),
+        subject: subject.to_string(),
+    })?;
     let xml = client
         .post(&format!("/{}/{}/merge_requests", project, repo), body)
         .await?;
-    let mr: MergeRequest = from_str(&xml)?;
+    let mr: MergeRequest = from_xml(&xml)?;
     Ok(mr)
 }
 
@@ -213,10 +227,9 @@ pub async fn comment_merge_request(
     mr_id: i64,
     content: &str,
 ) -> Result<()> {
-    let body = format!(
-        "<merge-request-comment><content>{}</content></merge-request-comment>",
-        content
-    );
+    let body = to_xml(&NewMergeRequestComment {
+        content: content.to_string(),
+    })?;
     client
         .post(
             &format!("/{}/{}/merge_requests/{}/comment", project, repo, mr_id),
@@ -278,10 +291,7 @@ pub async fn reassign_merge_request(
     mr_id: i64,
     user_id: i64,
 ) -> Result<()> {
-    let body = format!(
-        "<merge-request><user-id>{}</user-id></merge-request>",
-        user_id
-    );
+    let body = to_xml(&MergeRequestReassignment { user_id })?;
     client
         .post(
             &format!("/{}/{}/merge_requests/{}/reassign", project, repo, mr_id),
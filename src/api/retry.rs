@@ -0,0 +1,208 @@
+use std::time::{Duration, SystemTime};
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::transport::{TransportError, TransportResponse};
+
+/// When and how long [`CodebaseClient`](super::client::CodebaseClient)
+/// waits before repeating a failed request.
+///
+/// The delay before retry `n` (counting from zero) is `base_delay * 2^n`,
+/// capped at `max_delay` and shortened by up to `jitter` (a fraction
+/// between 0 and 1) so that parallel requests don't retry in lockstep. A
+/// `Retry-After` header on the response takes precedence over the backoff.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first; 1 disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: f64,
+    /// Response statuses that are retried.
+    pub statuses: Vec<StatusCode>,
+    /// Retry when the connection can't be established. The request never
+    /// reached the server, so this applies to every method.
+    pub retry_connect_errors: bool,
+    /// Retry when the request times out.
+    pub retry_timeouts: bool,
+    /// Also retry POST and PATCH requests on a retryable 5xx status or
+    /// timeout. Off by default: a create that timed out may have succeeded,
+    /// and repeating it would create a duplicate ticket or note. A 429 is
+    /// retried for every method, since the server refused the request.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.2,
+            statuses: [429, 503, 529]
+                .into_iter()
+                .filter_map(|s| StatusCode::from_u16(s).ok())
+                .collect(),
+            retry_connect_errors: true,
+            retry_timeouts: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a request with `method` that got `status` should be retried.
+    /// Server errors are only retried for idempotent methods, as the server
+    /// may have acted on the request before failing.
+    pub fn retries_status(&self, method: &Method, status: StatusCode) -> bool {
+        self.statuses.contains(&status)
+            && (!status.is_server_error() || self.retries_method(method))
+    }
+
+    /// Whether a request with `method` that failed to send with `err` should
+    /// be retried.
+    pub fn retries_error(&self, method: &Method, err: &TransportError) -> bool {
+        if err.is_connect() {
+            self.retry_connect_errors
+        } else if err.is_timeout() {
+            self.retry_timeouts && self.retries_method(method)
+        } else {
+            false
+        }
+    }
+
+    fn retries_method(&self, method: &Method) -> bool {
+        self.retry_non_idempotent || is_idempotent(method)
+    }
+
+    /// How long to wait before retry `retry` (0 for the first retry).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        delay.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+
+    /// How long to wait before retry `retry` after `resp`: its `Retry-After`
+    /// when present, otherwise the backoff. `None` when the server asks for a
+    /// longer wait than `max_delay`, in which case the request is not retried.
+    pub fn delay_after(&self, retry: u32, resp: &TransportResponse) -> Option<Duration> {
+        match retry_after(&resp.headers) {
+            Some(wait) => (wait <= self.max_delay).then_some(wait),
+            None => Some(self.backoff(retry)),
+        }
+    }
+}
+
+/// Methods that can be repeated without changing the result (RFC 9110 9.2.2).
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    parse_retry_after(value, SystemTime::now())
+}
+
+/// Parse a `Retry-After` value relative to `now`. A date in the past means
+/// no wait.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// Retry settings from the `[retry]` table of the config file; unset keys
+/// keep the [`RetryPolicy`] defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrySettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<u16>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_connect_errors: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_timeouts: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_non_idempotent: Option<bool>,
+}
+
+impl RetrySettings {
+    /// The default policy with these settings applied.
+    pub fn policy(&self) -> anyhow::Result<RetryPolicy> {
+        let mut policy = RetryPolicy::default();
+        if let Some(n) = self.max_attempts {
+            anyhow::ensure!(n >= 1, "retry.max_attempts must be at least 1");
+            policy.max_attempts = n;
+        }
+        if let Some(ms) = self.base_delay_ms {
+            policy.base_delay = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.max_delay_ms {
+            policy.max_delay = Duration::from_millis(ms);
+        }
+        if let Some(jitter) = self.jitter {
+            anyhow::ensure!(
+                (0.0..=1.0).contains(&jitter),
+                "retry.jitter must be between 0 and 1"
+            );
+            policy.jitter = jitter;
+        }
+        if let Some(statuses) = &self.statuses {
+            policy.statuses = statuses
+                .iter()
+                .map(|&s| {
+                    StatusCode::from_u16(s)
+                        .map_err(|_| anyhow::anyhow!("retry.statuses: invalid status {}", s))
+                })
+                .collect::<anyhow::Result<_>>()?;
+        }
+        if let Some(b) = self.retry_connect_errors {
+            policy.retry_connect_errors = b;
+        }
+        if let Some(b) = self.retry_timeouts {
+            policy.retry_timeouts = b;
+        }
+        if let Some(b) = self.retry_non_idempotent {
+            policy.retry_non_idempotent = b;
+        }
+        Ok(policy)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -0,0 +1,138 @@
+//! Keeping request volume under CodebaseHQ's rate limits: a token bucket
+//! shared by every clone of a client, and bounded fan-out for commands that
+//! send many requests at once.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Default sustained request rate.
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 5.0;
+
+/// Default number of requests that may be sent back to back before the
+/// sustained rate applies.
+pub const DEFAULT_BURST: u32 = 10;
+
+/// A token bucket: `burst` requests may go at once, after which requests are
+/// spaced to `requests_per_second`. Clones share the same bucket, so every
+/// clone of a [`CodebaseClient`](super::client::CodebaseClient) draws from
+/// one budget.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Option<Arc<Mutex<Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUESTS_PER_SECOND, DEFAULT_BURST)
+    }
+}
+
+impl RateLimiter {
+    /// A limiter allowing `requests_per_second` on average and bursts of
+    /// `burst`. A rate of zero or less means no limit.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        if requests_per_second <= 0.0 || !requests_per_second.is_finite() {
+            return Self::unlimited();
+        }
+        let burst = f64::from(burst.max(1));
+        Self {
+            bucket: Some(Arc::new(Mutex::new(Bucket {
+                rate: requests_per_second,
+                burst,
+                tokens: burst,
+                updated: Instant::now(),
+            }))),
+        }
+    }
+
+    /// A limiter that never waits.
+    pub fn unlimited() -> Self {
+        Self { bucket: None }
+    }
+
+    /// Wait until a request may be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token at `now` and return how long the caller must wait before
+    /// using it. Tokens may be taken ahead of time (the bucket goes
+    /// negative), so concurrent callers queue up in order instead of all
+    /// waking at once.
+    pub fn reserve(&self, now: Instant) -> Duration {
+        let Some(bucket) = &self.bucket else {
+            return Duration::ZERO;
+        };
+        let Ok(mut b) = bucket.lock() else {
+            return Duration::ZERO;
+        };
+        let elapsed = now.saturating_duration_since(b.updated).as_secs_f64();
+        b.tokens = (b.tokens + elapsed * b.rate).min(b.burst);
+        b.updated = now;
+        b.tokens -= 1.0;
+        if b.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-b.tokens / b.rate)
+        }
+    }
+}
+
+/// Settings from the `[rate_limit]` table of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    /// Sustained requests per second; 0 disables the limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+}
+
+impl RateLimitSettings {
+    pub fn limiter(&self) -> RateLimiter {
+        RateLimiter::new(
+            self.requests_per_second
+                .unwrap_or(DEFAULT_REQUESTS_PER_SECOND),
+            self.burst.unwrap_or(DEFAULT_BURST),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Run `f` over `items` with at most `limit` calls in flight, returning the
+/// results in the order of `items`. Stops at the first error.
+pub async fn map_concurrent<I, F, Fut, T, E>(
+    items: I,
+    limit: usize,
+    f: F,
+) -> std::result::Result<Vec<T>, E>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = std::result::Result<T, E>>,
+{
+    stream::iter(items)
+        .map(f)
+        .buffered(limit.max(1))
+        .try_collect()
+        .await
+}
@@ -1,15 +1,28 @@
-use anyhow::Result;
-use quick_xml::de::from_str;
-
+use super::cache::METADATA_TTL;
 use super::client::CodebaseClient;
+use super::error::{Result, from_xml, to_xml};
 use super::models::*;
+use super::pagination::Paginator;
 
 pub async fn list_tickets(client: &CodebaseClient, project: &str) -> Result<Vec<Ticket>> {
     let xml = client.get(&format!("/{}/tickets", project)).await?;
-    let tickets: Tickets = from_str(&xml)?;
+    let tickets: Tickets = from_xml(&xml)?;
     Ok(tickets.tickets)
 }
 
+/// Page through every ticket in a project.
+pub fn paginate_tickets(client: &CodebaseClient, project: &str) -> Paginator<Ticket> {
+    client.paginate(&format!("/{}/tickets", project), parse_tickets)
+}
+
+pub async fn show_ticket(client: &CodebaseClient, project: &str, ticket_id: i64) -> Result<Ticket> {
+    let xml = client
+        .get(&format!("/{}/tickets/{}", project, ticket_id))
+        .await?;
+    let ticket: Ticket = from_xml(&xml)?;
+    Ok(ticket)
+}
+
 pub async fn search_tickets(
     client: &CodebaseClient,
     project: &str,
@@ -19,10 +32,26 @@ pub async fn search_tickets(
     let xml = client
         .get(&format!("/{}/tickets?query={}", project, encoded))
         .await?;
-    let tickets: Tickets = from_str(&xml)?;
+    let tickets: Tickets = from_xml(&xml)?;
+    Ok(tickets.tickets)
+}
+
+/// Page through every ticket matching a search query.
+pub fn paginate_search_tickets(
+    client: &CodebaseClient,
+    project: &str,
+    query: &str,
+) -> Paginator<Ticket> {
+    let path = format!("/{}/tickets?query={}", project, urlencoding(query));
+    client.paginate(&path, parse_tickets)
+}
+
+fn parse_tickets(xml: &str) -> Result<Vec<Ticket>> {
+    let tickets: Tickets = from_xml(xml)?;
     Ok(tickets.tickets)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_ticket(
     client: &CodebaseClient,
     project: &str,
@@ -36,33 +65,19 @@ pub async fn create_ticket(
     milestone_id: Option<i64>,
     tags: Option<&str>,
 ) -> Result<Ticket> {
-    let mut fields = String::new();
-    fields.push_str(&format!("<summary>{}</summary>", summary));
-    fields.push_str(&format!("<ticket-type>{}</ticket-type>", ticket_type));
-    if let Some(id) = priority_id {
-        fields.push_str(&format!("<priority-id>{}</priority-id>", id));
-    }
-    if let Some(id) = status_id {
-        fields.push_str(&format!("<status-id>{}</status-id>", id));
-    }
-    if let Some(desc) = description {
-        fields.push_str(&format!("<description><![CDATA[{}]]></description>", desc));
-    }
-    if let Some(id) = assignee_id {
-        fields.push_str(&format!("<assignee-id>{}</assignee-id>", id));
-    }
-    if let Some(id) = category_id {
-        fields.push_str(&format!("<category-id>{}</category-id>", id));
-    }
-    if let Some(id) = milestone_id {
-        fields.push_str(&format!("<milestone-id>{}</milestone-id>", id));
-    }
-    if let Some(t) = tags {
-        fields.push_str(&format!("<tags>{}</tags>", t));
-    }
-    let body = format!("<ticket>{}</ticket>", fields);
+    let body = to_xml(&NewTicket {
+        summary: summary.to_string(),
+        ticket_type: ticket_type.to_string(),
+        priority_id,
+        status_id,
+        description: description.map(str::to_string),
+        assignee_id,
+        category_id,
+        milestone_id,
+        tags: tags.map(str::to_string),
+    })?;
     let xml = client.post(&format!("/{}/tickets", project), body).await?;
-    let ticket: Ticket = from_str(&xml)?;
+    let ticket: Ticket = from_xml(&xml)?;
     Ok(ticket)
 }
 
@@ -74,7 +89,7 @@ pub async fn list_ticket_notes(
     let xml = client
         .get(&format!("/{}/tickets/{}/notes", project, ticket_id))
         .await?;
-    let notes: TicketNotes = from_str(&xml)?;
+    let notes: TicketNotes = from_xml(&xml)?;
     Ok(notes.notes)
 }
 
@@ -86,40 +101,15 @@ pub async fn create_ticket_note(
     changes: Option<&NoteChanges>,
     private: bool,
 ) -> Result<TicketNote> {
-    let mut fields = String::new();
-    if let Some(c) = content {
-        fields.push_str(&format!("<content><![CDATA[{}]]></content>", c));
-    }
-    if private {
-        fields.push_str("<private>1</private>");
-    }
-    if let Some(ch) = changes {
-        let mut ch_fields = String::new();
-        if let Some(id) = ch.status_id {
-            ch_fields.push_str(&format!("<status-id>{}</status-id>", id));
-        }
-        if let Some(id) = ch.priority_id {
-            ch_fields.push_str(&format!("<priority-id>{}</priority-id>", id));
-        }
-        if let Some(id) = ch.category_id {
-            ch_fields.push_str(&format!("<category-id>{}</category-id>", id));
-        }
-        if let Some(id) = ch.assignee_id {
-            ch_fields.push_str(&format!("<assignee-id>{}</assignee-id>", id));
-        }
-        if let Some(id) = ch.milestone_id {
-            ch_fields.push_str(&format!("<milestone-id>{}</milestone-id>", id));
-        }
-        if let Some(ref s) = ch.subject {
-            ch_fields.push_str(&format!("<subject>{}</subject>", s));
-        }
-        fields.push_str(&format!("<changes>{}</changes>", ch_fields));
-    }
-    let body = format!("<ticket-note>{}</ticket-note>", fields);
+    let body = to_xml(&NewTicketNote {
+        content: content.map(str::to_string),
+        private,
+        changes: changes.cloned(),
+    })?;
     let xml = client
         .post(&format!("/{}/tickets/{}/notes", project, ticket_id), body)
         .await?;
-    let note: TicketNote = from_str(&xml)?;
+    let note: TicketNote = from_xml(&xml)?;
     Ok(note)
 }
 
@@ -131,7 +121,7 @@ pub async fn list_watchers(
     let xml = client
         .get(&format!("/{}/tickets/{}/watchers", project, ticket_id))
         .await?;
-    let watchers: Watchers = from_str(&xml)?;
+    let watchers: Watchers = from_xml(&xml)?;
     Ok(watchers.watchers)
 }
 
@@ -141,11 +131,9 @@ pub async fn set_watchers(
     ticket_id: i64,
     user_ids: &[i64],
 ) -> Result<()> {
-    let watchers_xml: String = user_ids
-        .iter()
-        .map(|id| format!("<watcher>{}</watcher>", id))
-        .collect();
-    let body = format!("<watchers>{}</watchers>", watchers_xml);
+    let body = to_xml(&WatchersRequest {
+        watchers: user_ids.to_vec(),
+    })?;
     client
         .post(
             &format!("/{}/tickets/{}/watchers", project, ticket_id),
@@ -157,9 +145,9 @@ pub async fn set_watchers(
 
 pub async fn list_statuses(client: &CodebaseClient, project: &str) -> Result<Vec<TicketStatus>> {
     let xml = client
-        .get(&format!("/{}/tickets/statuses", project))
+        .get_cached(&format!("/{}/tickets/statuses", project), METADATA_TTL)
         .await?;
-    let statuses: TicketStatuses = from_str(&xml)?;
+    let statuses: TicketStatuses = from_xml(&xml)?;
     Ok(statuses.statuses)
 }
 
@@ -168,9 +156,9 @@ pub async fn list_priorities(
     project: &str,
 ) -> Result<Vec<TicketPriority>> {
     let xml = client
-        .get(&format!("/{}/tickets/priorities", project))
+        .get_cached(&format!("/{}/tickets/priorities", project), METADATA_TTL)
         .await?;
-    let priorities: TicketPriorities = from_str(&xml)?;
+    let priorities: TicketPriorities = from_xml(&xml)?;
     Ok(priorities.priorities)
 }
 
@@ -179,15 +167,17 @@ pub async fn list_categories(
     project: &str,
 ) -> Result<Vec<TicketCategory>> {
     let xml = client
-        .get(&format!("/{}/tickets/categories", project))
+        .get_cached(&format!("/{}/tickets/categories", project), METADATA_TTL)
         .await?;
-    let categories: TicketCategories = from_str(&xml)?;
+    let categories: TicketCategories = from_xml(&xml)?;
     Ok(categories.categories)
 }
 
 pub async fn list_types(client: &CodebaseClient, project: &str) -> Result<Vec<TicketType>> {
-    let xml = client.get(&format!("/{}/tickets/types", project)).await?;
-    let types: TicketTypes = from_str(&xml)?;
+    let xml = client
+        .get_cached(&format!("/{}/tickets/types", project), METADATA_TTL)
+        .await?;
+    let types: TicketTypes = from_xml(&xml)?;
     Ok(types.types)
 }
 
@@ -0,0 +1,196 @@
+//! How a [`CodebaseClient`](super::client::CodebaseClient) sends requests.
+//!
+//! The client handles everything above the wire — retries, rate limiting,
+//! caching, error mapping — and hands each attempt to a [`Transport`]. The
+//! default is [`ReqwestTransport`]; programs embedding this crate can plug
+//! in their own with [`CodebaseClientBuilder::transport`], or use the
+//! in-memory `FakeTransport` from the `test-util` feature to unit-test code
+//! that calls `api::*` without a server.
+//!
+//! [`CodebaseClientBuilder::transport`]: super::client::CodebaseClientBuilder::transport
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// One attempt of a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportRequest {
+    pub method: Method,
+    /// The full URL: the client's base URL followed by `path`.
+    pub url: String,
+    /// The path below the base URL, including any query string.
+    pub path: String,
+    /// API username (`account/username`) for Basic authentication.
+    pub username: String,
+    pub api_key: String,
+    /// XML request body.
+    pub body: Option<String>,
+}
+
+/// The response to one attempt, whatever its status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// A response with no headers.
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Why a request got no response; decides whether it is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportErrorKind {
+    /// The connection couldn't be established, so the server never saw
+    /// the request.
+    Connect,
+    /// No response arrived in time.
+    Timeout,
+    Other,
+}
+
+/// A request that got no response, from any [`Transport`].
+#[derive(Debug, thiserror::Error)]
+#[error("{source}")]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    #[source]
+    source: Box<dyn StdError + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn StdError + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    /// A connection failure, e.g. to simulate a server that is down.
+    pub fn connect(source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Connect, source)
+    }
+
+    /// A timeout, e.g. to simulate a server that doesn't answer.
+    pub fn timeout(source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Timeout, source)
+    }
+
+    /// Any other failure; not retried.
+    pub fn other(source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::new(TransportErrorKind::Other, source)
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    pub fn is_connect(&self) -> bool {
+        self.kind == TransportErrorKind::Connect
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.kind == TransportErrorKind::Timeout
+    }
+
+    /// The HTTP status, for errors raised after a response started.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.source
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(e: reqwest::Error) -> Self {
+        let kind = if e.is_connect() {
+            TransportErrorKind::Connect
+        } else if e.is_timeout() {
+            TransportErrorKind::Timeout
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, e)
+    }
+}
+
+impl From<io::Error> for TransportError {
+    fn from(e: io::Error) -> Self {
+        let kind = match e.kind() {
+            io::ErrorKind::TimedOut => TransportErrorKind::Timeout,
+            io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected => TransportErrorKind::Connect,
+            _ => TransportErrorKind::Other,
+        };
+        Self::new(kind, e)
+    }
+}
+
+/// Sends requests for a client. Any response, whatever its status, is
+/// `Ok`; errors are for requests that got no response at all.
+pub trait Transport: fmt::Debug + Send + Sync {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, TransportError>>;
+}
+
+/// Sends requests over HTTP with `reqwest`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http: Client) -> Self {
+        Self { http }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> BoxFuture<'_, Result<TransportResponse, TransportError>> {
+        let mut req = self
+            .http
+            .request(request.method, &request.url)
+            .basic_auth(&request.username, Some(&request.api_key))
+            .header("Accept", "application/xml")
+            .header("Content-Type", "application/xml");
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+        Box::pin(async move {
+            let resp = req.send().await?;
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let body = resp.text().await?;
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::Parser;
+use codebase_cli::mock_server::{MockServer, Store};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
+
+#[derive(Parser)]
+#[command(
+    name = "cb-mock-server",
+    version,
+    about = "Serve a local mock of the CodebaseHQ API to run cb against",
+    after_help = "\
+EXAMPLES:
+    cb-mock-server
+    cb-mock-server --fixture fixtures/mock-server.json --listen 127.0.0.1:8080
+
+    CB_API_URL=http://127.0.0.1:4000 CB_API_USERNAME=acme/alice CB_API_KEY=x \\
+        cb ticket list widgets
+
+FIXTURES:
+    A fixture is a JSON or YAML file with optional users, project-groups,
+    projects and activity lists. Each project holds its own fields plus
+    tickets (with notes and watchers), milestones, repositories (with
+    commits, branches, files, hooks and merge-requests), assignments and
+    ticket statuses, priorities, categories and types. Fields use the API's
+    element names, e.g. ticket-id. See fixtures/mock-server.json.
+
+    Creates and updates are kept in memory only. Any credentials are
+    accepted; the user is the one whose username follows the / of the API
+    username."
+)]
+struct Args {
+    /// Fixture file to seed the store with (default: built-in sample data)
+    #[arg(long, short, value_name = "FILE")]
+    fixture: Option<PathBuf>,
+
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:4000")]
+    listen: SocketAddr,
+
+    /// Don't log requests
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if !args.quiet {
+        tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(io::stderr)
+                    .with_target(false),
+            )
+            .with(Targets::new().with_target("codebase_cli", Level::INFO))
+            .init();
+    }
+
+    let store = match &args.fixture {
+        Some(path) => Store::load(path)?,
+        None => Store::sample(),
+    };
+    let server = MockServer::bind(args.listen, store).await?;
+    eprintln!("Mock CodebaseHQ API listening on {}", server.url());
+    eprintln!("Point cb at it with CB_API_URL={}", server.url());
+
+    tokio::select! {
+        result = server.run() => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    Ok(())
+}
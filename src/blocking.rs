@@ -0,0 +1,350 @@
+//! A synchronous client, enabled by the `blocking` feature.
+//!
+//! [`CodebaseClient`] has a method for each async `api::*` function, with the
+//! same name and arguments minus the client, and runs it to completion on a
+//! runtime of its own:
+//!
+//! ```ignore
+//! let client = blocking::CodebaseClient::new(account, username, api_key)?;
+//! for ticket in client.paginate_tickets("my-project") {
+//!     println!("{}", ticket?.summary.unwrap_or_default());
+//! }
+//! ```
+//!
+//! Like `reqwest::blocking`, it must not be used from inside an async
+//! runtime; call the async functions there instead.
+
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use std::vec;
+
+use reqwest::Method;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::api::error::Result;
+use crate::api::models::*;
+use crate::api::{self, client, pagination};
+
+/// A blocking wrapper around [`api::client::CodebaseClient`]. Clones share
+/// the async client's state and the runtime.
+#[derive(Debug, Clone)]
+pub struct CodebaseClient {
+    inner: client::CodebaseClient,
+    runtime: Arc<Runtime>,
+}
+
+impl TryFrom<client::CodebaseClient> for CodebaseClient {
+    type Error = io::Error;
+
+    /// Wrap a configured async client, e.g. one from
+    /// [`client::CodebaseClient::builder`]. Fails if the runtime can't be
+    /// started.
+    fn try_from(inner: client::CodebaseClient) -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+/// One blocking method per async API function.
+macro_rules! blocking {
+    ($($module:ident::$name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!(
+                "Blocking [`", stringify!($module), "::", stringify!($name), "`](crate::api::",
+                stringify!($module), "::", stringify!($name), ")."
+            )]
+            pub fn $name(&self, $($arg: $ty),*) -> Result<$ret> {
+                self.block_on(api::$module::$name(&self.inner, $($arg),*))
+            }
+        )*
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+impl CodebaseClient {
+    pub fn new(account: String, username: String, api_key: String) -> io::Result<Self> {
+        client::CodebaseClient::new(account, username, api_key).try_into()
+    }
+
+    /// Create a client with a custom base URL (for testing with mock servers).
+    pub fn with_base_url(
+        base_url: String,
+        account: String,
+        username: String,
+        api_key: String,
+    ) -> io::Result<Self> {
+        client::CodebaseClient::with_base_url(base_url, account, username, api_key).try_into()
+    }
+
+    /// The async client underneath.
+    pub fn as_async(&self) -> &client::CodebaseClient {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    fn pager<T>(&self, inner: pagination::Paginator<T>) -> Paginator<T> {
+        Paginator {
+            inner,
+            runtime: self.runtime.clone(),
+            page: Vec::new().into_iter(),
+            failed: false,
+        }
+    }
+
+    blocking! {
+        projects::list_projects() -> Vec<Project>;
+        projects::show_project(permalink: &str) -> Project;
+        projects::create_project(name: &str) -> Project;
+        projects::update_project(
+            project_id: &str,
+            name: Option<&str>,
+            status: Option<&str>
+        ) -> Project;
+        projects::delete_project(permalink: &str) -> ();
+        projects::list_project_groups() -> Vec<ProjectGroup>;
+        projects::list_project_users(project: &str) -> Vec<ProjectUser>;
+        projects::assign_project_users(project: &str, user_ids: &[i64]) -> ();
+
+        tickets::list_tickets(project: &str) -> Vec<Ticket>;
+        tickets::show_ticket(project: &str, ticket_id: i64) -> Ticket;
+        tickets::search_tickets(project: &str, query: &str) -> Vec<Ticket>;
+        tickets::create_ticket(
+            project: &str,
+            summary: &str,
+            ticket_type: &str,
+            priority_id: Option<i64>,
+            status_id: Option<i64>,
+            description: Option<&str>,
+            assignee_id: Option<i64>,
+            category_id: Option<i64>,
+            milestone_id: Option<i64>,
+            tags: Option<&str>
+        ) -> Ticket;
+        tickets::list_ticket_notes(project: &str, ticket_id: i64) -> Vec<TicketNote>;
+        tickets::create_ticket_note(
+            project: &str,
+            ticket_id: i64,
+            content: Option<&str>,
+            changes: Option<&NoteChanges>,
+            private: bool
+        ) -> TicketNote;
+        tickets::list_watchers(project: &str, ticket_id: i64) -> Vec<Watcher>;
+        tickets::set_watchers(project: &str, ticket_id: i64, user_ids: &[i64]) -> ();
+        tickets::list_statuses(project: &str) -> Vec<TicketStatus>;
+        tickets::list_priorities(project: &str) -> Vec<TicketPriority>;
+        tickets::list_categories(project: &str) -> Vec<TicketCategory>;
+        tickets::list_types(project: &str) -> Vec<TicketType>;
+
+        milestones::list_milestones(project: &str) -> Vec<Milestone>;
+        milestones::create_milestone(
+            project: &str,
+            name: &str,
+            description: Option<&str>,
+            start_at: Option<&str>,
+            deadline: Option<&str>,
+            responsible_user_id: Option<i64>,
+            parent_id: Option<i64>,
+            status: Option<&str>
+        ) -> Milestone;
+        milestones::update_milestone(
+            project: &str,
+            milestone_id: i64,
+            name: Option<&str>,
+            description: Option<&str>,
+            start_at: Option<&str>,
+            deadline: Option<&str>,
+            responsible_user_id: Option<i64>,
+            parent_id: Option<i64>,
+            status: Option<&str>
+        ) -> Milestone;
+
+        repositories::list_repositories(project: &str) -> Vec<Repository>;
+        repositories::show_repository(project: &str, repo: &str) -> Repository;
+        repositories::create_repository(project: &str, name: &str, scm: &str) -> Repository;
+        repositories::delete_repository(project: &str, repo: &str) -> ();
+        repositories::list_commits(project: &str, repo: &str, git_ref: &str) -> Vec<Commit>;
+        repositories::list_commits_path(
+            project: &str,
+            repo: &str,
+            git_ref: &str,
+            path: &str
+        ) -> Vec<Commit>;
+        repositories::create_deployment(
+            project: &str,
+            repo: &str,
+            branch: &str,
+            revision: &str,
+            servers: &str,
+            environment: Option<&str>
+        ) -> ();
+        repositories::get_file(project: &str, repo: &str, git_ref: &str, path: &str) -> String;
+        repositories::list_hooks(project: &str, repo: &str) -> Vec<Hook>;
+        repositories::create_hook(
+            project: &str,
+            repo: &str,
+            url: &str,
+            username: Option<&str>,
+            password: Option<&str>
+        ) -> Hook;
+        repositories::list_branches(project: &str, repo: &str) -> Vec<Branch>;
+        repositories::list_merge_requests(project: &str, repo: &str) -> Vec<MergeRequest>;
+        repositories::show_merge_request(project: &str, repo: &str, mr_id: i64) -> MergeRequest;
+        repositories::create_merge_request(
+            project: &str,
+            repo: &str,
+            source_ref: &str,
+            target_ref: &str,
+            subject: &str
+        ) -> MergeRequest;
+        repositories::comment_merge_request(
+            project: &str,
+            repo: &str,
+            mr_id: i64,
+            content: &str
+        ) -> ();
+        repositories::close_merge_request(project: &str, repo: &str, mr_id: i64) -> ();
+        repositories::reopen_merge_request(project: &str, repo: &str, mr_id: i64) -> ();
+        repositories::merge_merge_request(project: &str, repo: &str, mr_id: i64) -> ();
+        repositories::reassign_merge_request(
+            project: &str,
+            repo: &str,
+            mr_id: i64,
+            user_id: i64
+        ) -> ();
+
+        activity::account_activity(
+            raw: bool,
+            since: Option<&str>,
+            page: Option<u32>
+        ) -> Vec<Event>;
+        activity::project_activity(
+            project: &str,
+            raw: bool,
+            since: Option<&str>,
+            page: Option<u32>
+        ) -> Vec<Event>;
+
+        raw::request(method: Method, path: &str, body: Option<String>) -> String;
+    }
+
+    /// Blocking [`tickets::paginate_tickets`](crate::api::tickets::paginate_tickets).
+    pub fn paginate_tickets(&self, project: &str) -> Paginator<Ticket> {
+        self.pager(api::tickets::paginate_tickets(&self.inner, project))
+    }
+
+    /// Blocking [`tickets::paginate_search_tickets`](crate::api::tickets::paginate_search_tickets).
+    pub fn paginate_search_tickets(&self, project: &str, query: &str) -> Paginator<Ticket> {
+        self.pager(api::tickets::paginate_search_tickets(
+            &self.inner,
+            project,
+            query,
+        ))
+    }
+
+    /// Blocking [`repositories::paginate_commits`](crate::api::repositories::paginate_commits).
+    pub fn paginate_commits(
+        &self,
+        project: &str,
+        repo: &str,
+        git_ref: &str,
+        path: Option<&str>,
+    ) -> Paginator<Commit> {
+        self.pager(api::repositories::paginate_commits(
+            &self.inner,
+            project,
+            repo,
+            git_ref,
+            path,
+        ))
+    }
+
+    /// Blocking [`activity::paginate_account_activity`](crate::api::activity::paginate_account_activity).
+    pub fn paginate_account_activity(&self, raw: bool, since: Option<&str>) -> Paginator<Event> {
+        self.pager(api::activity::paginate_account_activity(
+            &self.inner,
+            raw,
+            since,
+        ))
+    }
+
+    /// Blocking [`activity::paginate_project_activity`](crate::api::activity::paginate_project_activity).
+    pub fn paginate_project_activity(
+        &self,
+        project: &str,
+        raw: bool,
+        since: Option<&str>,
+    ) -> Paginator<Event> {
+        self.pager(api::activity::paginate_project_activity(
+            &self.inner,
+            project,
+            raw,
+            since,
+        ))
+    }
+}
+
+/// A blocking [`pagination::Paginator`]. Iterating yields individual items,
+/// fetching pages as needed, and stops after the first error.
+pub struct Paginator<T> {
+    inner: pagination::Paginator<T>,
+    runtime: Arc<Runtime>,
+    /// Items of the last fetched page not yet yielded by the iterator.
+    page: vec::IntoIter<T>,
+    failed: bool,
+}
+
+impl<T> Paginator<T> {
+    /// Start from the given page instead of the first one.
+    pub fn start_page(mut self, page: u32) -> Self {
+        self.inner = self.inner.start_page(page);
+        self
+    }
+
+    /// Stop once `limit` items have been returned in total.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.inner = self.inner.limit(limit);
+        self
+    }
+
+    /// Fetch the next page, or `None` once the listing is exhausted.
+    pub fn next_page(&mut self) -> Result<Option<Vec<T>>> {
+        self.runtime.block_on(self.inner.next_page())
+    }
+
+    /// Fetch every remaining page and concatenate the results.
+    pub fn collect_all(self) -> Result<Vec<T>> {
+        let mut all: Vec<T> = self.page.collect();
+        all.extend(self.runtime.block_on(self.inner.collect_all())?);
+        Ok(all)
+    }
+}
+
+impl<T> Iterator for Paginator<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.page.next() {
+                return Some(Ok(item));
+            }
+            if self.failed {
+                return None;
+            }
+            match self.next_page() {
+                Ok(Some(items)) => self.page = items.into_iter(),
+                Ok(None) => return None,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,13 @@
-use anyhow::Result;
 use clap::Subcommand;
-use colored::Colorize;
 
 use crate::api::activity;
 use crate::api::client::CodebaseClient;
-use crate::output;
+use crate::api::error::Result;
+use crate::api::models::Event;
+use crate::commands::PageArgs;
+use crate::output::{self, OutputOptions};
 
+/// The `cb activity` subcommands.
 #[derive(Subcommand)]
 pub enum ActivityCommands {
     /// Show account-wide activity feed
@@ -16,9 +18,11 @@ pub enum ActivityCommands {
         /// Filter events since timestamp (YYYY-MM-DD HH:MM:SS +TZ)
         #[arg(long)]
         since: Option<String>,
-        /// Page number
+        /// Page number (the first page fetched when combined with --all/--limit)
         #[arg(long)]
         page: Option<u32>,
+        #[command(flatten)]
+        pages: PageArgs,
     },
     /// Show project-specific activity feed
     Project {
@@ -30,43 +34,100 @@ pub enum ActivityCommands {
         /// Filter events since timestamp
         #[arg(long)]
         since: Option<String>,
-        /// Page number
+        /// Page number (the first page fetched when combined with --all/--limit)
         #[arg(long)]
         page: Option<u32>,
+        #[command(flatten)]
+        pages: PageArgs,
     },
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: ActivityCommands, json: bool) -> Result<()> {
+/// An activity feed request, as [`run`] takes it.
+#[derive(Debug, Clone)]
+pub enum ActivityCommand {
+    /// The account-wide feed.
+    Account {
+        raw: bool,
+        /// Only events since this time (`YYYY-MM-DD HH:MM:SS +TZ`).
+        since: Option<String>,
+        /// The first page to fetch; 1 when `None`.
+        page: Option<u32>,
+        pages: PageArgs,
+    },
+    /// The feed of one project.
+    Project {
+        project: String,
+        raw: bool,
+        since: Option<String>,
+        page: Option<u32>,
+        pages: PageArgs,
+    },
+}
+
+impl From<ActivityCommands> for ActivityCommand {
+    fn from(cmd: ActivityCommands) -> Self {
+        match cmd {
+            ActivityCommands::Account {
+                raw,
+                since,
+                page,
+                pages,
+            } => ActivityCommand::Account {
+                raw,
+                since,
+                page,
+                pages,
+            },
+            ActivityCommands::Project {
+                project,
+                raw,
+                since,
+                page,
+                pages,
+            } => ActivityCommand::Project {
+                project,
+                raw,
+                since,
+                page,
+                pages,
+            },
+        }
+    }
+}
+
+/// Run `cmd` and return the events it fetched.
+pub async fn run(client: &CodebaseClient, cmd: ActivityCommand) -> Result<Vec<Event>> {
     match cmd {
-        ActivityCommands::Account { raw, since, page } => {
-            let events = activity::account_activity(client, raw, since.as_deref(), page).await?;
-            output::print_list(json, &events, |events| {
-                print_events(events);
-            })?;
+        ActivityCommand::Account {
+            raw,
+            since,
+            page,
+            pages,
+        } => {
+            let pager = activity::paginate_account_activity(client, raw, since.as_deref())
+                .start_page(page.unwrap_or(1));
+            pages.fetch(pager).await
         }
-        ActivityCommands::Project {
+        ActivityCommand::Project {
             project,
             raw,
             since,
             page,
+            pages,
         } => {
-            let events =
-                activity::project_activity(client, &project, raw, since.as_deref(), page).await?;
-            output::print_list(json, &events, |events| {
-                print_events(events);
-            })?;
+            let pager =
+                activity::paginate_project_activity(client, &project, raw, since.as_deref())
+                    .start_page(page.unwrap_or(1));
+            pages.fetch(pager).await
         }
     }
-    Ok(())
 }
 
-fn print_events(events: &[crate::api::models::Event]) {
-    for e in events {
-        println!(
-            "[{}] {} — {}",
-            e.event_type.as_deref().unwrap_or("unknown").cyan(),
-            e.timestamp.as_deref().unwrap_or("").dimmed(),
-            e.title.as_deref().unwrap_or("")
-        );
-    }
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: ActivityCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    let events = run(client, cmd.into()).await?;
+    output::print_list(out, &events)
 }
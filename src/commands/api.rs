@@ -0,0 +1,87 @@
+use clap::Args;
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::client::CodebaseClient;
+use crate::api::error::{CodebaseError, Result};
+use crate::api::raw;
+use crate::compose;
+use crate::output::{self, OutputOptions};
+
+/// The arguments of `cb api`, also taken as they are by [`run`].
+#[derive(Args, Debug, Clone, Default)]
+pub struct ApiArgs {
+    /// HTTP method: GET, POST, PUT, DELETE, ...
+    pub method: String,
+    /// Path below the API base URL, e.g. /my-project/tickets/42/notes
+    pub path: String,
+    /// Request body as XML, or @FILE to read it from a file (@- for stdin)
+    #[arg(long, short = 'd', conflicts_with = "fields")]
+    pub data: Option<String>,
+    /// A key=value parameter (repeatable). Sent as the query string for GET,
+    /// otherwise as an XML body; nest with brackets: ticket[summary]=Hi
+    #[arg(long = "field", short = 'f', value_name = "KEY=VALUE", value_parser = raw::parse_field)]
+    pub fields: Vec<(String, String)>,
+}
+
+/// The body of a raw API response, as XML.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiResponse {
+    pub body: String,
+}
+
+/// Send the request described by `args` and return the response body.
+pub async fn run(client: &CodebaseClient, args: ApiArgs) -> Result<ApiResponse> {
+    let method = Method::from_bytes(args.method.to_uppercase().as_bytes())
+        .map_err(|_| CodebaseError::Input(format!("Invalid HTTP method '{}'", args.method)))?;
+
+    let (path, body) = match args.data {
+        Some(data) => {
+            let body = match data.strip_prefix('@') {
+                Some(file) => compose::read_input(file).map_err(CodebaseError::input)?,
+                None => data,
+            };
+            (args.path, Some(body))
+        }
+        None if args.fields.is_empty() => (args.path, None),
+        None if method == Method::GET || method == Method::HEAD => {
+            (raw::with_query(&args.path, &args.fields), None)
+        }
+        None => {
+            let body = raw::fields_to_xml(&args.fields).map_err(CodebaseError::Input)?;
+            (args.path, Some(body))
+        }
+    };
+
+    let body = raw::request(client, method, &path, body).await?;
+    Ok(ApiResponse { body })
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    args: ApiArgs,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    run(client, args).await?.print(out)
+}
+
+impl ApiResponse {
+    /// The body converted to JSON, as `cb api --json` prints it.
+    pub fn json(&self) -> Result<Value> {
+        raw::xml_to_json(&self.body)
+    }
+
+    /// Print the XML as-is for table output, otherwise as converted JSON.
+    /// An empty body prints nothing.
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        if self.body.trim().is_empty() {
+            return Ok(());
+        }
+        if !out.prints_data() {
+            println!("{}", self.body.trim_end());
+            return Ok(());
+        }
+        output::print_output(out, &self.json()?, || {})
+    }
+}
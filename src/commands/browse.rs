@@ -1,9 +1,11 @@
-use anyhow::Result;
-
 use crate::api::config::Config;
+use crate::api::error::{CodebaseError, Result};
 use crate::git_context;
 
-pub fn execute(config: &Config, project: Option<String>, target: Option<String>) -> Result<()> {
+/// The web page for `project` (detected from the git remote if not given),
+/// or for a ticket number or repository permalink within it. Nothing is
+/// opened; `execute` does that.
+pub fn run(config: &Config, project: Option<String>, target: Option<String>) -> Result<String> {
     let account = config.account();
 
     let project = match project {
@@ -11,9 +13,10 @@ pub fn execute(config: &Config, project: Option<String>, target: Option<String>)
         None => git_context::detect()
             .map(|ctx| ctx.project)
             .ok_or_else(|| {
-                anyhow::anyhow!(
+                CodebaseError::Input(
                     "No project specified and could not detect from git remote.\n\
                      Usage: cb browse <project> [target]"
+                        .to_string(),
                 )
             })?,
     };
@@ -38,7 +41,15 @@ pub fn execute(config: &Config, project: Option<String>, target: Option<String>)
             format!("https://{}.codebasehq.com/projects/{}", account, project)
         }
     };
+    Ok(url)
+}
 
+pub fn execute(
+    config: &Config,
+    project: Option<String>,
+    target: Option<String>,
+) -> anyhow::Result<()> {
+    let url = run(config, project, target)?;
     println!("Opening {}", url);
     open::that(&url)?;
     Ok(())
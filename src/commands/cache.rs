@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use serde::Serialize;
+
+use crate::api::cache::ResponseCache;
+use crate::api::config::Config;
+use crate::api::error::{CodebaseError, Result};
+
+/// The `cb cache` subcommands.
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Delete every cached response, for all profiles
+    Clear,
+}
+
+/// A cache command, as [`run`] takes it.
+#[derive(Debug, Clone, Copy)]
+pub enum CacheCommand {
+    /// Delete every cached response, for all profiles.
+    Clear,
+}
+
+impl From<CacheCommands> for CacheCommand {
+    fn from(cmd: CacheCommands) -> Self {
+        match cmd {
+            CacheCommands::Clear => CacheCommand::Clear,
+        }
+    }
+}
+
+/// What a cache command did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheOutput {
+    Cleared { dir: PathBuf },
+}
+
+/// Run `cmd` and return its result without printing anything.
+pub fn run(cmd: CacheCommand) -> Result<CacheOutput> {
+    match cmd {
+        CacheCommand::Clear => {
+            let dir = Config::cache_dir().map_err(|e| CodebaseError::Cache(format!("{:#}", e)))?;
+            ResponseCache::new(&dir).clear().map_err(|e| {
+                CodebaseError::Cache(format!("Failed to remove {}: {}", dir.display(), e))
+            })?;
+            Ok(CacheOutput::Cleared { dir })
+        }
+    }
+}
+
+pub fn execute(cmd: CacheCommands) -> anyhow::Result<()> {
+    match run(cmd.into())? {
+        CacheOutput::Cleared { dir } => println!("Cleared cache at {}", dir.display()),
+    }
+    Ok(())
+}
@@ -1,11 +1,14 @@
-use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
+use serde::Serialize;
 
 use crate::api::client::CodebaseClient;
+use crate::api::error::Result;
 use crate::api::milestones;
-use crate::output;
+use crate::api::models::Milestone;
+use crate::output::{self, OutputOptions};
 
+/// The `cb milestone` subcommands.
 #[derive(Subcommand)]
 pub enum MilestoneCommands {
     /// List milestones for a project
@@ -68,25 +71,103 @@ pub enum MilestoneCommands {
     },
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: MilestoneCommands, json: bool) -> Result<()> {
-    match cmd {
-        MilestoneCommands::List { project } => {
-            let milestone_list = milestones::list_milestones(client, &project).await?;
-            output::print_list(json, &milestone_list, |milestones| {
-                for m in milestones {
-                    let status = output::colorize_status(m.status.as_deref().unwrap_or("unknown"));
-                    println!(
-                        "{}: {} [{}] ({} -> {})",
-                        m.id.unwrap_or(0),
-                        m.name.as_deref().unwrap_or("").bold(),
-                        status,
-                        m.start_at.as_deref().unwrap_or("").dimmed(),
-                        m.deadline.as_deref().unwrap_or("").dimmed()
-                    );
-                }
-            })?;
+/// A milestone command, as [`run`] takes it.
+#[derive(Debug, Clone)]
+pub enum MilestoneCommand {
+    List {
+        project: String,
+    },
+    Create {
+        project: String,
+        name: String,
+        description: Option<String>,
+        /// `yyyy-mm-dd`
+        start_at: Option<String>,
+        /// `yyyy-mm-dd`
+        deadline: Option<String>,
+        responsible_user_id: Option<i64>,
+        parent_id: Option<i64>,
+        /// active, completed or cancelled
+        status: Option<String>,
+    },
+    /// Change the fields that are `Some`.
+    Update {
+        project: String,
+        milestone_id: i64,
+        name: Option<String>,
+        description: Option<String>,
+        start_at: Option<String>,
+        deadline: Option<String>,
+        responsible_user_id: Option<i64>,
+        parent_id: Option<i64>,
+        status: Option<String>,
+    },
+}
+
+impl From<MilestoneCommands> for MilestoneCommand {
+    fn from(cmd: MilestoneCommands) -> Self {
+        match cmd {
+            MilestoneCommands::List { project } => MilestoneCommand::List { project },
+            MilestoneCommands::Create {
+                project,
+                name,
+                description,
+                start_at,
+                deadline,
+                responsible_user_id,
+                parent_id,
+                status,
+            } => MilestoneCommand::Create {
+                project,
+                name,
+                description,
+                start_at,
+                deadline,
+                responsible_user_id,
+                parent_id,
+                status,
+            },
+            MilestoneCommands::Update {
+                project,
+                milestone_id,
+                name,
+                description,
+                start_at,
+                deadline,
+                responsible_user_id,
+                parent_id,
+                status,
+            } => MilestoneCommand::Update {
+                project,
+                milestone_id,
+                name,
+                description,
+                start_at,
+                deadline,
+                responsible_user_id,
+                parent_id,
+                status,
+            },
         }
-        MilestoneCommands::Create {
+    }
+}
+
+/// What a milestone command did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneOutput {
+    Milestones(Vec<Milestone>),
+    Created(Milestone),
+    Updated(Milestone),
+}
+
+/// Run `cmd` and return its result without printing anything.
+pub async fn run(client: &CodebaseClient, cmd: MilestoneCommand) -> Result<MilestoneOutput> {
+    Ok(match cmd {
+        MilestoneCommand::List { project } => {
+            MilestoneOutput::Milestones(milestones::list_milestones(client, &project).await?)
+        }
+        MilestoneCommand::Create {
             project,
             name,
             description,
@@ -108,15 +189,9 @@ pub async fn execute(client: &CodebaseClient, cmd: MilestoneCommands, json: bool
                 status.as_deref(),
             )
             .await?;
-            output::print_output(json, &m, || {
-                println!(
-                    "Created milestone {}: {}",
-                    m.id.unwrap_or(0),
-                    m.name.as_deref().unwrap_or("").bold()
-                );
-            })?;
+            MilestoneOutput::Created(m)
         }
-        MilestoneCommands::Update {
+        MilestoneCommand::Update {
             project,
             milestone_id,
             name,
@@ -140,14 +215,37 @@ pub async fn execute(client: &CodebaseClient, cmd: MilestoneCommands, json: bool
                 status.as_deref(),
             )
             .await?;
-            output::print_output(json, &m, || {
+            MilestoneOutput::Updated(m)
+        }
+    })
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: MilestoneCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    run(client, cmd.into()).await?.print(out)
+}
+
+impl MilestoneOutput {
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        match self {
+            MilestoneOutput::Milestones(list) => output::print_list(out, list),
+            MilestoneOutput::Created(m) => output::print_output(out, m, || {
+                println!(
+                    "Created milestone {}: {}",
+                    m.id.unwrap_or(0),
+                    m.name.as_deref().unwrap_or("").bold()
+                );
+            }),
+            MilestoneOutput::Updated(m) => output::print_output(out, m, || {
                 println!(
                     "Updated milestone {}: {}",
                     m.id.unwrap_or(0),
                     m.name.as_deref().unwrap_or("").bold()
                 );
-            })?;
+            }),
         }
     }
-    Ok(())
 }
@@ -1,8 +1,48 @@
+//! The `cb` subcommands, usable as a library.
+//!
+//! Each module's `run` performs a command and returns what it did as data,
+//! without printing; the result's `print` renders it the way `cb` does, and
+//! `execute` does both. `run` takes a plain command type (e.g.
+//! [`tickets::TicketCommand`]) and fails with a
+//! [`CodebaseError`](crate::api::error::CodebaseError); `execute` takes the
+//! clap subcommand as parsed from the command line.
+
 pub mod activity;
+pub mod api;
 pub mod browse;
+pub mod cache;
 pub mod milestones;
 pub mod pr;
 pub mod projects;
 pub mod repositories;
 pub mod status;
 pub mod tickets;
+
+use clap::Args;
+
+use crate::api::error::Result;
+use crate::api::pagination::Paginator;
+
+/// `--all` / `--limit` flags shared by list commands backed by paged endpoints.
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct PageArgs {
+    /// Fetch every page instead of only the first
+    #[arg(long)]
+    pub all: bool,
+    /// Stop after this many results, fetching further pages as needed
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
+impl PageArgs {
+    /// Drain the paginator according to the flags; without either flag only
+    /// the first page is fetched, matching the API's default behaviour.
+    pub async fn fetch<T>(&self, mut pager: Paginator<T>) -> Result<Vec<T>> {
+        let items = match self.limit {
+            Some(limit) => pager.limit(limit).collect_all().await?,
+            None if self.all => pager.collect_all().await?,
+            None => pager.next_page().await?.unwrap_or_default(),
+        };
+        Ok(items)
+    }
+}
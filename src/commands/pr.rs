@@ -1,11 +1,12 @@
-use anyhow::Result;
 use clap::Subcommand;
-use colored::Colorize;
 
 use crate::api::client::CodebaseClient;
-use crate::api::repositories;
-use crate::output;
+use crate::api::error::{CodebaseError, Result};
+use crate::commands::repositories::{self, RepoCommand, RepoOutput};
+use crate::git_context::resolve_project_repo;
+use crate::output::OutputOptions;
 
+/// The `cb pr` subcommands.
 #[derive(Subcommand)]
 pub enum PrCommands {
     /// List merge requests
@@ -102,134 +103,251 @@ pub enum PrCommands {
     },
 }
 
-fn resolve(project: Option<String>, repo: Option<String>) -> Result<(String, String)> {
-    crate::resolve_project_repo(project, repo)
+/// A merge request command, as [`run`] takes it. The fields are those of the
+/// matching [`PrCommands`] variant; a `project` or `repo` of `None` is
+/// detected from the git remote of the current directory.
+#[derive(Debug, Clone)]
+pub enum PrCommand {
+    List {
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Show {
+        mr_id: i64,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Create {
+        source_ref: String,
+        target_ref: String,
+        subject: String,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Comment {
+        mr_id: i64,
+        content: String,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Merge {
+        mr_id: i64,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Close {
+        mr_id: i64,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Reopen {
+        mr_id: i64,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+    Reassign {
+        mr_id: i64,
+        user_id: i64,
+        project: Option<String>,
+        repo: Option<String>,
+    },
+}
+
+impl From<PrCommands> for PrCommand {
+    fn from(cmd: PrCommands) -> Self {
+        match cmd {
+            PrCommands::List { project, repo } => PrCommand::List { project, repo },
+            PrCommands::Show {
+                mr_id,
+                project,
+                repo,
+            } => PrCommand::Show {
+                mr_id,
+                project,
+                repo,
+            },
+            PrCommands::Create {
+                source_ref,
+                target_ref,
+                subject,
+                project,
+                repo,
+            } => PrCommand::Create {
+                source_ref,
+                target_ref,
+                subject,
+                project,
+                repo,
+            },
+            PrCommands::Comment {
+                mr_id,
+                content,
+                project,
+                repo,
+            } => PrCommand::Comment {
+                mr_id,
+                content,
+                project,
+                repo,
+            },
+            PrCommands::Merge {
+                mr_id,
+                project,
+                repo,
+            } => PrCommand::Merge {
+                mr_id,
+                project,
+                repo,
+            },
+            PrCommands::Close {
+                mr_id,
+                project,
+                repo,
+            } => PrCommand::Close {
+                mr_id,
+                project,
+                repo,
+            },
+            PrCommands::Reopen {
+                mr_id,
+                project,
+                repo,
+            } => PrCommand::Reopen {
+                mr_id,
+                project,
+                repo,
+            },
+            PrCommands::Reassign {
+                mr_id,
+                user_id,
+                project,
+                repo,
+            } => PrCommand::Reassign {
+                mr_id,
+                user_id,
+                project,
+                repo,
+            },
+        }
+    }
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: PrCommands, json: bool) -> Result<()> {
-    match cmd {
-        PrCommands::List { project, repo } => {
-            let (project, repo) = resolve(project, repo)?;
-            let mrs = repositories::list_merge_requests(client, &project, &repo).await?;
-            output::print_list(json, &mrs, |mrs| {
-                for mr in mrs {
-                    let status =
-                        output::colorize_mr_status(mr.status.as_deref().unwrap_or("unknown"));
-                    println!(
-                        "#{} [{}] {} ({} -> {})",
-                        mr.id.unwrap_or(0).to_string().bold(),
-                        status,
-                        mr.subject.as_deref().unwrap_or(""),
-                        mr.source_ref.as_deref().unwrap_or("").cyan(),
-                        mr.target_ref.as_deref().unwrap_or("").green(),
-                    );
-                }
-            })?;
+/// Run `cmd` as the equivalent `repo` command, with the project and
+/// repository detected from the git remote where not given.
+pub async fn run(client: &CodebaseClient, cmd: PrCommand) -> Result<RepoOutput> {
+    let cmd = match cmd {
+        PrCommand::List { project, repo } => {
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::MergeRequests { project, repo }
         }
-        PrCommands::Show {
+        PrCommand::Show {
             mr_id,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            let mr = repositories::show_merge_request(client, &project, &repo, mr_id).await?;
-            output::print_output(json, &mr, || {
-                let status = output::colorize_mr_status(mr.status.as_deref().unwrap_or("unknown"));
-                let can_merge = output::colorize_bool(mr.can_merge.unwrap_or(false), "yes", "no");
-                println!("ID:         {}", mr.id.unwrap_or(0).to_string().bold());
-                println!("Subject:    {}", mr.subject.as_deref().unwrap_or(""));
-                println!("Status:     {}", status);
-                println!(
-                    "Source:     {}",
-                    mr.source_ref.as_deref().unwrap_or("").cyan()
-                );
-                println!(
-                    "Target:     {}",
-                    mr.target_ref.as_deref().unwrap_or("").green()
-                );
-                println!("Can Merge:  {}", can_merge);
-                println!(
-                    "Created:    {}",
-                    mr.created_at.as_deref().unwrap_or("").dimmed()
-                );
-                println!(
-                    "Updated:    {}",
-                    mr.updated_at.as_deref().unwrap_or("").dimmed()
-                );
-            })?;
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::ShowMr {
+                project,
+                repo,
+                mr_id,
+            }
         }
-        PrCommands::Create {
+        PrCommand::Create {
             source_ref,
             target_ref,
             subject,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            let mr = repositories::create_merge_request(
-                client,
-                &project,
-                &repo,
-                &source_ref,
-                &target_ref,
-                &subject,
-            )
-            .await?;
-            output::print_output(json, &mr, || {
-                println!(
-                    "Created merge request #{}: {}",
-                    mr.id.unwrap_or(0).to_string().bold(),
-                    mr.subject.as_deref().unwrap_or("")
-                );
-            })?;
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::CreateMr {
+                project,
+                repo,
+                source_ref,
+                target_ref,
+                subject,
+            }
         }
-        PrCommands::Comment {
+        PrCommand::Comment {
             mr_id,
             content,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            repositories::comment_merge_request(client, &project, &repo, mr_id, &content).await?;
-            println!("Commented on merge request #{}", mr_id);
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::CommentMr {
+                project,
+                repo,
+                mr_id,
+                content,
+            }
         }
-        PrCommands::Merge {
+        PrCommand::Merge {
             mr_id,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            repositories::merge_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Merged merge request #{}", mr_id.to_string().bold());
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::Merge {
+                project,
+                repo,
+                mr_id,
+            }
         }
-        PrCommands::Close {
+        PrCommand::Close {
             mr_id,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            repositories::close_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Closed merge request #{}", mr_id);
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::CloseMr {
+                project,
+                repo,
+                mr_id,
+            }
         }
-        PrCommands::Reopen {
+        PrCommand::Reopen {
             mr_id,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            repositories::reopen_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Reopened merge request #{}", mr_id);
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::ReopenMr {
+                project,
+                repo,
+                mr_id,
+            }
         }
-        PrCommands::Reassign {
+        PrCommand::Reassign {
             mr_id,
             user_id,
             project,
             repo,
         } => {
-            let (project, repo) = resolve(project, repo)?;
-            repositories::reassign_merge_request(client, &project, &repo, mr_id, user_id).await?;
-            println!("Reassigned merge request #{} to user {}", mr_id, user_id);
+            let (project, repo) =
+                resolve_project_repo(project, repo).map_err(CodebaseError::input)?;
+            RepoCommand::ReassignMr {
+                project,
+                repo,
+                mr_id,
+                user_id,
+            }
         }
-    }
-    Ok(())
+    };
+    repositories::run(client, cmd).await
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: PrCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    run(client, cmd.into()).await?.print(out)
 }
@@ -1,11 +1,14 @@
-use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
+use serde::Serialize;
 
 use crate::api::client::CodebaseClient;
+use crate::api::error::Result;
+use crate::api::models::{Project, ProjectGroup, ProjectUser};
 use crate::api::projects;
-use crate::output;
+use crate::output::{self, OutputOptions};
 
+/// The `cb project` subcommands.
 #[derive(Subcommand)]
 pub enum ProjectCommands {
     /// List all projects
@@ -53,110 +56,177 @@ pub enum ProjectCommands {
     },
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: ProjectCommands, json: bool) -> Result<()> {
-    match cmd {
-        ProjectCommands::List => {
-            let project_list = projects::list_projects(client).await?;
-            output::print_list(json, &project_list, |projects| {
-                for p in projects {
-                    let status = output::colorize_status(p.status.as_deref().unwrap_or("unknown"));
-                    println!(
-                        "{} ({}) [{}]",
-                        p.name.as_deref().unwrap_or("").bold(),
-                        p.permalink.as_deref().unwrap_or("").dimmed(),
-                        status,
-                    );
-                }
-            })?;
+/// A project command, as [`run`] takes it.
+#[derive(Debug, Clone)]
+pub enum ProjectCommand {
+    List,
+    Show {
+        permalink: String,
+    },
+    Create {
+        name: String,
+    },
+    /// Change the name and/or status (active, on_hold, archived).
+    Update {
+        permalink: String,
+        name: Option<String>,
+        status: Option<String>,
+    },
+    Delete {
+        permalink: String,
+    },
+    Groups,
+    Users {
+        project: String,
+    },
+    /// Replace the users assigned to `project`.
+    AssignUsers {
+        project: String,
+        user_ids: Vec<i64>,
+    },
+}
+
+impl From<ProjectCommands> for ProjectCommand {
+    fn from(cmd: ProjectCommands) -> Self {
+        match cmd {
+            ProjectCommands::List => ProjectCommand::List,
+            ProjectCommands::Show { permalink } => ProjectCommand::Show { permalink },
+            ProjectCommands::Create { name } => ProjectCommand::Create { name },
+            ProjectCommands::Update {
+                permalink,
+                name,
+                status,
+            } => ProjectCommand::Update {
+                permalink,
+                name,
+                status,
+            },
+            ProjectCommands::Delete { permalink } => ProjectCommand::Delete { permalink },
+            ProjectCommands::Groups => ProjectCommand::Groups,
+            ProjectCommands::Users { project } => ProjectCommand::Users { project },
+            ProjectCommands::AssignUsers { project, user_ids } => {
+                ProjectCommand::AssignUsers { project, user_ids }
+            }
         }
-        ProjectCommands::Show { permalink } => {
-            let p = projects::show_project(client, &permalink).await?;
-            output::print_output(json, &p, || {
-                let status = output::colorize_status(p.status.as_deref().unwrap_or("unknown"));
-                println!(
-                    "{}: {}",
-                    "Name".dimmed(),
-                    p.name.as_deref().unwrap_or("").bold()
-                );
-                println!(
-                    "{}: {}",
-                    "Permalink".dimmed(),
-                    p.permalink.as_deref().unwrap_or("")
-                );
-                println!("{}: {}", "Status".dimmed(), status);
-                println!(
-                    "{}: {}",
-                    "Overview".dimmed(),
-                    p.overview.as_deref().unwrap_or("")
-                );
-                println!(
-                    "{}: {} open / {} closed / {} total",
-                    "Tickets".dimmed(),
-                    p.open_tickets.unwrap_or(0).to_string().green(),
-                    p.closed_tickets.unwrap_or(0).to_string().red(),
-                    p.total_tickets.unwrap_or(0),
-                );
-            })?;
+    }
+}
+
+/// What a project command did, for [`ProjectOutput::print`] or for callers
+/// using the commands as a library.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectOutput {
+    Projects(Vec<Project>),
+    Project(Project),
+    Created(Project),
+    Updated(Project),
+    Deleted { permalink: String },
+    Groups(Vec<ProjectGroup>),
+    Users(Vec<ProjectUser>),
+    Assigned { project: String, user_ids: Vec<i64> },
+}
+
+/// Run `cmd` and return its result without printing anything.
+pub async fn run(client: &CodebaseClient, cmd: ProjectCommand) -> Result<ProjectOutput> {
+    Ok(match cmd {
+        ProjectCommand::List => ProjectOutput::Projects(projects::list_projects(client).await?),
+        ProjectCommand::Show { permalink } => {
+            ProjectOutput::Project(projects::show_project(client, &permalink).await?)
         }
-        ProjectCommands::Create { name } => {
-            let p = projects::create_project(client, &name).await?;
-            output::print_output(json, &p, || {
-                println!(
-                    "Created project: {} ({})",
-                    p.name.as_deref().unwrap_or("").bold(),
-                    p.permalink.as_deref().unwrap_or("")
-                );
-            })?;
+        ProjectCommand::Create { name } => {
+            ProjectOutput::Created(projects::create_project(client, &name).await?)
         }
-        ProjectCommands::Update {
+        ProjectCommand::Update {
             permalink,
             name,
             status,
-        } => {
-            let p =
-                projects::update_project(client, &permalink, name.as_deref(), status.as_deref())
-                    .await?;
-            output::print_output(json, &p, || {
-                println!(
-                    "Updated project: {}",
-                    p.name.as_deref().unwrap_or("").bold()
-                );
-            })?;
-        }
-        ProjectCommands::Delete { permalink } => {
+        } => ProjectOutput::Updated(
+            projects::update_project(client, &permalink, name.as_deref(), status.as_deref())
+                .await?,
+        ),
+        ProjectCommand::Delete { permalink } => {
             projects::delete_project(client, &permalink).await?;
-            println!("Deleted project: {}", permalink);
+            ProjectOutput::Deleted { permalink }
+        }
+        ProjectCommand::Groups => {
+            ProjectOutput::Groups(projects::list_project_groups(client).await?)
+        }
+        ProjectCommand::Users { project } => {
+            ProjectOutput::Users(projects::list_project_users(client, &project).await?)
         }
-        ProjectCommands::Groups => {
-            let groups = projects::list_project_groups(client).await?;
-            output::print_list(json, &groups, |groups| {
-                for g in groups {
+        ProjectCommand::AssignUsers { project, user_ids } => {
+            projects::assign_project_users(client, &project, &user_ids).await?;
+            ProjectOutput::Assigned { project, user_ids }
+        }
+    })
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: ProjectCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    run(client, cmd.into()).await?.print(out)
+}
+
+impl ProjectOutput {
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        match self {
+            ProjectOutput::Projects(list) => output::print_list(out, list)?,
+            ProjectOutput::Project(p) => {
+                output::print_output(out, p, || {
+                    let status = output::colorize_status(p.status.as_deref().unwrap_or("unknown"));
                     println!(
                         "{}: {}",
-                        g.id.unwrap_or(0),
-                        g.label.as_deref().unwrap_or("").bold()
+                        "Name".dimmed(),
+                        p.name.as_deref().unwrap_or("").bold()
                     );
-                }
-            })?;
-        }
-        ProjectCommands::Users { project } => {
-            let users = projects::list_project_users(client, &project).await?;
-            output::print_list(json, &users, |users| {
-                for u in users {
                     println!(
-                        "{}: {} {} ({})",
-                        u.id.unwrap_or(0),
-                        u.first_name.as_deref().unwrap_or(""),
-                        u.last_name.as_deref().unwrap_or(""),
-                        u.username.as_deref().unwrap_or("").dimmed()
+                        "{}: {}",
+                        "Permalink".dimmed(),
+                        p.permalink.as_deref().unwrap_or("")
                     );
-                }
-            })?;
-        }
-        ProjectCommands::AssignUsers { project, user_ids } => {
-            projects::assign_project_users(client, &project, &user_ids).await?;
-            println!("Assigned {} users to {}", user_ids.len(), project);
+                    println!("{}: {}", "Status".dimmed(), status);
+                    println!(
+                        "{}: {}",
+                        "Overview".dimmed(),
+                        p.overview.as_deref().unwrap_or("")
+                    );
+                    println!(
+                        "{}: {} open / {} closed / {} total",
+                        "Tickets".dimmed(),
+                        p.open_tickets.unwrap_or(0).to_string().green(),
+                        p.closed_tickets.unwrap_or(0).to_string().red(),
+                        p.total_tickets.unwrap_or(0),
+                    );
+                })?;
+            }
+            ProjectOutput::Created(p) => {
+                output::print_output(out, p, || {
+                    println!(
+                        "Created project: {} ({})",
+                        p.name.as_deref().unwrap_or("").bold(),
+                        p.permalink.as_deref().unwrap_or("")
+                    );
+                })?;
+            }
+            ProjectOutput::Updated(p) => {
+                output::print_output(out, p, || {
+                    println!(
+                        "Updated project: {}",
+                        p.name.as_deref().unwrap_or("").bold()
+                    );
+                })?;
+            }
+            ProjectOutput::Deleted { permalink } => {
+                println!("Deleted project: {}", permalink);
+            }
+            ProjectOutput::Groups(groups) => output::print_list(out, groups)?,
+            ProjectOutput::Users(users) => output::print_list(out, users)?,
+            ProjectOutput::Assigned { project, user_ids } => {
+                println!("Assigned {} users to {}", user_ids.len(), project);
+            }
         }
+        Ok(())
     }
-    Ok(())
 }
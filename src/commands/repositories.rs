@@ -1,11 +1,15 @@
-use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
+use serde::Serialize;
 
 use crate::api::client::CodebaseClient;
+use crate::api::error::Result;
+use crate::api::models::{Branch, Commit, Hook, MergeRequest, Repository};
 use crate::api::repositories;
-use crate::output;
+use crate::commands::PageArgs;
+use crate::output::{self, OutputOptions};
 
+/// The `cb repo` subcommands.
 #[derive(Subcommand)]
 pub enum RepoCommands {
     /// List repositories for a project
@@ -48,6 +52,8 @@ pub enum RepoCommands {
         /// Optional file/folder path to filter commits
         #[arg(long)]
         path: Option<String>,
+        #[command(flatten)]
+        pages: PageArgs,
     },
     /// Create a deployment
     Deploy {
@@ -185,96 +191,319 @@ pub enum RepoCommands {
     },
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: RepoCommands, json: bool) -> Result<()> {
-    match cmd {
-        RepoCommands::List { project } => {
-            let repos = repositories::list_repositories(client, &project).await?;
-            output::print_list(json, &repos, |repos| {
-                for r in repos {
-                    println!(
-                        "{} ({}) clone: {}",
-                        r.name.as_deref().unwrap_or("").bold(),
-                        r.permalink.as_deref().unwrap_or("").dimmed(),
-                        r.clone_url.as_deref().unwrap_or("").cyan()
-                    );
-                }
-            })?;
+/// A repository or merge request command, as [`run`] takes it. The fields
+/// are those of the matching [`RepoCommands`] variant.
+#[derive(Debug, Clone)]
+pub enum RepoCommand {
+    List {
+        project: String,
+    },
+    Show {
+        project: String,
+        repo: String,
+    },
+    Create {
+        project: String,
+        name: String,
+        scm: String,
+    },
+    Delete {
+        project: String,
+        repo: String,
+    },
+    Commits {
+        project: String,
+        repo: String,
+        git_ref: String,
+        path: Option<String>,
+        pages: PageArgs,
+    },
+    Deploy {
+        project: String,
+        repo: String,
+        branch: String,
+        revision: String,
+        servers: String,
+        environment: Option<String>,
+    },
+    File {
+        project: String,
+        repo: String,
+        git_ref: String,
+        path: String,
+    },
+    Hooks {
+        project: String,
+        repo: String,
+    },
+    CreateHook {
+        project: String,
+        repo: String,
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    Branches {
+        project: String,
+        repo: String,
+    },
+    MergeRequests {
+        project: String,
+        repo: String,
+    },
+    ShowMr {
+        project: String,
+        repo: String,
+        mr_id: i64,
+    },
+    CreateMr {
+        project: String,
+        repo: String,
+        source_ref: String,
+        target_ref: String,
+        subject: String,
+    },
+    CommentMr {
+        project: String,
+        repo: String,
+        mr_id: i64,
+        content: String,
+    },
+    CloseMr {
+        project: String,
+        repo: String,
+        mr_id: i64,
+    },
+    ReopenMr {
+        project: String,
+        repo: String,
+        mr_id: i64,
+    },
+    Merge {
+        project: String,
+        repo: String,
+        mr_id: i64,
+    },
+    ReassignMr {
+        project: String,
+        repo: String,
+        mr_id: i64,
+        user_id: i64,
+    },
+}
+
+impl From<RepoCommands> for RepoCommand {
+    fn from(cmd: RepoCommands) -> Self {
+        match cmd {
+            RepoCommands::List { project } => RepoCommand::List { project },
+            RepoCommands::Show { project, repo } => RepoCommand::Show { project, repo },
+            RepoCommands::Create { project, name, scm } => {
+                RepoCommand::Create { project, name, scm }
+            }
+            RepoCommands::Delete { project, repo } => RepoCommand::Delete { project, repo },
+            RepoCommands::Commits {
+                project,
+                repo,
+                git_ref,
+                path,
+                pages,
+            } => RepoCommand::Commits {
+                project,
+                repo,
+                git_ref,
+                path,
+                pages,
+            },
+            RepoCommands::Deploy {
+                project,
+                repo,
+                branch,
+                revision,
+                servers,
+                environment,
+            } => RepoCommand::Deploy {
+                project,
+                repo,
+                branch,
+                revision,
+                servers,
+                environment,
+            },
+            RepoCommands::File {
+                project,
+                repo,
+                git_ref,
+                path,
+            } => RepoCommand::File {
+                project,
+                repo,
+                git_ref,
+                path,
+            },
+            RepoCommands::Hooks { project, repo } => RepoCommand::Hooks { project, repo },
+            RepoCommands::CreateHook {
+                project,
+                repo,
+                url,
+                username,
+                password,
+            } => RepoCommand::CreateHook {
+                project,
+                repo,
+                url,
+                username,
+                password,
+            },
+            RepoCommands::Branches { project, repo } => RepoCommand::Branches { project, repo },
+            RepoCommands::MergeRequests { project, repo } => {
+                RepoCommand::MergeRequests { project, repo }
+            }
+            RepoCommands::ShowMr {
+                project,
+                repo,
+                mr_id,
+            } => RepoCommand::ShowMr {
+                project,
+                repo,
+                mr_id,
+            },
+            RepoCommands::CreateMr {
+                project,
+                repo,
+                source_ref,
+                target_ref,
+                subject,
+            } => RepoCommand::CreateMr {
+                project,
+                repo,
+                source_ref,
+                target_ref,
+                subject,
+            },
+            RepoCommands::CommentMr {
+                project,
+                repo,
+                mr_id,
+                content,
+            } => RepoCommand::CommentMr {
+                project,
+                repo,
+                mr_id,
+                content,
+            },
+            RepoCommands::CloseMr {
+                project,
+                repo,
+                mr_id,
+            } => RepoCommand::CloseMr {
+                project,
+                repo,
+                mr_id,
+            },
+            RepoCommands::ReopenMr {
+                project,
+                repo,
+                mr_id,
+            } => RepoCommand::ReopenMr {
+                project,
+                repo,
+                mr_id,
+            },
+            RepoCommands::Merge {
+                project,
+                repo,
+                mr_id,
+            } => RepoCommand::Merge {
+                project,
+                repo,
+                mr_id,
+            },
+            RepoCommands::ReassignMr {
+                project,
+                repo,
+                mr_id,
+                user_id,
+            } => RepoCommand::ReassignMr {
+                project,
+                repo,
+                mr_id,
+                user_id,
+            },
         }
-        RepoCommands::Show { project, repo } => {
-            let r = repositories::show_repository(client, &project, &repo).await?;
-            output::print_output(json, &r, || {
-                println!(
-                    "{}: {}",
-                    "Name".dimmed(),
-                    r.name.as_deref().unwrap_or("").bold()
-                );
-                println!(
-                    "{}: {}",
-                    "Permalink".dimmed(),
-                    r.permalink.as_deref().unwrap_or("")
-                );
-                println!(
-                    "{}: {}",
-                    "Clone URL".dimmed(),
-                    r.clone_url.as_deref().unwrap_or("").cyan()
-                );
-                println!(
-                    "{}: {} bytes",
-                    "Disk Usage".dimmed(),
-                    r.disk_usage.unwrap_or(0)
-                );
-                println!(
-                    "{}: {}",
-                    "Last Commit".dimmed(),
-                    r.last_commit_ref.as_deref().unwrap_or("")
-                );
-            })?;
+    }
+}
+
+/// What a repository or merge request command did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOutput {
+    Repositories(Vec<Repository>),
+    Repository(Repository),
+    Created(Repository),
+    Deleted {
+        project: String,
+        repo: String,
+    },
+    Commits(Vec<Commit>),
+    Deployed {
+        branch: String,
+        revision: String,
+        servers: String,
+    },
+    /// File contents.
+    File(String),
+    Hooks(Vec<Hook>),
+    HookCreated(Hook),
+    Branches(Vec<Branch>),
+    MergeRequests(Vec<MergeRequest>),
+    MergeRequest(MergeRequest),
+    MergeRequestCreated(MergeRequest),
+    Commented {
+        mr_id: i64,
+    },
+    Closed {
+        mr_id: i64,
+    },
+    Reopened {
+        mr_id: i64,
+    },
+    Merged {
+        mr_id: i64,
+    },
+    Reassigned {
+        mr_id: i64,
+        user_id: i64,
+    },
+}
+
+/// Run `cmd` and return its result without printing anything.
+pub async fn run(client: &CodebaseClient, cmd: RepoCommand) -> Result<RepoOutput> {
+    Ok(match cmd {
+        RepoCommand::List { project } => {
+            RepoOutput::Repositories(repositories::list_repositories(client, &project).await?)
         }
-        RepoCommands::Create { project, name, scm } => {
-            let r = repositories::create_repository(client, &project, &name, &scm).await?;
-            output::print_output(json, &r, || {
-                println!(
-                    "Created repository: {} ({})",
-                    r.name.as_deref().unwrap_or("").bold(),
-                    r.permalink.as_deref().unwrap_or("")
-                );
-            })?;
+        RepoCommand::Show { project, repo } => {
+            RepoOutput::Repository(repositories::show_repository(client, &project, &repo).await?)
         }
-        RepoCommands::Delete { project, repo } => {
+        RepoCommand::Create { project, name, scm } => RepoOutput::Created(
+            repositories::create_repository(client, &project, &name, &scm).await?,
+        ),
+        RepoCommand::Delete { project, repo } => {
             repositories::delete_repository(client, &project, &repo).await?;
-            println!("Deleted repository: {}/{}", project, repo);
+            RepoOutput::Deleted { project, repo }
         }
-        RepoCommands::Commits {
+        RepoCommand::Commits {
             project,
             repo,
             git_ref,
             path,
+            pages,
         } => {
-            let commits = if let Some(p) = path {
-                repositories::list_commits_path(client, &project, &repo, &git_ref, &p).await?
-            } else {
-                repositories::list_commits(client, &project, &repo, &git_ref).await?
-            };
-            output::print_list(json, &commits, |commits| {
-                for c in commits {
-                    let sha = c.commit_ref.as_deref().unwrap_or("");
-                    let short_sha = if sha.len() > 7 { &sha[..7] } else { sha };
-                    println!(
-                        "{} {} <{}> {}",
-                        short_sha.yellow(),
-                        c.author_name.as_deref().unwrap_or("").bold(),
-                        c.author_email.as_deref().unwrap_or("").dimmed(),
-                        c.message
-                            .as_deref()
-                            .unwrap_or("")
-                            .lines()
-                            .next()
-                            .unwrap_or("")
-                    );
-                }
-            })?;
+            let pager =
+                repositories::paginate_commits(client, &project, &repo, &git_ref, path.as_deref());
+            RepoOutput::Commits(pages.fetch(pager).await?)
         }
-        RepoCommands::Deploy {
+        RepoCommand::Deploy {
             project,
             repo,
             branch,
@@ -292,42 +521,31 @@ pub async fn execute(client: &CodebaseClient, cmd: RepoCommands, json: bool) ->
                 environment.as_deref(),
             )
             .await?;
-            println!(
-                "Deployed {} ({}) to {}",
-                branch.green(),
-                &revision[..7.min(revision.len())].yellow(),
-                servers
-            );
+            RepoOutput::Deployed {
+                branch,
+                revision,
+                servers,
+            }
         }
-        RepoCommands::File {
+        RepoCommand::File {
             project,
             repo,
             git_ref,
             path,
-        } => {
-            let content = repositories::get_file(client, &project, &repo, &git_ref, &path).await?;
-            println!("{}", content);
+        } => RepoOutput::File(
+            repositories::get_file(client, &project, &repo, &git_ref, &path).await?,
+        ),
+        RepoCommand::Hooks { project, repo } => {
+            RepoOutput::Hooks(repositories::list_hooks(client, &project, &repo).await?)
         }
-        RepoCommands::Hooks { project, repo } => {
-            let hooks = repositories::list_hooks(client, &project, &repo).await?;
-            output::print_list(json, &hooks, |hooks| {
-                for h in hooks {
-                    println!(
-                        "{}: {}",
-                        h.id.unwrap_or(0),
-                        h.url.as_deref().unwrap_or("").cyan()
-                    );
-                }
-            })?;
-        }
-        RepoCommands::CreateHook {
+        RepoCommand::CreateHook {
             project,
             repo,
             url,
             username,
             password,
-        } => {
-            let h = repositories::create_hook(
+        } => RepoOutput::HookCreated(
+            repositories::create_hook(
                 client,
                 &project,
                 &repo,
@@ -335,79 +553,29 @@ pub async fn execute(client: &CodebaseClient, cmd: RepoCommands, json: bool) ->
                 username.as_deref(),
                 password.as_deref(),
             )
-            .await?;
-            output::print_output(json, &h, || {
-                println!(
-                    "Created hook {}: {}",
-                    h.id.unwrap_or(0),
-                    h.url.as_deref().unwrap_or("").cyan()
-                );
-            })?;
+            .await?,
+        ),
+        RepoCommand::Branches { project, repo } => {
+            RepoOutput::Branches(repositories::list_branches(client, &project, &repo).await?)
         }
-        RepoCommands::Branches { project, repo } => {
-            let branches = repositories::list_branches(client, &project, &repo).await?;
-            output::print_list(json, &branches, |branches| {
-                for b in branches {
-                    println!("{}", b.name.as_deref().unwrap_or("").cyan());
-                }
-            })?;
-        }
-        RepoCommands::MergeRequests { project, repo } => {
-            let mrs = repositories::list_merge_requests(client, &project, &repo).await?;
-            output::print_list(json, &mrs, |mrs| {
-                for mr in mrs {
-                    let status =
-                        output::colorize_mr_status(mr.status.as_deref().unwrap_or("unknown"));
-                    println!(
-                        "#{} [{}] {} ({} -> {})",
-                        mr.id.unwrap_or(0).to_string().bold(),
-                        status,
-                        mr.subject.as_deref().unwrap_or(""),
-                        mr.source_ref.as_deref().unwrap_or("").cyan(),
-                        mr.target_ref.as_deref().unwrap_or("").green(),
-                    );
-                }
-            })?;
-        }
-        RepoCommands::ShowMr {
+        RepoCommand::MergeRequests { project, repo } => RepoOutput::MergeRequests(
+            repositories::list_merge_requests(client, &project, &repo).await?,
+        ),
+        RepoCommand::ShowMr {
             project,
             repo,
             mr_id,
-        } => {
-            let mr = repositories::show_merge_request(client, &project, &repo, mr_id).await?;
-            output::print_output(json, &mr, || {
-                let status = output::colorize_mr_status(mr.status.as_deref().unwrap_or("unknown"));
-                let can_merge = output::colorize_bool(mr.can_merge.unwrap_or(false), "yes", "no");
-                println!("ID:         {}", mr.id.unwrap_or(0).to_string().bold());
-                println!("Subject:    {}", mr.subject.as_deref().unwrap_or(""));
-                println!("Status:     {}", status);
-                println!(
-                    "Source:     {}",
-                    mr.source_ref.as_deref().unwrap_or("").cyan()
-                );
-                println!(
-                    "Target:     {}",
-                    mr.target_ref.as_deref().unwrap_or("").green()
-                );
-                println!("Can Merge:  {}", can_merge);
-                println!(
-                    "Created:    {}",
-                    mr.created_at.as_deref().unwrap_or("").dimmed()
-                );
-                println!(
-                    "Updated:    {}",
-                    mr.updated_at.as_deref().unwrap_or("").dimmed()
-                );
-            })?;
-        }
-        RepoCommands::CreateMr {
+        } => RepoOutput::MergeRequest(
+            repositories::show_merge_request(client, &project, &repo, mr_id).await?,
+        ),
+        RepoCommand::CreateMr {
             project,
             repo,
             source_ref,
             target_ref,
             subject,
-        } => {
-            let mr = repositories::create_merge_request(
+        } => RepoOutput::MergeRequestCreated(
+            repositories::create_merge_request(
                 client,
                 &project,
                 &repo,
@@ -415,57 +583,179 @@ pub async fn execute(client: &CodebaseClient, cmd: RepoCommands, json: bool) ->
                 &target_ref,
                 &subject,
             )
-            .await?;
-            output::print_output(json, &mr, || {
-                println!(
-                    "Created merge request #{}: {}",
-                    mr.id.unwrap_or(0).to_string().bold(),
-                    mr.subject.as_deref().unwrap_or("")
-                );
-            })?;
-        }
-        RepoCommands::CommentMr {
+            .await?,
+        ),
+        RepoCommand::CommentMr {
             project,
             repo,
             mr_id,
             content,
         } => {
             repositories::comment_merge_request(client, &project, &repo, mr_id, &content).await?;
-            println!("Commented on merge request #{}", mr_id);
+            RepoOutput::Commented { mr_id }
         }
-        RepoCommands::CloseMr {
+        RepoCommand::CloseMr {
             project,
             repo,
             mr_id,
         } => {
             repositories::close_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Closed merge request #{}", mr_id);
+            RepoOutput::Closed { mr_id }
         }
-        RepoCommands::ReopenMr {
+        RepoCommand::ReopenMr {
             project,
             repo,
             mr_id,
         } => {
             repositories::reopen_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Reopened merge request #{}", mr_id);
+            RepoOutput::Reopened { mr_id }
         }
-        RepoCommands::Merge {
+        RepoCommand::Merge {
             project,
             repo,
             mr_id,
         } => {
             repositories::merge_merge_request(client, &project, &repo, mr_id).await?;
-            println!("Merged merge request #{}", mr_id.to_string().bold());
+            RepoOutput::Merged { mr_id }
         }
-        RepoCommands::ReassignMr {
+        RepoCommand::ReassignMr {
             project,
             repo,
             mr_id,
             user_id,
         } => {
             repositories::reassign_merge_request(client, &project, &repo, mr_id, user_id).await?;
-            println!("Reassigned merge request #{} to user {}", mr_id, user_id);
+            RepoOutput::Reassigned { mr_id, user_id }
+        }
+    })
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: RepoCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    run(client, cmd.into()).await?.print(out)
+}
+
+impl RepoOutput {
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        match self {
+            RepoOutput::Repositories(repos) => output::print_list(out, repos)?,
+            RepoOutput::Repository(r) => {
+                output::print_output(out, r, || {
+                    println!(
+                        "{}: {}",
+                        "Name".dimmed(),
+                        r.name.as_deref().unwrap_or("").bold()
+                    );
+                    println!(
+                        "{}: {}",
+                        "Permalink".dimmed(),
+                        r.permalink.as_deref().unwrap_or("")
+                    );
+                    println!(
+                        "{}: {}",
+                        "Clone URL".dimmed(),
+                        r.clone_url.as_deref().unwrap_or("").cyan()
+                    );
+                    println!(
+                        "{}: {} bytes",
+                        "Disk Usage".dimmed(),
+                        r.disk_usage.unwrap_or(0)
+                    );
+                    println!(
+                        "{}: {}",
+                        "Last Commit".dimmed(),
+                        r.last_commit_ref.as_deref().unwrap_or("")
+                    );
+                })?;
+            }
+            RepoOutput::Created(r) => {
+                output::print_output(out, r, || {
+                    println!(
+                        "Created repository: {} ({})",
+                        r.name.as_deref().unwrap_or("").bold(),
+                        r.permalink.as_deref().unwrap_or("")
+                    );
+                })?;
+            }
+            RepoOutput::Deleted { project, repo } => {
+                println!("Deleted repository: {}/{}", project, repo);
+            }
+            RepoOutput::Commits(commits) => output::print_list(out, commits)?,
+            RepoOutput::Deployed {
+                branch,
+                revision,
+                servers,
+            } => {
+                println!(
+                    "Deployed {} ({}) to {}",
+                    branch.green(),
+                    &revision[..7.min(revision.len())].yellow(),
+                    servers
+                );
+            }
+            RepoOutput::File(content) => println!("{}", content),
+            RepoOutput::Hooks(hooks) => output::print_list(out, hooks)?,
+            RepoOutput::HookCreated(h) => {
+                output::print_output(out, h, || {
+                    println!(
+                        "Created hook {}: {}",
+                        h.id.unwrap_or(0),
+                        h.url.as_deref().unwrap_or("").cyan()
+                    );
+                })?;
+            }
+            RepoOutput::Branches(branches) => output::print_list(out, branches)?,
+            RepoOutput::MergeRequests(mrs) => output::print_list(out, mrs)?,
+            RepoOutput::MergeRequest(mr) => {
+                output::print_output(out, mr, || {
+                    let status =
+                        output::colorize_mr_status(mr.status.as_deref().unwrap_or("unknown"));
+                    let can_merge =
+                        output::colorize_bool(mr.can_merge.unwrap_or(false), "yes", "no");
+                    println!("ID:         {}", mr.id.unwrap_or(0).to_string().bold());
+                    println!("Subject:    {}", mr.subject.as_deref().unwrap_or(""));
+                    println!("Status:     {}", status);
+                    println!(
+                        "Source:     {}",
+                        mr.source_ref.as_deref().unwrap_or("").cyan()
+                    );
+                    println!(
+                        "Target:     {}",
+                        mr.target_ref.as_deref().unwrap_or("").green()
+                    );
+                    println!("Can Merge:  {}", can_merge);
+                    println!(
+                        "Created:    {}",
+                        mr.created_at.as_deref().unwrap_or("").dimmed()
+                    );
+                    println!(
+                        "Updated:    {}",
+                        mr.updated_at.as_deref().unwrap_or("").dimmed()
+                    );
+                })?;
+            }
+            RepoOutput::MergeRequestCreated(mr) => {
+                output::print_output(out, mr, || {
+                    println!(
+                        "Created merge request #{}: {}",
+                        mr.id.unwrap_or(0).to_string().bold(),
+                        mr.subject.as_deref().unwrap_or("")
+                    );
+                })?;
+            }
+            RepoOutput::Commented { mr_id } => println!("Commented on merge request #{}", mr_id),
+            RepoOutput::Closed { mr_id } => println!("Closed merge request #{}", mr_id),
+            RepoOutput::Reopened { mr_id } => println!("Reopened merge request #{}", mr_id),
+            RepoOutput::Merged { mr_id } => {
+                println!("Merged merge request #{}", mr_id.to_string().bold());
+            }
+            RepoOutput::Reassigned { mr_id, user_id } => {
+                println!("Reassigned merge request #{} to user {}", mr_id, user_id);
+            }
         }
+        Ok(())
     }
-    Ok(())
 }
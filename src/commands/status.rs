@@ -1,35 +1,38 @@
-use anyhow::Result;
 use colored::Colorize;
 use serde::Serialize;
 
 use crate::api::client::CodebaseClient;
+use crate::api::error::Result;
 use crate::api::{activity, projects};
-use crate::output;
+use crate::output::{self, OutputOptions};
 
-#[derive(Serialize)]
-struct StatusDashboard {
-    projects: Vec<ProjectSummary>,
-    recent_activity: Vec<ActivityItem>,
+/// The `cb status` overview.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusDashboard {
+    pub projects: Vec<ProjectSummary>,
+    /// The ten newest account events.
+    pub recent_activity: Vec<ActivityItem>,
 }
 
-#[derive(Serialize)]
-struct ProjectSummary {
-    name: String,
-    permalink: String,
-    status: String,
-    open_tickets: i64,
-    closed_tickets: i64,
-    total_tickets: i64,
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSummary {
+    pub name: String,
+    pub permalink: String,
+    pub status: String,
+    pub open_tickets: i64,
+    pub closed_tickets: i64,
+    pub total_tickets: i64,
 }
 
-#[derive(Serialize)]
-struct ActivityItem {
-    event_type: String,
-    timestamp: String,
-    title: String,
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityItem {
+    pub event_type: String,
+    pub timestamp: String,
+    pub title: String,
 }
 
-pub async fn execute(client: &CodebaseClient, json: bool) -> Result<()> {
+/// Fetch the dashboard: every project and recent account activity.
+pub async fn run(client: &CodebaseClient) -> Result<StatusDashboard> {
     let project_list = projects::list_projects(client).await?;
     let events = activity::account_activity(client, false, None, None).await?;
 
@@ -55,47 +58,50 @@ pub async fn execute(client: &CodebaseClient, json: bool) -> Result<()> {
         })
         .collect();
 
-    let dashboard = StatusDashboard {
+    Ok(StatusDashboard {
         projects: summaries,
         recent_activity: activity_items,
-    };
+    })
+}
 
-    if json {
-        let out = serde_json::to_string_pretty(&dashboard)?;
-        println!("{}", out);
-    } else {
-        println!("{}", "PROJECTS".bold());
-        println!("{}", "─".repeat(60));
-        for p in &dashboard.projects {
-            let status = output::colorize_status(&p.status);
-            println!(
-                "  {} ({}) [{}]  {} open / {} closed",
-                p.name.bold(),
-                p.permalink.dimmed(),
-                status,
-                p.open_tickets.to_string().green(),
-                p.closed_tickets.to_string().red(),
-            );
-        }
-        if dashboard.projects.is_empty() {
-            println!("  {}", "No projects found.".dimmed());
-        }
+pub async fn execute(client: &CodebaseClient, out: &OutputOptions) -> anyhow::Result<()> {
+    run(client).await?.print(out)
+}
 
-        println!();
-        println!("{}", "RECENT ACTIVITY".bold());
-        println!("{}", "─".repeat(60));
-        for a in &dashboard.recent_activity {
-            println!(
-                "  {} {} {}",
-                a.event_type.cyan(),
-                a.timestamp.dimmed(),
-                a.title,
-            );
-        }
-        if dashboard.recent_activity.is_empty() {
-            println!("  {}", "No recent activity.".dimmed());
-        }
-    }
+impl StatusDashboard {
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        output::print_output(out, self, || {
+            println!("{}", "PROJECTS".bold());
+            println!("{}", "─".repeat(60));
+            for p in &self.projects {
+                let status = output::colorize_status(&p.status);
+                println!(
+                    "  {} ({}) [{}]  {} open / {} closed",
+                    p.name.bold(),
+                    p.permalink.dimmed(),
+                    status,
+                    p.open_tickets.to_string().green(),
+                    p.closed_tickets.to_string().red(),
+                );
+            }
+            if self.projects.is_empty() {
+                println!("  {}", "No projects found.".dimmed());
+            }
 
-    Ok(())
+            println!();
+            println!("{}", "RECENT ACTIVITY".bold());
+            println!("{}", "─".repeat(60));
+            for a in &self.recent_activity {
+                println!(
+                    "  {} {} {}",
+                    a.event_type.cyan(),
+                    a.timestamp.dimmed(),
+                    a.title,
+                );
+            }
+            if self.recent_activity.is_empty() {
+                println!("  {}", "No recent activity.".dimmed());
+            }
+        })
+    }
 }
@@ -1,18 +1,29 @@
-use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
 
+use serde::Serialize;
+
 use crate::api::client::CodebaseClient;
-use crate::api::models::NoteChanges;
+use crate::api::error::{CodebaseError, Result};
+use crate::api::lookup::{self, ProjectLookup, ResolveError, TimelineEntry};
+use crate::api::models::{
+    NoteChanges, Ticket, TicketCategory, TicketNote, TicketPriority, TicketStatus, TicketType,
+    Watcher,
+};
 use crate::api::tickets;
-use crate::output;
+use crate::commands::PageArgs;
+use crate::compose::{self, Draft};
+use crate::output::{self, OutputOptions, TicketRow};
 
+/// The `cb ticket` subcommands.
 #[derive(Subcommand)]
 pub enum TicketCommands {
     /// List all tickets for a project
     List {
         /// Project permalink
         project: String,
+        #[command(flatten)]
+        pages: PageArgs,
     },
     /// Search tickets
     Search {
@@ -20,37 +31,64 @@ pub enum TicketCommands {
         project: String,
         /// Search query (e.g. "status:closed")
         query: String,
+        #[command(flatten)]
+        pages: PageArgs,
+    },
+    /// Show a ticket with its full details and note timeline
+    Show {
+        /// Project permalink
+        project: String,
+        /// Ticket ID
+        ticket_id: i64,
     },
-    /// Create a new ticket
+    /// Create a new ticket (opens $EDITOR when no description is given on a terminal)
     Create {
         /// Project permalink
         project: String,
-        /// Ticket summary
-        summary: String,
-        /// Ticket type: bug, enhancement, or task
-        #[arg(long, default_value = "task")]
-        ticket_type: String,
-        /// Priority ID
+        /// Ticket summary (may instead be filled in the editor)
+        summary: Option<String>,
+        /// Ticket type: bug, enhancement, or task [default: task]
         #[arg(long)]
+        ticket_type: Option<String>,
+        /// Priority ID
+        #[arg(long, conflicts_with = "priority")]
         priority_id: Option<i64>,
-        /// Status ID
+        /// Priority name (e.g. "High")
         #[arg(long)]
+        priority: Option<String>,
+        /// Status ID
+        #[arg(long, conflicts_with = "status")]
         status_id: Option<i64>,
-        /// Description
+        /// Status name (e.g. "In Progress")
         #[arg(long)]
+        status: Option<String>,
+        /// Description
+        #[arg(long, conflicts_with = "description_file")]
         description: Option<String>,
+        /// Read the description from a file, or `-` for stdin
+        #[arg(long, value_name = "PATH")]
+        description_file: Option<String>,
         /// Assignee user ID
-        #[arg(long)]
+        #[arg(long, conflicts_with = "assignee")]
         assignee_id: Option<i64>,
-        /// Category ID
+        /// Assignee username, full name, or email
         #[arg(long)]
+        assignee: Option<String>,
+        /// Category ID
+        #[arg(long, conflicts_with = "category")]
         category_id: Option<i64>,
+        /// Category name
+        #[arg(long)]
+        category: Option<String>,
         /// Milestone ID
         #[arg(long)]
         milestone_id: Option<i64>,
         /// Space-separated tags
         #[arg(long)]
         tags: Option<String>,
+        /// Never open an editor, even on a terminal
+        #[arg(long)]
+        no_edit: bool,
     },
     /// List notes for a ticket
     Notes {
@@ -60,29 +98,48 @@ pub enum TicketCommands {
         ticket_id: i64,
     },
     /// Add a note (and optionally update fields) on a ticket
+    /// (opens $EDITOR when no content is given on a terminal)
     AddNote {
         /// Project permalink
         project: String,
         /// Ticket ID
         ticket_id: i64,
         /// Note content
-        #[arg(long)]
+        #[arg(long, conflicts_with = "content_file")]
         content: Option<String>,
+        /// Read the note content from a file, or `-` for stdin
+        #[arg(long, value_name = "PATH")]
+        content_file: Option<String>,
+        /// Never open an editor, even on a terminal
+        #[arg(long)]
+        no_edit: bool,
         /// Make private (company-only)
         #[arg(long)]
         private: bool,
         /// Change status ID
-        #[arg(long)]
+        #[arg(long, conflicts_with = "status")]
         status_id: Option<i64>,
-        /// Change priority ID
+        /// Change status by name (e.g. "In Progress")
         #[arg(long)]
+        status: Option<String>,
+        /// Change priority ID
+        #[arg(long, conflicts_with = "priority")]
         priority_id: Option<i64>,
-        /// Change assignee ID
+        /// Change priority by name
         #[arg(long)]
+        priority: Option<String>,
+        /// Change assignee ID
+        #[arg(long, conflicts_with = "assignee")]
         assignee_id: Option<i64>,
-        /// Change category ID
+        /// Change assignee by username, full name, or email
         #[arg(long)]
+        assignee: Option<String>,
+        /// Change category ID
+        #[arg(long, conflicts_with = "category")]
         category_id: Option<i64>,
+        /// Change category by name
+        #[arg(long)]
+        category: Option<String>,
         /// Change milestone ID
         #[arg(long)]
         milestone_id: Option<i64>,
@@ -129,191 +186,710 @@ pub enum TicketCommands {
     },
 }
 
-pub async fn execute(client: &CodebaseClient, cmd: TicketCommands, json: bool) -> Result<()> {
-    match cmd {
-        TicketCommands::List { project } => {
-            let tix = tickets::list_tickets(client, &project).await?;
-            output::print_list(json, &tix, |tix| {
-                for t in tix {
-                    let tt = output::colorize_ticket_type(t.ticket_type.as_deref().unwrap_or(""));
-                    println!(
-                        "#{} [{}] {}",
-                        t.ticket_id.unwrap_or(0).to_string().bold(),
-                        tt,
-                        t.summary.as_deref().unwrap_or("")
-                    );
-                }
-            })?;
-        }
-        TicketCommands::Search { project, query } => {
-            let tix = tickets::search_tickets(client, &project, &query).await?;
-            output::print_list(json, &tix, |tix| {
-                for t in tix {
-                    let tt = output::colorize_ticket_type(t.ticket_type.as_deref().unwrap_or(""));
-                    println!(
-                        "#{} [{}] {}",
-                        t.ticket_id.unwrap_or(0).to_string().bold(),
-                        tt,
-                        t.summary.as_deref().unwrap_or("")
-                    );
-                }
-            })?;
-        }
-        TicketCommands::Create {
-            project,
-            summary,
-            ticket_type,
-            priority_id,
-            status_id,
-            description,
-            assignee_id,
-            category_id,
-            milestone_id,
-            tags,
-        } => {
-            let t = tickets::create_ticket(
-                client,
-                &project,
-                &summary,
-                &ticket_type,
+/// A ticket command, as [`run`] takes it. Fields that name a status,
+/// priority, category, type or user also accept its numeric ID.
+#[derive(Debug, Clone)]
+pub enum TicketCommand {
+    /// List a project's tickets. With `names`, also fetch the lookup tables
+    /// that name their statuses, priorities and categories.
+    List {
+        project: String,
+        pages: PageArgs,
+        names: bool,
+    },
+    /// Search a project's tickets, e.g. for `status:open`.
+    Search {
+        project: String,
+        query: String,
+        pages: PageArgs,
+        names: bool,
+    },
+    /// A ticket with its note timeline.
+    Show {
+        project: String,
+        ticket_id: i64,
+    },
+    Create(NewTicket),
+    Notes {
+        project: String,
+        ticket_id: i64,
+    },
+    AddNote(NewNote),
+    Watchers {
+        project: String,
+        ticket_id: i64,
+    },
+    /// Replace a ticket's watchers.
+    SetWatchers {
+        project: String,
+        ticket_id: i64,
+        user_ids: Vec<i64>,
+    },
+    Statuses {
+        project: String,
+    },
+    Priorities {
+        project: String,
+    },
+    Categories {
+        project: String,
+    },
+    Types {
+        project: String,
+    },
+}
+
+/// A ticket to create. Fields left `None` get the project's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct NewTicket {
+    pub project: String,
+    /// Required, unless filled in the editor.
+    pub summary: Option<String>,
+    /// bug, enhancement or task; task when `None`.
+    pub ticket_type: Option<String>,
+    pub priority: Option<String>,
+    pub status: Option<String>,
+    pub description: Option<String>,
+    /// Username, full name or email.
+    pub assignee: Option<String>,
+    pub category: Option<String>,
+    pub milestone_id: Option<i64>,
+    /// Space-separated.
+    pub tags: Option<String>,
+    /// Open `$EDITOR` to fill in the ticket when there is no description
+    /// and `cb` runs on a terminal.
+    pub edit: bool,
+}
+
+/// A note to add to a ticket, changing the fields that are `Some`.
+#[derive(Debug, Clone, Default)]
+pub struct NewNote {
+    pub project: String,
+    pub ticket_id: i64,
+    pub content: Option<String>,
+    /// Visible to the company only.
+    pub private: bool,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    /// Username, full name or email.
+    pub assignee: Option<String>,
+    pub category: Option<String>,
+    pub milestone_id: Option<i64>,
+    pub subject: Option<String>,
+    /// Open `$EDITOR` to write the note when there is no content and `cb`
+    /// runs on a terminal.
+    pub edit: bool,
+}
+
+impl TryFrom<TicketCommands> for TicketCommand {
+    type Error = CodebaseError;
+
+    /// Reads `--description-file` and `--content-file`, and merges each
+    /// `--*-id` flag into its name counterpart.
+    fn try_from(cmd: TicketCommands) -> Result<Self> {
+        let read = |path: String| compose::read_input(&path).map_err(CodebaseError::input);
+        Ok(match cmd {
+            TicketCommands::List { project, pages } => TicketCommand::List {
+                project,
+                pages,
+                names: false,
+            },
+            TicketCommands::Search {
+                project,
+                query,
+                pages,
+            } => TicketCommand::Search {
+                project,
+                query,
+                pages,
+                names: false,
+            },
+            TicketCommands::Show { project, ticket_id } => {
+                TicketCommand::Show { project, ticket_id }
+            }
+            TicketCommands::Create {
+                project,
+                summary,
+                ticket_type,
                 priority_id,
+                priority,
                 status_id,
-                description.as_deref(),
+                status,
+                description,
+                description_file,
                 assignee_id,
+                assignee,
                 category_id,
+                category,
                 milestone_id,
-                tags.as_deref(),
-            )
-            .await?;
-            output::print_output(json, &t, || {
-                println!(
-                    "Created ticket #{}: {}",
-                    t.ticket_id.unwrap_or(0).to_string().bold(),
-                    t.summary.as_deref().unwrap_or("")
-                );
-            })?;
+                tags,
+                no_edit,
+            } => TicketCommand::Create(NewTicket {
+                project,
+                summary,
+                ticket_type,
+                priority: name_or_id(priority, priority_id),
+                status: name_or_id(status, status_id),
+                description: description_file.map(read).transpose()?.or(description),
+                assignee: name_or_id(assignee, assignee_id),
+                category: name_or_id(category, category_id),
+                milestone_id,
+                tags,
+                edit: !no_edit,
+            }),
+            TicketCommands::Notes { project, ticket_id } => {
+                TicketCommand::Notes { project, ticket_id }
+            }
+            TicketCommands::AddNote {
+                project,
+                ticket_id,
+                content,
+                content_file,
+                no_edit,
+                private,
+                status_id,
+                status,
+                priority_id,
+                priority,
+                assignee_id,
+                assignee,
+                category_id,
+                category,
+                milestone_id,
+                subject,
+            } => TicketCommand::AddNote(NewNote {
+                project,
+                ticket_id,
+                content: content_file.map(read).transpose()?.or(content),
+                private,
+                status: name_or_id(status, status_id),
+                priority: name_or_id(priority, priority_id),
+                assignee: name_or_id(assignee, assignee_id),
+                category: name_or_id(category, category_id),
+                milestone_id,
+                subject,
+                edit: !no_edit,
+            }),
+            TicketCommands::Watchers { project, ticket_id } => {
+                TicketCommand::Watchers { project, ticket_id }
+            }
+            TicketCommands::SetWatchers {
+                project,
+                ticket_id,
+                user_ids,
+            } => TicketCommand::SetWatchers {
+                project,
+                ticket_id,
+                user_ids,
+            },
+            TicketCommands::Statuses { project } => TicketCommand::Statuses { project },
+            TicketCommands::Priorities { project } => TicketCommand::Priorities { project },
+            TicketCommands::Categories { project } => TicketCommand::Categories { project },
+            TicketCommands::Types { project } => TicketCommand::Types { project },
+        })
+    }
+}
+
+/// Ticket fields given on the command line or in the editor, as names or
+/// numeric IDs. When any field is given, the project's lookup tables are
+/// fetched and every value, numeric or not, is checked against them.
+#[derive(Default)]
+struct NamedFields {
+    status: Option<String>,
+    priority: Option<String>,
+    category: Option<String>,
+    assignee: Option<String>,
+    ticket_type: Option<String>,
+}
+
+#[derive(Default)]
+struct ResolvedFields {
+    status_id: Option<i64>,
+    priority_id: Option<i64>,
+    category_id: Option<i64>,
+    assignee_id: Option<i64>,
+    ticket_type: Option<String>,
+}
+
+type Resolver = fn(&ProjectLookup, &str) -> std::result::Result<i64, ResolveError>;
+
+impl NamedFields {
+    async fn resolve(self, client: &CodebaseClient, project: &str) -> Result<ResolvedFields> {
+        let given = [&self.status, &self.priority, &self.category, &self.assignee]
+            .into_iter()
+            .any(Option::is_some)
+            || self.ticket_type.is_some();
+        if !given {
+            return Ok(ResolvedFields::default());
         }
-        TicketCommands::Notes { project, ticket_id } => {
-            let notes = tickets::list_ticket_notes(client, &project, ticket_id).await?;
-            output::print_list(json, &notes, |notes| {
-                for n in notes {
-                    println!(
-                        "Note #{}: {}",
-                        n.id.unwrap_or(0).to_string().bold(),
-                        n.content.as_deref().unwrap_or("").dimmed()
-                    );
-                }
-            })?;
+        let lookup = ProjectLookup::fetch(client, project).await?;
+        let id = |value: Option<String>, resolve: Resolver| -> Result<Option<i64>> {
+            Ok(value.map(|v| resolve(&lookup, &v)).transpose()?)
+        };
+        Ok(ResolvedFields {
+            status_id: id(self.status, ProjectLookup::resolve_status)?,
+            priority_id: id(self.priority, ProjectLookup::resolve_priority)?,
+            category_id: id(self.category, ProjectLookup::resolve_category)?,
+            assignee_id: id(self.assignee, ProjectLookup::resolve_user)?,
+            ticket_type: self
+                .ticket_type
+                .map(|t| lookup.resolve_type(&t))
+                .transpose()?,
+        })
+    }
+}
+
+/// A name flag, or else its `--*-id` counterpart as text.
+fn name_or_id(name: Option<String>, id: Option<i64>) -> Option<String> {
+    name.or_else(|| id.map(|id| id.to_string()))
+}
+
+const TICKET_HELP: &[&str] = &[
+    "Fields take names or IDs; leave a field blank to use the project default.",
+    "Write the description below the closing line. An empty summary aborts.",
+    "Quitting without saving a change aborts.",
+];
+
+const NOTE_HELP: &[&str] = &[
+    "Fields take names or IDs; leave a field blank to keep its current value.",
+    "Write the note below the closing line. An empty note with no changes aborts.",
+    "Quitting without saving a change aborts.",
+];
+
+/// Whether ticket table or CSV/TSV output has a status, priority or category
+/// column, whose names come from the project's lookup tables.
+fn shows_names(out: &OutputOptions) -> anyhow::Result<bool> {
+    let columns = output::select_columns::<TicketRow>(out.columns.as_deref())?;
+    Ok(out.uses_columns()
+        && columns
+            .iter()
+            .any(|c| matches!(c.name, "status" | "priority" | "category")))
+}
+
+/// A ticket with the history of its notes, as `ticket show` prints it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TicketDetails {
+    #[serde(flatten)]
+    pub ticket: Ticket,
+    pub timeline: Vec<TimelineEntry>,
+}
+
+/// What a ticket command did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketOutput {
+    Tickets {
+        project: String,
+        tickets: Vec<Ticket>,
+        /// Names for the status, priority and category table columns; empty
+        /// unless the command asked for `names`.
+        lookup: ProjectLookup,
+    },
+    Ticket {
+        details: TicketDetails,
+        lookup: ProjectLookup,
+    },
+    Created(Ticket),
+    Notes(Vec<TicketNote>),
+    NoteAdded(TicketNote),
+    Watchers(Vec<Watcher>),
+    WatchersSet {
+        ticket_id: i64,
+        user_ids: Vec<i64>,
+    },
+    Statuses(Vec<TicketStatus>),
+    Priorities(Vec<TicketPriority>),
+    Categories(Vec<TicketCategory>),
+    Types(Vec<TicketType>),
+}
+
+/// Run `cmd` and return its result without printing anything.
+///
+/// `create` and `add-note` open an editor only when asked to with `edit`.
+pub async fn run(client: &CodebaseClient, cmd: TicketCommand) -> Result<TicketOutput> {
+    Ok(match cmd {
+        TicketCommand::List {
+            project,
+            pages,
+            names,
+        } => {
+            let tickets = pages
+                .fetch(tickets::paginate_tickets(client, &project))
+                .await?;
+            let lookup = names_for(client, &project, names).await;
+            TicketOutput::Tickets {
+                project,
+                tickets,
+                lookup,
+            }
         }
-        TicketCommands::AddNote {
+        TicketCommand::Search {
             project,
-            ticket_id,
-            content,
-            private,
-            status_id,
-            priority_id,
-            assignee_id,
-            category_id,
-            milestone_id,
-            subject,
+            query,
+            pages,
+            names,
         } => {
-            let changes = if status_id.is_some()
-                || priority_id.is_some()
-                || assignee_id.is_some()
-                || category_id.is_some()
-                || milestone_id.is_some()
-                || subject.is_some()
-            {
-                Some(NoteChanges {
-                    status_id,
-                    priority_id,
-                    category_id,
-                    assignee_id,
-                    milestone_id,
-                    subject,
-                })
-            } else {
-                None
+            let tickets = pages
+                .fetch(tickets::paginate_search_tickets(client, &project, &query))
+                .await?;
+            let lookup = names_for(client, &project, names).await;
+            TicketOutput::Tickets {
+                project,
+                tickets,
+                lookup,
+            }
+        }
+        TicketCommand::Show { project, ticket_id } => {
+            // `--raw-fields` shows only the ticket itself.
+            let unrecorded = client.without_raw_capture();
+            let (ticket, notes, lookup) = tokio::join!(
+                tickets::show_ticket(client, &project, ticket_id),
+                tickets::list_ticket_notes(&unrecorded, &project, ticket_id),
+                ProjectLookup::fetch(client, &project),
+            );
+            let (ticket, notes) = (ticket?, notes?);
+            // Names are cosmetic: if the lookup fails (e.g. /assignments is
+            // admin-only), show IDs instead.
+            let lookup = lookup.unwrap_or_default();
+            let details = TicketDetails {
+                timeline: lookup::timeline(&notes, &lookup),
+                ticket,
             };
-            let n = tickets::create_ticket_note(
-                client,
-                &project,
-                ticket_id,
-                content.as_deref(),
-                changes.as_ref(),
-                private,
-            )
-            .await?;
-            output::print_output(json, &n, || {
-                println!("Added note #{}", n.id.unwrap_or(0).to_string().bold());
-            })?;
+            TicketOutput::Ticket { details, lookup }
+        }
+        TicketCommand::Create(new) => TicketOutput::Created(create(client, new).await?),
+        TicketCommand::Notes { project, ticket_id } => {
+            TicketOutput::Notes(tickets::list_ticket_notes(client, &project, ticket_id).await?)
         }
-        TicketCommands::Watchers { project, ticket_id } => {
-            let watchers = tickets::list_watchers(client, &project, ticket_id).await?;
-            output::print_list(json, &watchers, |watchers| {
-                for w in watchers {
-                    println!("User ID: {}", w.watcher.unwrap_or(0));
-                }
-            })?;
+        TicketCommand::AddNote(new) => TicketOutput::NoteAdded(add_note(client, new).await?),
+        TicketCommand::Watchers { project, ticket_id } => {
+            TicketOutput::Watchers(tickets::list_watchers(client, &project, ticket_id).await?)
         }
-        TicketCommands::SetWatchers {
+        TicketCommand::SetWatchers {
             project,
             ticket_id,
             user_ids,
         } => {
             tickets::set_watchers(client, &project, ticket_id, &user_ids).await?;
-            println!("Set {} watchers on ticket #{}", user_ids.len(), ticket_id);
+            TicketOutput::WatchersSet {
+                ticket_id,
+                user_ids,
+            }
+        }
+        TicketCommand::Statuses { project } => {
+            TicketOutput::Statuses(tickets::list_statuses(client, &project).await?)
         }
-        TicketCommands::Statuses { project } => {
-            let statuses = tickets::list_statuses(client, &project).await?;
-            output::print_list(json, &statuses, |statuses| {
-                for s in statuses {
-                    let closed =
-                        output::colorize_bool(s.treat_as_closed.unwrap_or(false), "closed", "open");
-                    println!(
-                        "{}: {} ({})",
-                        s.id.unwrap_or(0),
-                        s.name.as_deref().unwrap_or("").bold(),
-                        closed,
-                    );
-                }
-            })?;
+        TicketCommand::Priorities { project } => {
+            TicketOutput::Priorities(tickets::list_priorities(client, &project).await?)
         }
-        TicketCommands::Priorities { project } => {
-            let priorities = tickets::list_priorities(client, &project).await?;
-            output::print_list(json, &priorities, |priorities| {
-                for p in priorities {
-                    let name = output::colorize_priority(p.name.as_deref().unwrap_or(""));
-                    let default_marker = if p.default.unwrap_or(false) {
-                        " *".green().to_string()
-                    } else {
-                        String::new()
-                    };
-                    println!("{}: {}{}", p.id.unwrap_or(0), name, default_marker);
-                }
-            })?;
+        TicketCommand::Categories { project } => {
+            TicketOutput::Categories(tickets::list_categories(client, &project).await?)
         }
-        TicketCommands::Categories { project } => {
-            let categories = tickets::list_categories(client, &project).await?;
-            output::print_list(json, &categories, |categories| {
-                for c in categories {
-                    println!(
-                        "{}: {}",
-                        c.id.unwrap_or(0),
-                        c.name.as_deref().unwrap_or("").bold()
-                    );
-                }
-            })?;
+        TicketCommand::Types { project } => {
+            TicketOutput::Types(tickets::list_types(client, &project).await?)
+        }
+    })
+}
+
+/// The lookup tables of `project` if `names` asks for them.
+async fn names_for(client: &CodebaseClient, project: &str, names: bool) -> ProjectLookup {
+    if !names {
+        return ProjectLookup::default();
+    }
+    // Names are cosmetic here: if the lookup fails, show IDs instead.
+    ProjectLookup::fetch(client, project)
+        .await
+        .unwrap_or_default()
+}
+
+/// `text` edited in the user's editor.
+fn edit(text: &str, allowed: &[&str]) -> Result<Draft> {
+    compose::edit(text)
+        .and_then(|edited| Draft::parse(&edited, allowed))
+        .map_err(CodebaseError::input)
+}
+
+async fn create(client: &CodebaseClient, new: NewTicket) -> Result<Ticket> {
+    let NewTicket {
+        project,
+        mut summary,
+        ticket_type,
+        priority,
+        status,
+        mut description,
+        assignee,
+        category,
+        milestone_id,
+        mut tags,
+        edit: open_editor,
+    } = new;
+    let mut named = NamedFields {
+        status,
+        priority,
+        category,
+        assignee,
+        ticket_type,
+    };
+
+    if description.is_none() && open_editor && compose::interactive() {
+        let template = Draft::new([
+            ("summary", summary.clone()),
+            (
+                "type",
+                named.ticket_type.clone().or(Some("task".to_string())),
+            ),
+            ("priority", named.priority.clone()),
+            ("status", named.status.clone()),
+            ("category", named.category.clone()),
+            ("assignee", named.assignee.clone()),
+            ("tags", tags.clone()),
+        ]);
+        let draft = edit(&template.render(TICKET_HELP), compose::TICKET_FIELDS)?;
+        summary = draft.field("summary");
+        tags = draft.field("tags");
+        description = draft.body();
+        named = NamedFields {
+            status: draft.field("status"),
+            priority: draft.field("priority"),
+            category: draft.field("category"),
+            assignee: draft.field("assignee"),
+            ticket_type: draft.field("type"),
+        };
+    }
+
+    let Some(summary) = summary.filter(|s| !s.trim().is_empty()) else {
+        return Err(CodebaseError::Input(
+            "A ticket summary is required".to_string(),
+        ));
+    };
+    let named = named.resolve(client, &project).await?;
+    tickets::create_ticket(
+        client,
+        &project,
+        &summary,
+        named.ticket_type.as_deref().unwrap_or("task"),
+        named.priority_id,
+        named.status_id,
+        description.as_deref(),
+        named.assignee_id,
+        named.category_id,
+        milestone_id,
+        tags.as_deref(),
+    )
+    .await
+}
+
+async fn add_note(client: &CodebaseClient, new: NewNote) -> Result<TicketNote> {
+    let NewNote {
+        project,
+        ticket_id,
+        mut content,
+        private,
+        status,
+        priority,
+        assignee,
+        category,
+        milestone_id,
+        mut subject,
+        edit: open_editor,
+    } = new;
+    let mut named = NamedFields {
+        status,
+        priority,
+        category,
+        assignee,
+        ticket_type: None,
+    };
+
+    let edited = content.is_none() && open_editor && compose::interactive();
+    if edited {
+        let template = Draft::new([
+            ("status", named.status.clone()),
+            ("priority", named.priority.clone()),
+            ("category", named.category.clone()),
+            ("assignee", named.assignee.clone()),
+            ("subject", subject.clone()),
+        ]);
+        let draft = edit(&template.render(NOTE_HELP), compose::NOTE_FIELDS)?;
+        subject = draft.field("subject");
+        content = draft.body();
+        named = NamedFields {
+            status: draft.field("status"),
+            priority: draft.field("priority"),
+            category: draft.field("category"),
+            assignee: draft.field("assignee"),
+            ticket_type: None,
+        };
+    }
+
+    let ResolvedFields {
+        status_id,
+        priority_id,
+        category_id,
+        assignee_id,
+        ..
+    } = named.resolve(client, &project).await?;
+    let changes = if status_id.is_some()
+        || priority_id.is_some()
+        || assignee_id.is_some()
+        || category_id.is_some()
+        || milestone_id.is_some()
+        || subject.is_some()
+    {
+        Some(NoteChanges {
+            status_id,
+            priority_id,
+            category_id,
+            assignee_id,
+            milestone_id,
+            subject,
+        })
+    } else {
+        None
+    };
+    if edited && changes.is_none() && content.is_none() {
+        return Err(CodebaseError::Input(
+            "Aborting: the note is empty and changes nothing".to_string(),
+        ));
+    }
+    tickets::create_ticket_note(
+        client,
+        &project,
+        ticket_id,
+        content.as_deref(),
+        changes.as_ref(),
+        private,
+    )
+    .await
+}
+
+pub async fn execute(
+    client: &CodebaseClient,
+    cmd: TicketCommands,
+    out: &OutputOptions,
+) -> anyhow::Result<()> {
+    let mut cmd = TicketCommand::try_from(cmd)?;
+    if let TicketCommand::List { names, .. } | TicketCommand::Search { names, .. } = &mut cmd {
+        *names = shows_names(out)?;
+    }
+    run(client, cmd).await?.print(out)
+}
+
+impl TicketOutput {
+    pub fn print(&self, out: &OutputOptions) -> anyhow::Result<()> {
+        match self {
+            TicketOutput::Tickets {
+                tickets, lookup, ..
+            } => output::print_list_with(out, tickets, |ticket| TicketRow { ticket, lookup }),
+            TicketOutput::Ticket { details, lookup } => {
+                output::print_output(out, details, || print_ticket(details, lookup))
+            }
+            TicketOutput::Created(t) => output::print_output(out, t, || {
+                println!(
+                    "Created ticket #{}: {}",
+                    t.ticket_id.unwrap_or(0).to_string().bold(),
+                    t.summary.as_deref().unwrap_or("")
+                );
+            }),
+            TicketOutput::Notes(notes) => output::print_list(out, notes),
+            TicketOutput::NoteAdded(n) => output::print_output(out, n, || {
+                println!("Added note #{}", n.id.unwrap_or(0).to_string().bold());
+            }),
+            TicketOutput::Watchers(watchers) => output::print_list(out, watchers),
+            TicketOutput::WatchersSet {
+                ticket_id,
+                user_ids,
+            } => {
+                println!("Set {} watchers on ticket #{}", user_ids.len(), ticket_id);
+                Ok(())
+            }
+            TicketOutput::Statuses(statuses) => output::print_list(out, statuses),
+            TicketOutput::Priorities(priorities) => output::print_list(out, priorities),
+            TicketOutput::Categories(categories) => output::print_list(out, categories),
+            TicketOutput::Types(types) => output::print_list(out, types),
+        }
+    }
+}
+
+fn print_ticket(details: &TicketDetails, lookup: &ProjectLookup) {
+    let t = &details.ticket;
+    println!(
+        "#{} {}",
+        t.ticket_id.unwrap_or(0).to_string().bold(),
+        t.summary.as_deref().unwrap_or("").bold()
+    );
+
+    let named = |id: Option<i64>, name: fn(&ProjectLookup, i64) -> Option<&str>| {
+        id.map(|id| name(lookup, id).map_or_else(|| format!("#{}", id), str::to_string))
+            .unwrap_or_default()
+    };
+    let fields = [
+        (
+            "Type",
+            output::colorize_ticket_type(t.ticket_type.as_deref().unwrap_or("")),
+        ),
+        (
+            "Status",
+            output::colorize_status(&named(t.status_id, ProjectLookup::status_name)),
+        ),
+        (
+            "Priority",
+            output::colorize_priority(&named(t.priority_id, ProjectLookup::priority_name)),
+        ),
+        (
+            "Category",
+            named(t.category_id, ProjectLookup::category_name),
+        ),
+        ("Assignee", t.assignee.clone().unwrap_or_default()),
+        ("Reporter", t.reporter.clone().unwrap_or_default()),
+        (
+            "Milestone",
+            t.milestone_id
+                .map(|id| format!("#{}", id))
+                .unwrap_or_default(),
+        ),
+        ("Tags", t.tags.clone().unwrap_or_default()),
+    ];
+    for (label, value) in fields {
+        if !value.is_empty() {
+            println!("{}: {}", label.dimmed(), value);
+        }
+    }
+
+    if let Some(description) = t.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        println!();
+        println!("{}", description.trim());
+    }
+
+    if details.timeline.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", "Timeline".bold());
+    for entry in &details.timeline {
+        let private = if entry.note.private.unwrap_or(false) {
+            format!(" {}", "(private)".yellow())
+        } else {
+            String::new()
+        };
+        println!(
+            "{} {}{}",
+            "●".dimmed(),
+            entry.note.time_added.as_deref().unwrap_or("").dimmed(),
+            private
+        );
+        for transition in &entry.transitions {
+            println!("    {}", transition.to_string().cyan());
         }
-        TicketCommands::Types { project } => {
-            let types = tickets::list_types(client, &project).await?;
-            output::print_list(json, &types, |types| {
-                for t in types {
-                    let name = output::colorize_ticket_type(t.name.as_deref().unwrap_or(""));
-                    println!("{}: {}", t.id.unwrap_or(0), name);
-                }
-            })?;
+        if let Some(content) = entry
+            .note
+            .content
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+        {
+            for line in content.trim().lines() {
+                println!("    {}", line);
+            }
         }
     }
-    Ok(())
 }
@@ -0,0 +1,209 @@
+//! Composing ticket descriptions and notes in `$EDITOR`.
+//!
+//! A draft is a small front-matter block of `key: value` lines followed by a
+//! free-form body:
+//!
+//! ```text
+//! ---
+//! summary: Login fails with SSO
+//! type: bug
+//! priority: high
+//! ---
+//! Steps to reproduce...
+//! ```
+
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+/// Front-matter keys accepted when creating a ticket.
+pub const TICKET_FIELDS: &[&str] = &[
+    "summary", "type", "priority", "status", "category", "assignee", "tags",
+];
+
+/// Front-matter keys accepted when adding a note.
+pub const NOTE_FIELDS: &[&str] = &["status", "priority", "category", "assignee", "subject"];
+
+const DELIMITER: &str = "---";
+
+/// Parsed (or to-be-rendered) editor contents.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Draft {
+    /// Front-matter fields in display order.
+    pub fields: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Draft {
+    pub fn new<K: Into<String>>(fields: impl IntoIterator<Item = (K, Option<String>)>) -> Self {
+        Draft {
+            fields: fields
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.unwrap_or_default()))
+                .collect(),
+            body: String::new(),
+        }
+    }
+
+    /// The value of `key`, or `None` if it is missing or left blank.
+    pub fn field(&self, key: &str) -> Option<String> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// The body, or `None` if it is blank.
+    pub fn body(&self) -> Option<String> {
+        Some(self.body.trim().to_string()).filter(|b| !b.is_empty())
+    }
+
+    /// Render the draft for editing; each `help` line becomes a `#` comment
+    /// in the front matter.
+    pub fn render(&self, help: &[&str]) -> String {
+        let mut out = String::from(DELIMITER);
+        out.push('\n');
+        for line in help {
+            out.push_str(&format!("# {}\n", line));
+        }
+        for (key, value) in &self.fields {
+            out.push_str(&format!("{}: {}\n", key, value));
+        }
+        out.push_str(DELIMITER);
+        out.push('\n');
+        out.push_str(&self.body);
+        out
+    }
+
+    /// Parse edited text, rejecting front-matter keys not in `allowed`.
+    /// Text without a leading `---` line is treated as all body.
+    pub fn parse(text: &str, allowed: &[&str]) -> Result<Self> {
+        let mut lines = text.lines();
+        let starts_with_front_matter = text
+            .lines()
+            .next()
+            .is_some_and(|l| l.trim_end() == DELIMITER);
+        if !starts_with_front_matter {
+            return Ok(Draft {
+                fields: Vec::new(),
+                body: text.to_string(),
+            });
+        }
+        lines.next();
+
+        let mut fields = Vec::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line == DELIMITER {
+                closed = true;
+                break;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(':').with_context(|| {
+                format!("Expected `key: value` in front matter, got `{}`", line)
+            })?;
+            let key = key.trim().to_lowercase();
+            if !allowed.contains(&key.as_str()) {
+                anyhow::bail!(
+                    "Unknown field `{}` in front matter (expected one of: {})",
+                    key,
+                    allowed.join(", ")
+                );
+            }
+            fields.push((key, value.trim().to_string()));
+        }
+        if !closed {
+            anyhow::bail!("Front matter is missing its closing `{}` line", DELIMITER);
+        }
+
+        Ok(Draft {
+            fields,
+            body: lines.collect::<Vec<_>>().join("\n"),
+        })
+    }
+}
+
+/// Whether both stdin and stdout are attached to a terminal, i.e. whether
+/// it is reasonable to open an editor.
+pub fn interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Read text from `path`, or from stdin when `path` is `-`.
+pub fn read_input(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read from stdin")?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))
+    }
+}
+
+/// The editor command from `$VISUAL`/`$EDITOR`, falling back to `vi`.
+pub fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|v| !v.trim().is_empty()))
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Open the user's [`editor`] on `initial` and return the saved text.
+pub fn edit(initial: &str) -> Result<String> {
+    edit_with(&editor(), initial)
+}
+
+/// Run `editor` on a private temporary file holding `initial` and return the
+/// saved text. Fails if the file is left unchanged or emptied.
+pub fn edit_with(editor: &str, initial: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("cb-")
+        .suffix(".md")
+        .tempfile()
+        .context("Failed to create temporary file for editor")?;
+    file.write_all(initial.as_bytes())
+        .and_then(|_| file.flush())
+        .context("Failed to create temporary file for editor")?;
+    let path = file.path();
+
+    // Run through the shell so editors configured with arguments
+    // (e.g. `code --wait`) work.
+    let status = if cfg!(windows) {
+        Command::new("cmd")
+            .args(["/C", &format!("{} \"{}\"", editor, path.display())])
+            .status()
+    } else {
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("cb-editor")
+            .arg(path)
+            .status()
+    };
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => anyhow::bail!("Editor `{}` exited with {}", editor, status),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to launch editor `{}`", editor));
+        }
+    }
+    // Editors may replace the file rather than write to it, so read it back
+    // by path.
+    let text = fs::read_to_string(path).context("Failed to read back edited file")?;
+    if text.trim().is_empty() {
+        anyhow::bail!("Aborting: the file is empty");
+    }
+    if text == initial {
+        anyhow::bail!("Aborting: the file was not changed");
+    }
+    Ok(text)
+}
@@ -3,6 +3,8 @@ use std::process::Command;
 /// Detected project and repository from the current git working directory.
 #[derive(Debug, Clone)]
 pub struct GitContext {
+    /// CodebaseHQ account name (the SSH path prefix or HTTPS subdomain).
+    pub account: Option<String>,
     pub project: String,
     pub repo: Option<String>,
 }
@@ -34,17 +36,19 @@ fn get_remote_url(remote: &str) -> Option<String> {
 fn parse_codebase_remote(url: &str) -> Option<GitContext> {
     // SSH: git@codebasehq.com:account/project/repo.git
     if url.contains("codebasehq.com:") {
-        let path = url.split(':').last()?;
+        let path = url.split(':').next_back()?;
         let path = path.strip_suffix(".git").unwrap_or(path);
         let parts: Vec<&str> = path.split('/').collect();
         return match parts.len() {
             // account/project/repo
             3 => Some(GitContext {
+                account: Some(parts[0].to_string()),
                 project: parts[1].to_string(),
                 repo: Some(parts[2].to_string()),
             }),
             // account/project
             2 => Some(GitContext {
+                account: Some(parts[0].to_string()),
                 project: parts[1].to_string(),
                 repo: None,
             }),
@@ -54,15 +58,18 @@ fn parse_codebase_remote(url: &str) -> Option<GitContext> {
 
     // HTTPS: https://account.codebasehq.com/project/repo.git
     if url.contains("codebasehq.com/") {
+        let account = https_account(url);
         let path = url.split("codebasehq.com/").nth(1)?;
         let path = path.strip_suffix(".git").unwrap_or(path);
         let parts: Vec<&str> = path.split('/').collect();
         return match parts.len() {
             2 => Some(GitContext {
+                account,
                 project: parts[0].to_string(),
                 repo: Some(parts[1].to_string()),
             }),
             1 if !parts[0].is_empty() => Some(GitContext {
+                account,
                 project: parts[0].to_string(),
                 repo: None,
             }),
@@ -73,6 +80,49 @@ fn parse_codebase_remote(url: &str) -> Option<GitContext> {
     None
 }
 
+/// Extract the account subdomain from `https://[user@]account.codebasehq.com/...`.
+fn https_account(url: &str) -> Option<String> {
+    let host = url.split("://").nth(1)?.split('/').next()?;
+    let host = host.rsplit('@').next()?;
+    let account = host.strip_suffix(".codebasehq.com")?;
+    if account.is_empty() || account.contains('.') {
+        None
+    } else {
+        Some(account.to_string())
+    }
+}
+
+/// Fill in project and repository arguments that weren't given from the
+/// git remote, failing if neither says what they are.
+pub fn resolve_project_repo(
+    project: Option<String>,
+    repo: Option<String>,
+) -> anyhow::Result<(String, String)> {
+    let ctx = detect();
+    let project = match project {
+        Some(p) => p,
+        None => ctx
+            .as_ref()
+            .map(|c| c.project.clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No project specified and could not detect from git remote.\n\
+                     Either provide the project argument or run from a git repo with a CodebaseHQ remote."
+                )
+            })?,
+    };
+    let repo = match repo {
+        Some(r) => r,
+        None => ctx.and_then(|c| c.repo).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No repository specified and could not detect from git remote.\n\
+                     Please provide the repository argument."
+            )
+        })?,
+    };
+    Ok((project, repo))
+}
+
 /// Get the current git branch name.
 pub fn current_branch() -> Option<String> {
     let output = Command::new("git")
@@ -115,6 +165,7 @@ mod tests {
     fn test_parse_ssh_remote() {
         let url = "git@codebasehq.com:mycompany/myproject/myrepo.git";
         let ctx = parse_codebase_remote(url).unwrap();
+        assert_eq!(ctx.account.as_deref(), Some("mycompany"));
         assert_eq!(ctx.project, "myproject");
         assert_eq!(ctx.repo.unwrap(), "myrepo");
     }
@@ -123,6 +174,7 @@ mod tests {
     fn test_parse_https_remote() {
         let url = "https://mycompany.codebasehq.com/myproject/myrepo.git";
         let ctx = parse_codebase_remote(url).unwrap();
+        assert_eq!(ctx.account.as_deref(), Some("mycompany"));
         assert_eq!(ctx.project, "myproject");
         assert_eq!(ctx.repo.unwrap(), "myrepo");
     }
@@ -135,6 +187,13 @@ mod tests {
         assert_eq!(ctx.repo.unwrap(), "myrepo");
     }
 
+    #[test]
+    fn test_parse_https_remote_with_user_info() {
+        let url = "https://jdoe@mycompany.codebasehq.com/myproject/myrepo.git";
+        let ctx = parse_codebase_remote(url).unwrap();
+        assert_eq!(ctx.account.as_deref(), Some("mycompany"));
+    }
+
     #[test]
     fn test_parse_non_codebase_remote() {
         let url = "git@github.com:user/repo.git";
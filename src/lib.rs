@@ -1,3 +1,9 @@
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod commands;
+pub mod compose;
 pub mod git_context;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
 pub mod output;
@@ -1,20 +1,27 @@
-mod api;
-mod commands;
-mod git_context;
-mod output;
-
-use std::io;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
 
 use clap::{CommandFactory, Parser};
 use clap_complete::{Shell, generate};
+use codebase_cli::{api, commands, output};
 
+use api::cache::ResponseCache;
+use api::cassette::Cassette;
 use api::client::CodebaseClient;
-use api::config::Config;
+use api::config::{Config, ConfigFile};
+use api::error::CodebaseError;
+use api::raw::RawCapture;
 use commands::activity::ActivityCommands;
+use commands::api::ApiArgs;
+use commands::cache::CacheCommands;
 use commands::milestones::MilestoneCommands;
 use commands::projects::ProjectCommands;
 use commands::repositories::RepoCommands;
 use commands::tickets::TicketCommands;
+use output::{JqFilter, OutputFormat, OutputOptions, Template, UnknownColumn};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
 
 #[derive(Parser)]
 #[command(name = "cb")]
@@ -27,18 +34,27 @@ GETTING STARTED:
     2. Run: cb login <account/username> <api-key>
     3. Credentials are stored in ~/.config/cb/config.toml
 
+PROFILES:
+    Credentials for several accounts can be kept side by side:
+        cb login work/jdoe KEY --profile work
+        cb login personal/jdoe KEY --profile personal
+    The profile is chosen from --profile, then CB_PROFILE, then the account in
+    the git remote URL, then default_profile in the config file.
+
 COMMANDS IN DETAIL:
     login          Store API credentials for future use
     project        List, show, create, update, delete projects; manage groups and user assignments
     repo           List, show, create, delete repositories; browse branches, commits, and files;
                    manage hooks and deployments; create and manage merge requests
-    ticket         List, search, create tickets; add notes to update ticket fields; manage
+    ticket         List, search, show, create tickets; add notes to update ticket fields; manage
                    watchers; view statuses, priorities, categories, and types
     milestone      List, create, update milestones with deadlines and responsible users
     activity       View account-wide or project-specific activity feeds with pagination
     status         Dashboard showing your projects and open tickets
     browse         Open a project, repo, or ticket in the web browser
     pr             Shorthand for merge request commands (alias for repo merge-request commands)
+    api            Send a request to any API endpoint and print the XML (or JSON with --json)
+    cache          Clear the local cache of lookup data
     completions    Generate shell completions for bash, zsh, or fish
     version        Display the current version of cb
 
@@ -48,31 +64,110 @@ CONTEXTUAL DEFAULTS:
     git remote URL (origin).
 
 OUTPUT:
-    Use --json on any command to get machine-readable JSON output instead of
-    the default human-readable colored output.
+    Use --format on any command to choose the output: table (default colored
+    text), json, ndjson (one JSON object per line), csv, tsv or yaml.
+    --json is shorthand for --format json. For lists, csv and tsv contain the
+    table's columns, so --columns applies to them too.
+
+    --template renders each result (each item of a list) with a Go-style
+    template: {{field}} inserts a field from the JSON output, and the helpers
+    color, truncate and timeago can be called or piped into, e.g.
+    --template '{{ticket_id}}\\t{{assignee}}\\t{{summary | truncate 50}}'
+
+    --jq EXPR runs a jq filter (built in; no jq install needed) over the JSON
+    output, e.g. --jq '.[] | select(.assignee == \"jdoe\") | .summary'.
+    String results print without quotes; other results print as JSON, one
+    per line with --format ndjson.
+
+    --raw-fields outputs the API's responses converted field for field, so
+    JSON (and csv, yaml, --jq, --template) includes fields cb doesn't model.
+
+    List commands print aligned tables sized to the terminal; long text
+    columns (summary, subject, message) are truncated to fit. Choose columns
+    with --columns, e.g. --columns id,status,assignee,summary. An unknown
+    column name lists the available ones. Set COLUMNS to override the width.
 
 EXAMPLES:
     cb login mycompany/jdoe abc123def456
     cb project list
     cb project list --json
+    cb ticket list my-project --format csv > tickets.csv
     cb repo branches my-project my-repo
     cb repo commits my-project my-repo main --path src/
-    cb ticket create my-project \"Fix bug\" --ticket-type bug --priority-id 1
-    cb ticket add-note my-project 42 --content \"Fixed\" --status-id 3
+    cb ticket show my-project 42
+    cb ticket create my-project \"Fix bug\" --ticket-type bug --priority high --assignee jdoe
+    cb ticket add-note my-project 42 --content \"Fixed\" --status \"In Progress\"
     cb pr list my-project my-repo
     cb pr create my-project my-repo feature main \"Add feature\"
     cb status
+    cb api GET /my-project/tickets/42/watchers --json
+    cb api POST /my-project/time_sessions -f 'time-session[summary]=Review' -f 'time-session[minutes]=30'
     cb browse my-project
     cb completions zsh
     cb activity account --page 2
+    cb ticket list my-project --all
+
+PAGINATION:
+    ticket list, ticket search, repo commits and activity feeds return only the
+    first page by default. Use --all to fetch every page or --limit N to stop
+    after N results.
+
+EDITOR:
+    ticket create without --description and ticket add-note without --content
+    open $VISUAL/$EDITOR on a terminal with a front-matter template. Use
+    --description-file/--content-file (`-` for stdin) or --no-edit to skip it.
+
+CACHING:
+    Ticket statuses, priorities, categories and types (24h), project users (1h)
+    and the project list (5m) are cached under ~/.config/cb/cache, separately
+    for each account and user. Pass --no-cache to bypass the cache, or run
+    `cb cache clear` to empty it.
 
 RETRY BEHAVIOR:
-    The client automatically retries with exponential backoff (1s, 2s, 4s, 8s, 16s)
-    on HTTP 429 (rate limit), 503 (service unavailable), and 529 (overloaded) responses.
+    Requests that get HTTP 429 (rate limit), 503 (service unavailable) or 529
+    (overloaded), fail to connect, or time out are retried with exponential
+    backoff (about 1s, 2s, 4s, 8s, 16s), or after the server's Retry-After.
+    A 429 is retried for every method; POST requests are not retried after a
+    5xx or timeout, so a create is never sent twice. Use --max-attempts N to change the number of attempts,
+    or configure the [retry] table in the config file. Requests are also
+    paced to 5 per second after a burst of 10 ([rate_limit] in the config).
+
+TROUBLESHOOTING:
+    -v/--verbose logs each HTTP request (method, URL, status, latency, attempt)
+    to stderr; --debug also logs request and response bodies, with passwords,
+    tokens and API keys redacted.
+
+EXIT CODES:
+    0  Success
+    1  General error (including unclassified API errors)
+    2  Invalid command-line usage (including an unknown --columns name, or a
+       --status/--assignee/etc. name that matches nothing or more than one
+       entry)
+    3  Unauthorized (401): credentials missing, wrong, or expired
+    4  Not found (404): project, repository, ticket, etc. does not exist
+    5  Validation failed (422): the API rejected the submitted fields
+    6  Rate limited: still throttled after all retries
+    7  Network/transport failure
+    8  Unexpected response body (XML decode failure)
 
 ENVIRONMENT:
     Credentials file:  ~/.config/cb/config.toml
-    API base URL:      https://api3.codebasehq.com
+    CB_PROFILE:        Profile to use when --profile is not given
+    CB_API_USERNAME:   API username, overriding the config file unless
+                       --profile is given
+    CB_API_KEY:        API key, overriding the config file unless
+                       --profile is given
+                       (or set credential_command in a profile)
+    VISUAL, EDITOR:    Editor for composing tickets and notes (default: vi)
+    COLUMNS:           Table width (default: terminal width)
+    HTTPS_PROXY:       Proxy for API requests (or set proxy in [http] of the
+                       config file, along with timeouts and ca_certs)
+    CB_API_URL:        API base URL, overriding the config file
+                       (default: https://api3.codebasehq.com)
+    CB_RECORD:         Record API requests and responses to this file, with
+                       credentials scrubbed
+    CB_REPLAY:         Answer API requests from a file recorded with CB_RECORD
+                       instead of the network
 
 AUTHOR:
     Sam Krishna <samkrishna@gmail.com>
@@ -81,10 +176,57 @@ SEE ALSO:
     CodebaseHQ API docs: https://support.codebasehq.com/kb
 ")]
 struct Cli {
-    /// Output results as JSON
+    /// Output format for results
+    #[arg(long, global = true, value_enum, value_name = "FORMAT")]
+    format: Option<OutputFormat>,
+
+    /// Render each result with a template, e.g. '{{ticket_id}}\t{{summary}}'
+    #[arg(
+        long,
+        global = true,
+        value_parser = Template::parse,
+        conflicts_with_all = ["format", "json"]
+    )]
+    template: Option<Template>,
+
+    /// Filter the JSON output with a jq expression, e.g. '.[] | .summary'
+    #[arg(long, global = true, value_name = "EXPR", value_parser = JqFilter::parse, conflicts_with = "template")]
+    jq: Option<JqFilter>,
+
+    /// Output every field of the API's responses, not just the modelled ones
     #[arg(long, global = true)]
+    raw_fields: bool,
+
+    /// Output results as JSON (same as --format json)
+    #[arg(long, global = true, conflicts_with = "format")]
     json: bool,
 
+    /// Table columns to show for list commands, comma-separated (e.g. id,status,summary)
+    #[arg(long, global = true, value_delimiter = ',', value_name = "COLUMNS")]
+    columns: Option<Vec<String>>,
+
+    /// Credentials profile to use (overrides CB_PROFILE and git remote detection)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Bypass the on-disk cache of statuses, priorities, users and projects
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Attempts per request, including the first; 1 disables retries
+    /// (overrides retry.max_attempts in the config file)
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    max_attempts: Option<u32>,
+
+    /// Log each HTTP request's method, URL, status and latency to stderr
+    #[arg(long, short = 'v', global = true)]
+    verbose: bool,
+
+    /// Like --verbose, and also log request and response bodies (with
+    /// passwords and keys redacted)
+    #[arg(long, global = true)]
+    debug: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -95,7 +237,8 @@ enum Commands {
     Login {
         /// API username (format: account/username)
         api_username: String,
-        /// API key
+        /// API key (saved under --profile when given, otherwise as the
+        /// default credentials)
         api_key: String,
     },
     /// Manage projects
@@ -137,6 +280,13 @@ enum Commands {
         #[command(subcommand)]
         command: commands::pr::PrCommands,
     },
+    /// Make an authenticated request to any API endpoint
+    Api(ApiArgs),
+    /// Manage the local cache of lookup data
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -146,64 +296,133 @@ enum Commands {
     Version,
 }
 
-fn load_client() -> anyhow::Result<CodebaseClient> {
-    let config = Config::load()?;
-    Ok(CodebaseClient::new(
+fn load_client(
+    profile: Option<&str>,
+    use_cache: bool,
+    max_attempts: Option<u32>,
+    out: &OutputOptions,
+) -> anyhow::Result<CodebaseClient> {
+    let cassette = Cassette::from_env()?;
+    let config = if cassette.as_ref().is_some_and(Cassette::is_replay) {
+        Config::load_for_replay(profile)?
+    } else {
+        Config::load_profile(profile)?
+    };
+    let file = ConfigFile::load()?;
+    let mut retry = file.retry.policy()?;
+    if let Some(n) = max_attempts {
+        retry.max_attempts = n;
+    }
+    let mut builder = CodebaseClient::builder(
         config.account().to_string(),
         config.api_username.clone(),
-        config.api_key,
-    ))
+        config.api_key.clone(),
+    )
+    .base_url(config.base_url().to_string())
+    .retry_policy(retry)
+    .rate_limiter(file.rate_limit.limiter());
+    // Cached responses would bypass the cassette.
+    if let Some(cassette) = cassette {
+        builder = builder.cassette(cassette);
+    } else if use_cache {
+        builder = builder.cache(ResponseCache::for_endpoint(
+            &Config::cache_dir()?,
+            config.base_url(),
+            config.account(),
+            config.username(),
+        ));
+    }
+    if let Some(raw) = &out.raw_fields {
+        builder = builder.raw_capture(raw.clone());
+    }
+    Ok(file.http.apply(builder)?.build()?)
 }
 
-/// Resolve a project argument: use the provided value or fall back to git context.
-fn resolve_project(project: Option<String>) -> anyhow::Result<String> {
-    match project {
-        Some(p) => Ok(p),
-        None => git_context::detect()
-            .map(|ctx| ctx.project)
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No project specified and could not detect from git remote.\n\
-                     Either provide the project argument or run from a git repo with a CodebaseHQ remote."
-                )
-            }),
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.verbose, cli.debug);
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader went away (e.g. `cb ... | head`); nothing left to say.
+        Err(err) if is_broken_pipe(&err) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::from(exit_code(&err))
+        }
     }
 }
 
-/// Resolve project and repo arguments from git context.
-fn resolve_project_repo(
-    project: Option<String>,
-    repo: Option<String>,
-) -> anyhow::Result<(String, String)> {
-    let ctx = git_context::detect();
-    let project = match project {
-        Some(p) => p,
-        None => ctx
-            .as_ref()
-            .map(|c| c.project.clone())
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No project specified and could not detect from git remote.\n\
-                     Either provide the project argument or run from a git repo with a CodebaseHQ remote."
-                )
-            })?,
+/// Log this crate's HTTP traffic to stderr: retries always, every request at
+/// `-v`, plus redacted bodies at `--debug`.
+fn init_tracing(verbose: bool, debug: bool) {
+    // Warnings such as retries are shown even without --verbose.
+    let level = match (verbose, debug) {
+        (_, true) => Level::DEBUG,
+        (true, false) => Level::INFO,
+        (false, false) => Level::WARN,
     };
-    let repo = match repo {
-        Some(r) => r,
-        None => ctx.and_then(|c| c.repo).ok_or_else(|| {
-            anyhow::anyhow!(
-                "No repository specified and could not detect from git remote.\n\
-                     Please provide the repository argument."
-            )
-        })?,
-    };
-    Ok((project, repo))
+    let filter = Targets::new().with_target("codebase_cli", level);
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(io::stderr)
+                .with_ansi(io::stderr().is_terminal())
+                .with_target(false),
+        )
+        .with(filter)
+        .init();
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
-    let json = cli.json;
+/// Map an error to the documented process exit code (see EXIT CODES in --help).
+fn exit_code(err: &anyhow::Error) -> u8 {
+    if err.chain().any(|e| e.is::<UnknownColumn>()) {
+        return 2;
+    }
+    err.chain()
+        .find_map(|e| e.downcast_ref::<CodebaseError>())
+        .map_or(1, CodebaseError::exit_code)
+}
+
+fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        let kind = match e.downcast_ref::<io::Error>() {
+            Some(e) => Some(e.kind()),
+            None => e
+                .downcast_ref::<serde_json::Error>()
+                .and_then(serde_json::Error::io_error_kind),
+        };
+        kind == Some(io::ErrorKind::BrokenPipe)
+    })
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let out = OutputOptions {
+        format: if cli.json {
+            OutputFormat::Json
+        } else {
+            cli.format.unwrap_or_default()
+        },
+        columns: cli.columns,
+        template: cli.template,
+        jq: cli.jq,
+        raw_fields: cli.raw_fields.then(RawCapture::new),
+    };
+    if out.jq.is_some()
+        && !matches!(
+            out.format,
+            OutputFormat::Table | OutputFormat::Json | OutputFormat::Ndjson
+        )
+    {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--jq prints JSON and can only be combined with --format json or ndjson",
+            )
+            .exit();
+    }
+    let profile = cli.profile.as_deref();
+    let use_cache = !cli.no_cache;
 
     match cli.command {
         Commands::Login {
@@ -213,45 +432,59 @@ async fn main() -> anyhow::Result<()> {
             let config = Config {
                 api_username: api_username.clone(),
                 api_key,
+                base_url: None,
             };
-            config.save()?;
+            match profile {
+                Some(name) => config.save_profile(name)?,
+                None => config.save()?,
+            }
             println!(
-                "Credentials saved for {} at {}",
+                "Credentials saved for {}{} at {}",
                 api_username,
+                profile
+                    .map(|n| format!(" (profile '{}')", n))
+                    .unwrap_or_default(),
                 Config::config_path()?.display()
             );
         }
         Commands::Project { command } => {
-            let client = load_client()?;
-            commands::projects::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::projects::execute(&client, command, &out).await?;
         }
         Commands::Repo { command } => {
-            let client = load_client()?;
-            commands::repositories::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::repositories::execute(&client, command, &out).await?;
         }
         Commands::Ticket { command } => {
-            let client = load_client()?;
-            commands::tickets::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::tickets::execute(&client, command, &out).await?;
         }
         Commands::Milestone { command } => {
-            let client = load_client()?;
-            commands::milestones::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::milestones::execute(&client, command, &out).await?;
         }
         Commands::Activity { command } => {
-            let client = load_client()?;
-            commands::activity::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::activity::execute(&client, command, &out).await?;
         }
         Commands::Status => {
-            let client = load_client()?;
-            commands::status::execute(&client, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::status::execute(&client, &out).await?;
         }
         Commands::Browse { project, target } => {
-            let config = Config::load()?;
+            let config = Config::load_profile(profile)?;
             commands::browse::execute(&config, project, target)?;
         }
         Commands::Pr { command } => {
-            let client = load_client()?;
-            commands::pr::execute(&client, command, json).await?;
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::pr::execute(&client, command, &out).await?;
+        }
+        Commands::Api(args) => {
+            let client = load_client(profile, use_cache, cli.max_attempts, &out)?;
+            commands::api::execute(&client, args, &out).await?;
+        }
+        Commands::Cache { command } => {
+            commands::cache::execute(command)?;
         }
         Commands::Completions { shell } => {
             let mut cmd = Cli::command();
@@ -0,0 +1,138 @@
+//! A local stand-in for the CodebaseHQ API, for developing scripts against
+//! `cb` without touching real data. Run it with the `cb-mock-server` binary
+//! and point `cb` at it with `CB_API_URL`.
+//!
+//! The server answers the XML endpoints wrapped by `api::projects`,
+//! `api::tickets`, `api::milestones`, `api::repositories` and
+//! `api::activity` from an in-memory [`Store`] seeded by a fixture file.
+//! Creates and updates change the store, so whole workflows (create a
+//! ticket, comment on it, close it, see it in the activity feed) can be
+//! exercised; nothing is written back to the fixture. Any credentials are
+//! accepted.
+//!
+//! Available with the `mock-server` feature, which `test-util` turns on.
+
+pub mod store;
+mod xml;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+pub use store::{Fault, Reply, Store};
+
+/// A bound mock server; [`run`](Self::run) serves requests until dropped.
+pub struct MockServer {
+    listener: TcpListener,
+    store: Arc<Mutex<Store>>,
+}
+
+impl MockServer {
+    /// Listen on `addr` (port 0 picks a free port) and serve `store`.
+    pub async fn bind(addr: SocketAddr, store: Store) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            store: Arc::new(Mutex::new(store)),
+        })
+    }
+
+    /// The base URL to give `cb`, e.g. `http://127.0.0.1:4000`.
+    pub fn url(&self) -> String {
+        match self.listener.local_addr() {
+            Ok(addr) => format!("http://{}", addr),
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Accept connections and answer requests forever.
+    pub async fn run(self) -> io::Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let store = Arc::clone(&self.store);
+            tokio::spawn(async move {
+                let service = service_fn(move |req| respond(Arc::clone(&store), req));
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    warn!(error = %e, "connection failed");
+                }
+            });
+        }
+    }
+}
+
+/// How long a [`Fault::Timeout`] request is held before its connection is
+/// dropped; far longer than any client waits.
+const TIMEOUT_HOLD: Duration = Duration::from_secs(600);
+
+async fn respond(
+    store: Arc<Mutex<Store>>,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, io::Error> {
+    let (parts, body) = req.into_parts();
+    let body = match body.collect().await {
+        Ok(body) => String::from_utf8_lossy(&body.to_bytes()).into_owned(),
+        Err(_) => String::new(),
+    };
+    let target = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let (fault, reply) = {
+        let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+        match (store.take_fault(), api_user(&parts.headers)) {
+            (Some(Fault::Reply(reply)), _) => (None, reply),
+            (Some(fault), _) => (Some(fault), Reply::new(200, "")),
+            (None, None) => (None, Reply::unauthorized()),
+            (None, Some(user)) => (None, store.respond(&parts.method, target, &body, &user)),
+        }
+    };
+    match fault {
+        Some(Fault::Timeout) => {
+            info!(method = %parts.method, uri = %parts.uri, "request held (injected timeout)");
+            tokio::time::sleep(TIMEOUT_HOLD).await;
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "injected timeout"));
+        }
+        Some(_) => {
+            info!(method = %parts.method, uri = %parts.uri, "connection dropped (injected fault)");
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "injected disconnect",
+            ));
+        }
+        None => {}
+    }
+    info!(method = %parts.method, uri = %parts.uri, status = reply.status, "request");
+    let mut response = Response::builder()
+        .status(reply.status)
+        .header(CONTENT_TYPE, "application/xml; charset=utf-8");
+    for (name, value) in &reply.headers {
+        response = response.header(name, value);
+    }
+    response
+        .body(Full::new(Bytes::from(reply.body)))
+        .map_err(io::Error::other)
+}
+
+/// The API username from a Basic `Authorization` header.
+fn api_user(headers: &hyper::HeaderMap) -> Option<String> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let encoded = value.strip_prefix("Basic ")?;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (user, _key) = decoded.split_once(':')?;
+    Some(user.to_string())
+}
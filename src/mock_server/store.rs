@@ -0,0 +1,1243 @@
+//! The mock server's data, and the API operations on it.
+//!
+//! Records are kept as JSON objects keyed by the API's element names
+//! (`ticket-id`, `status-id`, ...), so a fixture can hold any field the real
+//! API returns and every one of them is served back.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use super::xml;
+use crate::api::raw::xml_to_json;
+
+/// One API object, keyed by element name.
+pub type Record = Map<String, Value>;
+
+/// Items per page of the paged endpoints (tickets, commits and activity).
+pub const PAGE_SIZE: usize = 20;
+
+/// Everything the mock server knows. A fixture file is this, as JSON or
+/// YAML; every key is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Store {
+    /// Account users. A request's user is the one whose `username` matches
+    /// the part of its API username after the `/`.
+    #[serde(default)]
+    pub users: Vec<Record>,
+    #[serde(default)]
+    pub project_groups: Vec<Record>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    /// Account activity, newest first; each event's `project-permalink`
+    /// places it in a project's feed.
+    #[serde(default)]
+    pub activity: Vec<Record>,
+    /// Failures queued by [`inject_fault`](Self::inject_fault).
+    #[serde(skip)]
+    faults: VecDeque<Fault>,
+}
+
+/// A failure to answer a request with instead of handling it, for testing
+/// retries and error handling.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Answer with this reply, e.g. [`Reply::rate_limited`].
+    Reply(Reply),
+    /// Drop the connection without answering.
+    Disconnect,
+    /// Never answer, so the request times out.
+    Timeout,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Project {
+    /// The project's own fields: `name`, `permalink`, `status`, ...
+    #[serde(flatten)]
+    pub fields: Record,
+    /// IDs of the users assigned to the project; all users when omitted.
+    #[serde(default)]
+    pub assignments: Option<Vec<i64>>,
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<Record>,
+    #[serde(default = "default_priorities")]
+    pub priorities: Vec<Record>,
+    #[serde(default = "default_categories")]
+    pub categories: Vec<Record>,
+    #[serde(default = "default_types")]
+    pub types: Vec<Record>,
+    #[serde(default)]
+    pub tickets: Vec<Ticket>,
+    #[serde(default)]
+    pub milestones: Vec<Record>,
+    #[serde(default)]
+    pub repositories: Vec<Repository>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Ticket {
+    #[serde(flatten)]
+    pub fields: Record,
+    #[serde(default)]
+    pub notes: Vec<Record>,
+    /// IDs of the users watching the ticket.
+    #[serde(default)]
+    pub watchers: Vec<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Repository {
+    #[serde(flatten)]
+    pub fields: Record,
+    /// Newest first. Every ref and path has the same history.
+    #[serde(default)]
+    pub commits: Vec<Record>,
+    #[serde(default)]
+    pub branches: Vec<Record>,
+    /// File contents by path, served for every ref.
+    #[serde(default)]
+    pub files: Map<String, Value>,
+    #[serde(default)]
+    pub hooks: Vec<Record>,
+    #[serde(default)]
+    pub merge_requests: Vec<Record>,
+    #[serde(default)]
+    pub deployments: Vec<Record>,
+}
+
+fn records(value: Value) -> Vec<Record> {
+    serde_json::from_value(value).expect("default records are objects")
+}
+
+fn default_statuses() -> Vec<Record> {
+    records(json!([
+        {"id": 1, "name": "New", "background-colour": "green", "order": 1, "treat-as-closed": false},
+        {"id": 2, "name": "In Progress", "background-colour": "blue", "order": 2, "treat-as-closed": false},
+        {"id": 3, "name": "Completed", "background-colour": "grey", "order": 3, "treat-as-closed": true},
+        {"id": 4, "name": "Invalid", "background-colour": "grey", "order": 4, "treat-as-closed": true},
+    ]))
+}
+
+fn default_priorities() -> Vec<Record> {
+    records(json!([
+        {"id": 1, "name": "Critical", "colour": "red", "default": false, "position": 1},
+        {"id": 2, "name": "High", "colour": "orange", "default": false, "position": 2},
+        {"id": 3, "name": "Normal", "colour": "blue", "default": true, "position": 3},
+        {"id": 4, "name": "Low", "colour": "grey", "default": false, "position": 4},
+    ]))
+}
+
+fn default_categories() -> Vec<Record> {
+    records(json!([{"id": 1, "name": "General"}]))
+}
+
+fn default_types() -> Vec<Record> {
+    records(json!([
+        {"id": 1, "name": "Bug", "icon": "bug"},
+        {"id": 2, "name": "Feature", "icon": "feature"},
+        {"id": 3, "name": "Task", "icon": "task"},
+    ]))
+}
+
+/// A request as the store sees it: path segments and query parameters
+/// already percent-decoded.
+#[derive(Debug)]
+struct Request<'a> {
+    method: &'a Method,
+    segments: Vec<String>,
+    query: Vec<(String, String)>,
+    body: &'a str,
+    /// The API username, `account/username`.
+    user: &'a str,
+}
+
+impl<'a> Request<'a> {
+    /// Split `target`, a path with an optional query string.
+    fn parse(method: &'a Method, target: &str, body: &'a str, user: &'a str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Self {
+            method,
+            segments: path
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(percent_decode)
+                .collect(),
+            query: query
+                .split('&')
+                .filter(|p| !p.is_empty())
+                .map(|p| {
+                    let (k, v) = p.split_once('=').unwrap_or((p, ""));
+                    (percent_decode(k), percent_decode(&v.replace('+', " ")))
+                })
+                .collect(),
+            body,
+            user,
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn page(&self) -> usize {
+        self.param("page")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    fn account(&self) -> &str {
+        self.user.split('/').next().unwrap_or(self.user)
+    }
+
+    fn username(&self) -> &str {
+        self.user.split('/').nth(1).unwrap_or(self.user)
+    }
+
+    /// The request body's fields, with IDs and flags typed.
+    fn fields(&self) -> std::result::Result<Record, Reply> {
+        if self.body.trim().is_empty() {
+            return Ok(Record::new());
+        }
+        match xml_to_json(self.body) {
+            Ok(Value::Object(fields)) => Ok(fields
+                .into_iter()
+                .map(|(k, v)| {
+                    let v = typed(&k, v);
+                    (k, v)
+                })
+                .collect()),
+            Ok(_) => Ok(Record::new()),
+            Err(e) => Err(Reply::status(400, format!("Invalid XML: {}", e))),
+        }
+    }
+}
+
+/// A response: status code, extra headers and body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self::status(status, body.into())
+    }
+
+    /// 429, with a `Retry-After` header when `retry_after` (in seconds) is
+    /// given.
+    pub fn rate_limited(retry_after: Option<u64>) -> Self {
+        let mut reply = Self::status(429, "Rate limit exceeded".to_string());
+        reply
+            .headers
+            .extend(retry_after.map(|secs| ("Retry-After".to_string(), secs.to_string())));
+        reply
+    }
+
+    fn ok(body: String) -> Self {
+        Self::status(200, body)
+    }
+
+    fn created(body: String) -> Self {
+        Self::status(201, body)
+    }
+
+    fn empty() -> Self {
+        Self::status(200, String::new())
+    }
+
+    pub fn not_found() -> Self {
+        Self::status(404, "Record not found".to_string())
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::status(401, "HTTP Basic: Access denied.".to_string())
+    }
+
+    /// 422 with the API's `<errors>` body.
+    fn invalid(errors: &[&str]) -> Self {
+        let errors: String = errors
+            .iter()
+            .map(|e| format!("<error>{}</error>", quick_xml::escape::escape(*e)))
+            .collect();
+        Self::status(422, format!("<errors>{}</errors>", errors))
+    }
+
+    fn status(status: u16, body: String) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+}
+
+type Handled = std::result::Result<Reply, Reply>;
+
+impl Store {
+    /// Read a fixture file: YAML for `.yaml`/`.yml`, otherwise JSON.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        let is_yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        );
+        let store = if is_yaml {
+            serde_yaml::from_str(&text).map_err(anyhow::Error::from)
+        } else {
+            serde_json::from_str(&text).map_err(anyhow::Error::from)
+        };
+        store.with_context(|| format!("Invalid fixture {}", path.display()))
+    }
+
+    /// The sample data served when no fixture is given.
+    pub fn sample() -> Self {
+        serde_json::from_str(include_str!("../../fixtures/mock-server.json"))
+            .expect("sample fixture is valid")
+    }
+
+    /// Fail the next request not already claimed by an earlier fault with
+    /// `fault` instead of answering it.
+    pub fn inject_fault(&mut self, fault: Fault) {
+        self.faults.push_back(fault);
+    }
+
+    /// Remove and return the fault for the request being handled, if any.
+    /// Servers check this before calling [`respond`](Self::respond).
+    pub fn take_fault(&mut self) -> Option<Fault> {
+        self.faults.pop_front()
+    }
+
+    /// Answer `method target` (a path with an optional query string) from
+    /// API user `user`, changing the store for creates, updates and deletes.
+    pub fn respond(&mut self, method: &Method, target: &str, body: &str, user: &str) -> Reply {
+        let req = Request::parse(method, target, body, user);
+        self.route(&req).unwrap_or_else(|reply| reply)
+    }
+
+    fn route(&mut self, req: &Request) -> Handled {
+        let segments: Vec<&str> = req.segments.iter().map(String::as_str).collect();
+        let method = req.method;
+        let get = method == Method::GET;
+        let post = method == Method::POST;
+        let put = method == Method::PUT;
+        let delete = method == Method::DELETE;
+        match segments.as_slice() {
+            ["projects"] if get => Ok(self.list_projects()),
+            ["project_groups"] if get => Ok(Reply::ok(xml::list(
+                "project-groups",
+                "project-group",
+                &self.project_groups,
+            ))),
+            ["create_project"] if post => self.create_project(req),
+            ["project", project] if put => self.update_project(req, project),
+            ["activity"] if get => Ok(self.activity(req, None)),
+            [project] if get => {
+                let project = self.project(project)?;
+                Ok(Reply::ok(xml::document("project", &project.view())))
+            }
+            [project] if delete => {
+                let index = self.project_index(project)?;
+                self.projects.remove(index);
+                Ok(Reply::empty())
+            }
+            [project, "activity"] if get => {
+                self.project(project)?;
+                Ok(self.activity(req, Some(project)))
+            }
+            [project, "assignments"] if get => self.assignments(project),
+            [project, "assignments"] if post => self.assign(req, project),
+            [project, "tickets"] if get => self.tickets(req, project),
+            [project, "tickets"] if post => self.create_ticket(req, project),
+            [project, "tickets", "statuses"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "ticketing-statuses",
+                    "ticketing-status",
+                    &p.statuses,
+                )))
+            }
+            [project, "tickets", "priorities"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "ticketing-priorities",
+                    "ticketing-priority",
+                    &p.priorities,
+                )))
+            }
+            [project, "tickets", "categories"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "ticketing-categories",
+                    "ticketing-category",
+                    &p.categories,
+                )))
+            }
+            [project, "tickets", "types"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "ticketing-types",
+                    "ticketing-type",
+                    &p.types,
+                )))
+            }
+            [project, "tickets", id] if get => {
+                let ticket = self.project(project)?.ticket(id)?;
+                Ok(Reply::ok(xml::document("ticket", &ticket.fields)))
+            }
+            [project, "tickets", id, "notes"] if get => {
+                let ticket = self.project(project)?.ticket(id)?;
+                Ok(Reply::ok(xml::list(
+                    "ticket-notes",
+                    "ticket-note",
+                    &ticket.notes,
+                )))
+            }
+            [project, "tickets", id, "notes"] if post => self.create_note(req, project, id),
+            [project, "tickets", id, "watchers"] if get => {
+                let ticket = self.project(project)?.ticket(id)?;
+                let watchers = ticket.watchers.iter().map(|&id| json!(id)).collect();
+                Ok(Reply::ok(xml::values("watchers", watchers)))
+            }
+            [project, "tickets", id, "watchers"] if post => {
+                let fields = req.fields()?;
+                let ticket = self.project_mut(project)?.ticket_mut(id)?;
+                ticket.watchers = items(fields.get("watcher"))
+                    .into_iter()
+                    .filter_map(as_i64)
+                    .collect();
+                Ok(Reply::empty())
+            }
+            [project, "milestones"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "ticketing-milestones",
+                    "ticketing-milestone",
+                    &p.milestones,
+                )))
+            }
+            [project, "milestones"] if post => self.create_milestone(req, project),
+            [project, "milestones", id] if put => self.update_milestone(req, project, id),
+            [project, "repositories"] if get => {
+                let p = self.project(project)?;
+                Ok(Reply::ok(xml::list(
+                    "repositories",
+                    "repository",
+                    p.repositories.iter().map(|r| &r.fields),
+                )))
+            }
+            [project, "repositories"] if post => self.create_repository(req, project),
+            [project, repo] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                Ok(Reply::ok(xml::document("repository", &repo.fields)))
+            }
+            [project, repo] if delete => {
+                let p = self.project_mut(project)?;
+                let index = p.repository_index(repo)?;
+                p.repositories.remove(index);
+                Ok(Reply::empty())
+            }
+            [project, repo, "commits", _git_ref, ..] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                Ok(Reply::ok(xml::list(
+                    "commits",
+                    "commit",
+                    page(&repo.commits, req.page()),
+                )))
+            }
+            [project, repo, "blob", _git_ref, path @ ..] if get && !path.is_empty() => {
+                let repo = self.project(project)?.repository(repo)?;
+                match repo.files.get(&path.join("/")) {
+                    Some(Value::String(content)) => Ok(Reply::ok(content.clone())),
+                    Some(other) => Ok(Reply::ok(other.to_string())),
+                    None => Err(Reply::not_found()),
+                }
+            }
+            [project, repo, "branches"] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                Ok(Reply::ok(xml::list("branches", "branch", &repo.branches)))
+            }
+            [project, repo, "hooks"] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                Ok(Reply::ok(xml::list(
+                    "repository-hooks",
+                    "repository-hook",
+                    &repo.hooks,
+                )))
+            }
+            [project, repo, "hooks"] if post => {
+                let fields = req.fields()?;
+                if text(&fields, "url").is_empty() {
+                    return Err(Reply::invalid(&["Url can't be blank"]));
+                }
+                let repo = self.project_mut(project)?.repository_mut(repo)?;
+                let mut hook = record(json!({ "id": next_id(&repo.hooks, "id") }));
+                hook.extend(fields);
+                repo.hooks.push(hook.clone());
+                Ok(Reply::created(xml::document("repository-hook", &hook)))
+            }
+            [project, repo, "deployments"] if post => {
+                let fields = req.fields()?;
+                let repo = self.project_mut(project)?.repository_mut(repo)?;
+                repo.deployments.push(fields.clone());
+                Ok(Reply::created(xml::document("deployment", &fields)))
+            }
+            [project, repo, "merge_requests"] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                Ok(Reply::ok(xml::list(
+                    "merge-requests",
+                    "merge-request",
+                    &repo.merge_requests,
+                )))
+            }
+            [project, repo, "merge_requests"] if post => {
+                self.create_merge_request(req, project, repo)
+            }
+            [project, repo, "merge_requests", id] if get => {
+                let repo = self.project(project)?.repository(repo)?;
+                let mr = find(&repo.merge_requests, "id", id).ok_or_else(Reply::not_found)?;
+                Ok(Reply::ok(xml::document("merge-request", mr)))
+            }
+            [project, repo, "merge_requests", id, action] if post => {
+                self.update_merge_request(req, project, repo, id, action)
+            }
+            _ => Err(Reply::not_found()),
+        }
+    }
+
+    fn project_index(&self, permalink: &str) -> std::result::Result<usize, Reply> {
+        self.projects
+            .iter()
+            .position(|p| {
+                text(&p.fields, "permalink") == permalink
+                    || text(&p.fields, "project-id") == permalink
+            })
+            .ok_or_else(Reply::not_found)
+    }
+
+    fn project(&self, permalink: &str) -> std::result::Result<&Project, Reply> {
+        Ok(&self.projects[self.project_index(permalink)?])
+    }
+
+    fn project_mut(&mut self, permalink: &str) -> std::result::Result<&mut Project, Reply> {
+        let index = self.project_index(permalink)?;
+        Ok(&mut self.projects[index])
+    }
+
+    fn user(&self, id: i64) -> Option<&Record> {
+        self.users.iter().find(|u| int(u, "id") == Some(id))
+    }
+
+    fn user_id(&self, username: &str) -> Option<i64> {
+        self.users
+            .iter()
+            .find(|u| text(u, "username") == username)
+            .and_then(|u| int(u, "id"))
+    }
+
+    fn list_projects(&self) -> Reply {
+        let projects: Vec<Record> = self.projects.iter().map(Project::view).collect();
+        Reply::ok(xml::list("projects", "project", &projects))
+    }
+
+    fn create_project(&mut self, req: &Request) -> Handled {
+        let fields = req.fields()?;
+        let name = text(&fields, "name");
+        if name.trim().is_empty() {
+            return Err(Reply::invalid(&["Name can't be blank"]));
+        }
+        let ids: Vec<Record> = self.projects.iter().map(|p| p.fields.clone()).collect();
+        let taken: Vec<String> = ids.iter().map(|p| text(p, "permalink")).collect();
+        let mut project = Project {
+            fields: record(json!({
+                "name": name,
+                "account-name": req.account(),
+                "permalink": unique_permalink(&name, &taken),
+                "project-id": next_id(&ids, "project-id"),
+                "group-id": null,
+                "overview": "",
+                "start-page": "overview",
+                "status": "active",
+            })),
+            statuses: default_statuses(),
+            priorities: default_priorities(),
+            categories: default_categories(),
+            types: default_types(),
+            ..Default::default()
+        };
+        merge(&mut project.fields, fields);
+        let view = project.view();
+        self.projects.push(project);
+        Ok(Reply::created(xml::document("project", &view)))
+    }
+
+    fn update_project(&mut self, req: &Request, permalink: &str) -> Handled {
+        let fields = req.fields()?;
+        let project = self.project_mut(permalink)?;
+        merge(&mut project.fields, fields);
+        Ok(Reply::ok(xml::document("project", &project.view())))
+    }
+
+    fn assignments(&self, permalink: &str) -> Handled {
+        let project = self.project(permalink)?;
+        let users: Vec<&Record> = match &project.assignments {
+            Some(ids) => ids.iter().filter_map(|&id| self.user(id)).collect(),
+            None => self.users.iter().collect(),
+        };
+        Ok(Reply::ok(xml::list("users", "user", users)))
+    }
+
+    fn assign(&mut self, req: &Request, permalink: &str) -> Handled {
+        let fields = req.fields()?;
+        let ids = items(fields.get("user"))
+            .into_iter()
+            .filter_map(|user| user.get("id").and_then(as_i64))
+            .collect();
+        self.project_mut(permalink)?.assignments = Some(ids);
+        Ok(Reply::empty())
+    }
+
+    fn tickets(&self, req: &Request, permalink: &str) -> Handled {
+        let project = self.project(permalink)?;
+        let query = parse_query(req.param("query").unwrap_or_default());
+        let matching: Vec<&Record> = project
+            .tickets
+            .iter()
+            .map(|t| &t.fields)
+            .filter(|t| query.iter().all(|term| self.matches(project, t, term, req)))
+            .collect();
+        let start = (req.page() - 1) * PAGE_SIZE;
+        let page = matching.into_iter().skip(start).take(PAGE_SIZE);
+        Ok(Reply::ok(xml::list("tickets", "ticket", page)))
+    }
+
+    /// Whether `ticket` satisfies one term of a search query. Unknown
+    /// keys (such as `sort`) match everything.
+    fn matches(&self, project: &Project, ticket: &Record, term: &Term, req: &Request) -> bool {
+        let Some(key) = &term.key else {
+            return text(ticket, "summary")
+                .to_lowercase()
+                .contains(&term.values[0].to_lowercase());
+        };
+        term.values.iter().any(|value| {
+            let value = value.as_str();
+            match key.as_str() {
+                "id" => text(ticket, "ticket-id") == value,
+                "status" => {
+                    let status =
+                        int(ticket, "status-id").and_then(|id| find_by_id(&project.statuses, id));
+                    let closed =
+                        status.is_some_and(|s| s.get("treat-as-closed") == Some(&json!(true)));
+                    match value {
+                        "open" => !closed,
+                        "closed" => closed,
+                        _ => status.is_some_and(|s| named(s, value)),
+                    }
+                }
+                "priority" => int(ticket, "priority-id")
+                    .and_then(|id| find_by_id(&project.priorities, id))
+                    .is_some_and(|p| named(p, value)),
+                "category" => int(ticket, "category-id")
+                    .and_then(|id| find_by_id(&project.categories, id))
+                    .is_some_and(|c| named(c, value)),
+                "milestone" => int(ticket, "milestone-id")
+                    .and_then(|id| find_by_id(&project.milestones, id))
+                    .is_some_and(|m| named(m, value)),
+                "type" => text(ticket, "ticket-type").eq_ignore_ascii_case(value),
+                "assignee" | "reporter" => {
+                    let value = if value == "me" { req.username() } else { value };
+                    let id = int(ticket, &format!("{}-id", key));
+                    text(ticket, key).eq_ignore_ascii_case(value)
+                        || id.is_some_and(|id| {
+                            id.to_string() == value
+                                || self.user(id).is_some_and(|u| {
+                                    text(u, "username").eq_ignore_ascii_case(value)
+                                })
+                        })
+                }
+                "tag" | "tags" => text(ticket, "tags")
+                    .split([',', ' '])
+                    .any(|tag| tag.eq_ignore_ascii_case(value)),
+                _ => true,
+            }
+        })
+    }
+
+    fn create_ticket(&mut self, req: &Request, permalink: &str) -> Handled {
+        let fields = req.fields()?;
+        let summary = text(&fields, "summary");
+        if summary.trim().is_empty() {
+            return Err(Reply::invalid(&["Summary can't be blank"]));
+        }
+        let reporter_id = self.user_id(req.username());
+        let assignee = int(&fields, "assignee-id")
+            .and_then(|id| self.user(id))
+            .map(|u| text(u, "username"));
+        let project = self.project_mut(permalink)?;
+        let existing: Vec<Record> = project.tickets.iter().map(|t| t.fields.clone()).collect();
+        let ticket_id = next_id(&existing, "ticket-id");
+        let now = timestamp();
+        let default_priority = project
+            .priorities
+            .iter()
+            .find(|p| p.get("default") == Some(&json!(true)))
+            .or(project.priorities.first())
+            .and_then(|p| int(p, "id"));
+        let mut ticket = record(json!({
+            "ticket-id": ticket_id,
+            "summary": summary,
+            "ticket-type": project.types.first().map(|t| text(t, "name")).unwrap_or_else(|| "Bug".to_string()),
+            "description": null,
+            "reporter-id": reporter_id,
+            "reporter": req.username(),
+            "assignee-id": null,
+            "assignee": assignee,
+            "category-id": null,
+            "priority-id": default_priority,
+            "status-id": project.statuses.first().and_then(|s| int(s, "id")),
+            "milestone-id": null,
+            "tags": null,
+            "created-at": now,
+            "updated-at": now,
+        }));
+        merge(&mut ticket, fields);
+        project.tickets.push(Ticket {
+            fields: ticket.clone(),
+            ..Default::default()
+        });
+        let event = project.event(
+            "ticketing_ticket",
+            format!("Ticket #{} created: {}", ticket_id, summary),
+            ticket_id,
+            &summary,
+        );
+        self.activity.insert(0, event);
+        Ok(Reply::created(xml::document("ticket", &ticket)))
+    }
+
+    /// Add a note to a ticket, applying any `<changes>` to the ticket.
+    fn create_note(&mut self, req: &Request, permalink: &str, id: &str) -> Handled {
+        let mut fields = req.fields()?;
+        let changes = match fields.remove("changes") {
+            Some(Value::Object(changes)) => changes
+                .into_iter()
+                .map(|(k, v)| {
+                    let v = typed(&k, v);
+                    (k, v)
+                })
+                .collect(),
+            _ => Record::new(),
+        };
+        let assignee = int(&changes, "assignee-id")
+            .and_then(|id| self.user(id))
+            .map(|u| text(u, "username"));
+        let user_id = self.user_id(req.username());
+        let project = self.project_mut(permalink)?;
+        let ticket = project.ticket_mut(id)?;
+        let now = timestamp();
+        let mut updates = Record::new();
+        for (key, value) in &changes {
+            let field = if key == "subject" { "summary" } else { key };
+            let old = ticket.fields.insert(field.to_string(), value.clone());
+            updates.insert(
+                key.replace('-', "_"),
+                json!([old.unwrap_or(Value::Null), value]),
+            );
+        }
+        if let Some(assignee) = assignee {
+            ticket
+                .fields
+                .insert("assignee".to_string(), json!(assignee));
+        }
+        ticket.fields.insert("updated-at".to_string(), json!(now));
+        let mut note = record(json!({
+            "id": next_id(&ticket.notes, "id"),
+            "content": "",
+            "time-added": now,
+            "user-id": user_id,
+            "private": false,
+        }));
+        merge(&mut note, fields);
+        if !changes.is_empty() {
+            note.insert("changes".to_string(), Value::Object(changes));
+            note.insert(
+                "updates".to_string(),
+                json!(Value::Object(updates).to_string()),
+            );
+        }
+        ticket.notes.push(note.clone());
+        let ticket_id = int(&ticket.fields, "ticket-id").unwrap_or_default();
+        let summary = text(&ticket.fields, "summary");
+        let mut event = project.event(
+            "ticketing_note",
+            format!("Ticket #{} updated: {}", ticket_id, summary),
+            ticket_id,
+            &summary,
+        );
+        event.insert("content".to_string(), json!(text(&note, "content")));
+        self.activity.insert(0, event);
+        Ok(Reply::created(xml::document("ticket-note", &note)))
+    }
+
+    fn create_milestone(&mut self, req: &Request, permalink: &str) -> Handled {
+        let fields = req.fields()?;
+        if text(&fields, "name").trim().is_empty() {
+            return Err(Reply::invalid(&["Name can't be blank"]));
+        }
+        let project = self.project_mut(permalink)?;
+        let mut milestone = record(json!({
+            "id": next_id(&project.milestones, "id"),
+            "name": null,
+            "description": null,
+            "start-at": null,
+            "deadline": null,
+            "parent-id": null,
+            "estimated-time": null,
+            "responsible-user-id": null,
+            "status": "active",
+        }));
+        merge(&mut milestone, fields);
+        project.milestones.push(milestone.clone());
+        Ok(Reply::created(xml::document(
+            "ticketing-milestone",
+            &milestone,
+        )))
+    }
+
+    fn update_milestone(&mut self, req: &Request, permalink: &str, id: &str) -> Handled {
+        let fields = req.fields()?;
+        let project = self.project_mut(permalink)?;
+        let milestone = project
+            .milestones
+            .iter_mut()
+            .find(|m| text(m, "id") == id)
+            .ok_or_else(Reply::not_found)?;
+        merge(milestone, fields);
+        Ok(Reply::ok(xml::document("ticketing-milestone", milestone)))
+    }
+
+    fn create_repository(&mut self, req: &Request, permalink: &str) -> Handled {
+        let fields = req.fields()?;
+        let name = text(&fields, "name");
+        if name.trim().is_empty() {
+            return Err(Reply::invalid(&["Name can't be blank"]));
+        }
+        let account = req.account().to_string();
+        let project = self.project_mut(permalink)?;
+        let taken: Vec<String> = project
+            .repositories
+            .iter()
+            .map(|r| text(&r.fields, "permalink"))
+            .collect();
+        let repo_permalink = unique_permalink(&name, &taken);
+        let mut repo = Repository {
+            fields: record(json!({
+                "name": name,
+                "permalink": repo_permalink,
+                "disk-usage": 0,
+                "last-commit-ref": null,
+                "clone-url": format!(
+                    "git@codebasehq.com:{}/{}/{}.git",
+                    account,
+                    text(&project.fields, "permalink"),
+                    repo_permalink
+                ),
+                "source": null,
+            })),
+            ..Default::default()
+        };
+        merge(&mut repo.fields, fields);
+        let view = repo.fields.clone();
+        project.repositories.push(repo);
+        Ok(Reply::created(xml::document("repository", &view)))
+    }
+
+    fn create_merge_request(&mut self, req: &Request, permalink: &str, repo: &str) -> Handled {
+        let fields = req.fields()?;
+        let missing: Vec<&str> = [
+            ("subject", "Subject can't be blank"),
+            ("source-ref", "Source ref can't be blank"),
+            ("target-ref", "Target ref can't be blank"),
+        ]
+        .into_iter()
+        .filter(|(key, _)| text(&fields, key).trim().is_empty())
+        .map(|(_, message)| message)
+        .collect();
+        if !missing.is_empty() {
+            return Err(Reply::invalid(&missing));
+        }
+        let user_id = self.user_id(req.username());
+        let repo = self.project_mut(permalink)?.repository_mut(repo)?;
+        let now = timestamp();
+        let mut mr = record(json!({
+            "id": next_id(&repo.merge_requests, "id"),
+            "source-ref": null,
+            "target-ref": null,
+            "subject": null,
+            "status": "new",
+            "user-id": user_id,
+            "created-at": now,
+            "updated-at": now,
+            "can-merge": true,
+        }));
+        merge(&mut mr, fields);
+        repo.merge_requests.push(mr.clone());
+        Ok(Reply::created(xml::document("merge-request", &mr)))
+    }
+
+    /// `comment`, `close`, `reopen`, `merge` or `reassign` a merge request.
+    fn update_merge_request(
+        &mut self,
+        req: &Request,
+        permalink: &str,
+        repo: &str,
+        id: &str,
+        action: &str,
+    ) -> Handled {
+        let fields = req.fields()?;
+        let user_id = self.user_id(req.username());
+        let repo = self.project_mut(permalink)?.repository_mut(repo)?;
+        let mr = repo
+            .merge_requests
+            .iter_mut()
+            .find(|m| text(m, "id") == id)
+            .ok_or_else(Reply::not_found)?;
+        let status = match action {
+            "comment" => None,
+            "close" => Some("closed"),
+            "reopen" => Some("new"),
+            "merge" => Some("merged"),
+            "reassign" => {
+                let Some(assignee) = int(&fields, "user-id") else {
+                    return Err(Reply::invalid(&["User can't be blank"]));
+                };
+                mr.insert("user-id".to_string(), json!(assignee));
+                None
+            }
+            _ => return Err(Reply::not_found()),
+        };
+        if let Some(status) = status {
+            mr.insert("status".to_string(), json!(status));
+        }
+        let now = timestamp();
+        mr.insert("updated-at".to_string(), json!(now));
+        let comment = record(json!({
+            "content": fields.get("content").cloned().unwrap_or(Value::Null),
+            "user-id": user_id,
+            "action": action,
+            "created-at": now,
+        }));
+        match mr.get_mut("comments") {
+            Some(Value::Array(comments)) => comments.push(Value::Object(comment)),
+            _ => {
+                mr.insert("comments".to_string(), json!([comment]));
+            }
+        }
+        Ok(Reply::ok(xml::document("merge-request", mr)))
+    }
+
+    /// A page of the account feed, or of one project's.
+    fn activity(&self, req: &Request, project: Option<&str>) -> Reply {
+        let since = req.param("since");
+        let events = self.activity.iter().filter(|event| {
+            project.is_none_or(|p| text(event, "project-permalink") == p)
+                && since.is_none_or(|since| text(event, "timestamp").as_str() >= since)
+        });
+        let start = (req.page() - 1) * PAGE_SIZE;
+        Reply::ok(xml::list(
+            "events",
+            "event",
+            events.skip(start).take(PAGE_SIZE),
+        ))
+    }
+}
+
+impl Project {
+    /// The project as served, with ticket counts taken from its tickets.
+    fn view(&self) -> Record {
+        let closed_ids: Vec<i64> = self
+            .statuses
+            .iter()
+            .filter(|s| s.get("treat-as-closed") == Some(&json!(true)))
+            .filter_map(|s| int(s, "id"))
+            .collect();
+        let closed = self
+            .tickets
+            .iter()
+            .filter(|t| int(&t.fields, "status-id").is_some_and(|id| closed_ids.contains(&id)))
+            .count();
+        let mut view = self.fields.clone();
+        view.insert("total-tickets".to_string(), json!(self.tickets.len()));
+        view.insert(
+            "open-tickets".to_string(),
+            json!(self.tickets.len() - closed),
+        );
+        view.insert("closed-tickets".to_string(), json!(closed));
+        view
+    }
+
+    fn ticket(&self, id: &str) -> std::result::Result<&Ticket, Reply> {
+        self.tickets
+            .iter()
+            .find(|t| text(&t.fields, "ticket-id") == id)
+            .ok_or_else(Reply::not_found)
+    }
+
+    fn ticket_mut(&mut self, id: &str) -> std::result::Result<&mut Ticket, Reply> {
+        self.tickets
+            .iter_mut()
+            .find(|t| text(&t.fields, "ticket-id") == id)
+            .ok_or_else(Reply::not_found)
+    }
+
+    fn repository_index(&self, permalink: &str) -> std::result::Result<usize, Reply> {
+        self.repositories
+            .iter()
+            .position(|r| text(&r.fields, "permalink") == permalink)
+            .ok_or_else(Reply::not_found)
+    }
+
+    fn repository(&self, permalink: &str) -> std::result::Result<&Repository, Reply> {
+        Ok(&self.repositories[self.repository_index(permalink)?])
+    }
+
+    fn repository_mut(&mut self, permalink: &str) -> std::result::Result<&mut Repository, Reply> {
+        let index = self.repository_index(permalink)?;
+        Ok(&mut self.repositories[index])
+    }
+
+    /// An activity event about ticket `number` of this project.
+    fn event(&self, kind: &str, title: String, number: i64, subject: &str) -> Record {
+        record(json!({
+            "title": title,
+            "type": kind,
+            "timestamp": timestamp(),
+            "project-permalink": text(&self.fields, "permalink"),
+            "project-name": text(&self.fields, "name"),
+            "subject": subject,
+            "number": number,
+        }))
+    }
+}
+
+/// One term of a ticket search: `key:value[,value...]`, or free text.
+#[derive(Debug, PartialEq)]
+struct Term {
+    key: Option<String>,
+    values: Vec<String>,
+}
+
+/// Split a search query into terms; values may be double-quoted.
+fn parse_query(query: &str) -> Vec<Term> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => words.push(std::mem::take(&mut word)),
+            c => word.push(c),
+        }
+    }
+    words.push(word);
+    words
+        .into_iter()
+        .filter(|w| !w.is_empty())
+        .map(|w| match w.split_once(':') {
+            Some((key, values)) => Term {
+                key: Some(key.to_lowercase()),
+                values: values.split(',').map(str::to_string).collect(),
+            },
+            None => Term {
+                key: None,
+                values: vec![w],
+            },
+        })
+        .collect()
+}
+
+fn record(value: Value) -> Record {
+    match value {
+        Value::Object(record) => record,
+        _ => Record::new(),
+    }
+}
+
+/// Overwrite `record`'s fields with `fields`.
+fn merge(record: &mut Record, fields: Record) {
+    for (key, value) in fields {
+        record.insert(key, value);
+    }
+}
+
+/// A field as text: strings as-is, null and missing fields as nothing.
+fn text(record: &Record, key: &str) -> String {
+    match record.get(key) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn int(record: &Record, key: &str) -> Option<i64> {
+    record.get(key).and_then(as_i64)
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Request bodies carry no type attributes; give IDs and flags their types
+/// so they're served back as such.
+fn typed(key: &str, value: Value) -> Value {
+    let Value::String(s) = &value else {
+        return value;
+    };
+    if key == "id" || key.ends_with("-id") {
+        return match s.trim() {
+            "" => Value::Null,
+            s => s.parse::<i64>().map(Value::from).unwrap_or(value),
+        };
+    }
+    match (key, s.as_str()) {
+        ("private" | "sync" | "can-merge", "1" | "true") => Value::Bool(true),
+        ("private" | "sync" | "can-merge", "0" | "false") => Value::Bool(false),
+        _ => value,
+    }
+}
+
+/// The elements of a repeated body element, which reads as an array when
+/// there are several and a single value when there's one.
+fn items(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(item) => vec![item],
+        None => Vec::new(),
+    }
+}
+
+fn find<'a>(records: &'a [Record], key: &str, id: &str) -> Option<&'a Record> {
+    records.iter().find(|r| text(r, key) == id)
+}
+
+fn find_by_id(records: &[Record], id: i64) -> Option<&Record> {
+    records.iter().find(|r| int(r, "id") == Some(id))
+}
+
+/// Whether `record` is called `name` (ignoring case) or has it as its ID.
+fn named(record: &Record, name: &str) -> bool {
+    text(record, "name").eq_ignore_ascii_case(name) || text(record, "id") == name
+}
+
+fn next_id(records: &[Record], key: &str) -> i64 {
+    records
+        .iter()
+        .filter_map(|r| int(r, key))
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+fn page(records: &[Record], page: usize) -> &[Record] {
+    let start = ((page - 1) * PAGE_SIZE).min(records.len());
+    let end = (start + PAGE_SIZE).min(records.len());
+    &records[start..end]
+}
+
+/// `name` as a permalink (`My Project` → `my-project`), with a numeric
+/// suffix if it is already `taken`.
+fn unique_permalink(name: &str, taken: &[String]) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug
+    };
+    let mut candidate = slug.clone();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// The current time as the API writes it, e.g. `2026-10-17T09:30:00Z`.
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// The date `days` after 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
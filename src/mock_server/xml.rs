@@ -0,0 +1,73 @@
+//! Rendering stored records as the API's Rails-style XML.
+
+use quick_xml::escape::escape;
+use serde_json::Value;
+
+use super::store::Record;
+
+const DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+/// `record` as a `<name>` document.
+pub fn document(name: &str, record: &Record) -> String {
+    let mut out = DECLARATION.to_string();
+    write_record(&mut out, name, record);
+    out
+}
+
+/// `values` as a `<root type="array">` document of typed `<item>` elements,
+/// e.g. `<watchers type="array"><watcher type="integer">1</watcher>...`.
+/// Items are named by dropping a trailing `s` from `root`.
+pub fn values(root: &str, values: Vec<Value>) -> String {
+    let mut out = DECLARATION.to_string();
+    write_value(&mut out, root, &Value::Array(values));
+    out
+}
+
+/// `records` as a `<root type="array">` document of `<item>` elements.
+pub fn list<'a>(root: &str, item: &str, records: impl IntoIterator<Item = &'a Record>) -> String {
+    let mut out = DECLARATION.to_string();
+    out.push_str(&format!("<{} type=\"array\">", root));
+    for record in records {
+        write_record(&mut out, item, record);
+    }
+    out.push_str(&format!("</{}>", root));
+    out
+}
+
+fn write_record(out: &mut String, name: &str, record: &Record) {
+    out.push_str(&format!("<{}>", name));
+    for (key, value) in record {
+        write_value(out, key, value);
+    }
+    out.push_str(&format!("</{}>", name));
+}
+
+/// One element, with a `type` attribute for anything but text so that
+/// `api::raw::xml_to_json` reads back the same value. An array becomes a
+/// `type="array"` element whose items are named by dropping a trailing `s`.
+fn write_value(out: &mut String, name: &str, value: &Value) {
+    let (kind, text) = match value {
+        Value::Null => {
+            out.push_str(&format!("<{} nil=\"true\"/>", name));
+            return;
+        }
+        Value::Object(record) => return write_record(out, name, record),
+        Value::Array(items) => {
+            let item = name.strip_suffix('s').unwrap_or(name);
+            out.push_str(&format!("<{} type=\"array\">", name));
+            for value in items {
+                write_value(out, item, value);
+            }
+            out.push_str(&format!("</{}>", name));
+            return;
+        }
+        Value::Bool(b) => (Some("boolean"), b.to_string()),
+        Value::Number(n) if n.is_f64() => (Some("float"), n.to_string()),
+        Value::Number(n) => (Some("integer"), n.to_string()),
+        Value::String(s) => (None, escape(s.as_str()).into_owned()),
+    };
+    match kind {
+        Some(kind) => out.push_str(&format!("<{} type=\"{}\">{}</{}>", name, kind, text, name)),
+        None => out.push_str(&format!("<{}>{}</{}>", name, text, name)),
+    }
+}
@@ -0,0 +1,140 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+/// How command results are written, chosen with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored text and aligned tables for people.
+    #[default]
+    Table,
+    /// Pretty-printed JSON.
+    Json,
+    /// One compact JSON value per line.
+    Ndjson,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+    /// YAML.
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Whether lists in this format are made of table columns (and so honor
+    /// `--columns`) rather than the serialized data.
+    pub fn uses_columns(self) -> bool {
+        matches!(self, Self::Table | Self::Csv | Self::Tsv)
+    }
+
+    /// The field separator for delimited formats.
+    pub(super) fn delimiter(self) -> Option<u8> {
+        match self {
+            Self::Csv => Some(b','),
+            Self::Tsv => Some(b'\t'),
+            _ => None,
+        }
+    }
+}
+
+/// Write `data` in a data format (anything but `table`). For NDJSON a
+/// sequence is written one element per line; for CSV and TSV each object is a
+/// row with a column per top-level field.
+pub fn write_data<T, W>(format: OutputFormat, data: &T, w: &mut W) -> Result<()>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *w, data).context("Failed to serialize to JSON")?;
+            writeln!(w)?;
+        }
+        OutputFormat::Ndjson => match serde_json::to_value(data)? {
+            Value::Array(items) => {
+                for item in items {
+                    writeln!(w, "{}", item)?;
+                }
+            }
+            value => writeln!(w, "{}", value)?,
+        },
+        OutputFormat::Yaml => {
+            serde_yaml::to_writer(&mut *w, data).context("Failed to serialize to YAML")?;
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let (headers, rows) = records(serde_json::to_value(data)?);
+            write_delimited(format, &headers, rows, w)?;
+        }
+        OutputFormat::Table => anyhow::bail!("Table output has no serialized form"),
+    }
+    Ok(())
+}
+
+/// Write a header row and data rows as CSV or TSV.
+pub(super) fn write_delimited<W, H, R>(
+    format: OutputFormat,
+    headers: &[H],
+    rows: impl IntoIterator<Item = R>,
+    w: &mut W,
+) -> Result<()>
+where
+    W: Write,
+    H: AsRef<[u8]>,
+    R: IntoIterator,
+    R::Item: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(format.delimiter().unwrap_or(b','))
+        .from_writer(w);
+    writer.write_record(headers)?;
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Flatten serialized data into delimited records: an object is one row and
+/// an array of objects one row each, with a column per top-level key. Nested
+/// values are written as compact JSON.
+fn records(value: Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let items = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    let mut headers: Vec<String> = Vec::new();
+    for item in &items {
+        if let Value::Object(map) = item {
+            for key in map.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+    }
+    if headers.is_empty() {
+        headers.push("value".to_string());
+    }
+
+    let rows = items
+        .iter()
+        .map(|item| match item {
+            Value::Object(map) => headers
+                .iter()
+                .map(|h| map.get(h).map(field).unwrap_or_default())
+                .collect(),
+            other => vec![field(other)],
+        })
+        .collect();
+    (headers, rows)
+}
+
+fn field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::raw::RawCapture;
+
+mod format;
+mod query;
+mod rows;
+mod table;
+mod template;
+
+pub use format::{OutputFormat, write_data};
+pub use query::JqFilter;
+pub use rows::TicketRow;
+pub use table::{
+    Cell, Column, TableRow, UnknownColumn, output_width, render, select_columns, truncate,
+};
+pub use template::{Template, relative_time};
+
+/// How command results are printed, from the global output flags.
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    /// `--format` (or `--json`).
+    pub format: OutputFormat,
+    /// `--columns`: table columns to show instead of the defaults.
+    pub columns: Option<Vec<String>>,
+    /// `--template`: render each result with this instead of `format`.
+    pub template: Option<Template>,
+    /// `--jq`: print the results of this filter over the JSON output.
+    pub jq: Option<JqFilter>,
+    /// `--raw-fields`: print the recorded API responses, converted to JSON
+    /// with every field, in place of the data passed to the print functions.
+    pub raw_fields: Option<RawCapture>,
+}
+
+impl OutputOptions {
+    /// Whether list output is built from table columns, so commands can skip
+    /// fetching data only those columns need.
+    pub fn uses_columns(&self) -> bool {
+        self.raw_fields.is_none()
+            && self.template.is_none()
+            && self.jq.is_none()
+            && self.format.uses_columns()
+    }
+
+    /// Whether output is serialized data (a `--format` other than table,
+    /// `--template`, `--jq` or `--raw-fields`) rather than human-readable text.
+    pub fn prints_data(&self) -> bool {
+        self.format != OutputFormat::Table
+            || self.template.is_some()
+            || self.jq.is_some()
+            || self.raw_fields.is_some()
+    }
+}
+
+/// Print `data` through the jq filter or template or in the selected format,
+/// or run the human-readable closure for table output.
+pub fn print_output<T, F>(out: &OutputOptions, data: &T, human: F) -> Result<()>
+where
+    T: Serialize,
+    F: FnOnce(),
+{
+    if let Some(raw) = &out.raw_fields {
+        return write_raw(out, &raw.take_json()?, &mut io::stdout().lock());
+    }
+    if let Some(jq) = &out.jq {
+        return jq.write(
+            data,
+            out.format == OutputFormat::Ndjson,
+            &mut io::stdout().lock(),
+        );
+    }
+    if let Some(template) = &out.template {
+        return write_templated(template, data, &mut io::stdout().lock());
+    }
+    if out.format == OutputFormat::Table {
+        human();
+        return Ok(());
+    }
+    format::write_data(out.format, data, &mut io::stdout().lock())
+}
+
+/// Print a list in the selected format; tables and CSV/TSV show its default
+/// (or `--columns`) columns.
+pub fn print_list<T>(out: &OutputOptions, data: &[T]) -> Result<()>
+where
+    T: Serialize + TableRow,
+{
+    print_list_with(out, data, |item| item)
+}
+
+/// Like [`print_list`], but builds each table row with `row`, for rows that
+/// need more than the item itself (e.g. names looked up from IDs).
+pub fn print_list_with<'a, T, R, F>(out: &OutputOptions, data: &'a [T], row: F) -> Result<()>
+where
+    T: Serialize,
+    R: TableRow,
+    F: Fn(&'a T) -> R,
+{
+    write_list_with(out, data, row, &mut io::stdout().lock())
+}
+
+/// [`print_list_with`] writing to `w` instead of stdout.
+pub fn write_list_with<'a, T, R, F, W>(
+    out: &OutputOptions,
+    data: &'a [T],
+    row: F,
+    w: &mut W,
+) -> Result<()>
+where
+    T: Serialize,
+    R: TableRow,
+    F: Fn(&'a T) -> R,
+    W: Write,
+{
+    if let Some(raw) = &out.raw_fields {
+        return write_raw(out, &raw.take_json()?, w);
+    }
+    if let Some(jq) = &out.jq {
+        return jq.write(data, out.format == OutputFormat::Ndjson, w);
+    }
+    if let Some(template) = &out.template {
+        return data
+            .iter()
+            .try_for_each(|item| write_templated(template, item, w));
+    }
+    if !out.uses_columns() {
+        return format::write_data(out.format, data, w);
+    }
+    let columns = select_columns::<R>(out.columns.as_deref())?;
+    let rows: Vec<R> = data.iter().map(row).collect();
+    if out.format == OutputFormat::Table {
+        if !rows.is_empty() {
+            for line in render(&columns, &rows, output_width()) {
+                writeln!(w, "{}", line)?;
+            }
+        }
+        return Ok(());
+    }
+    let headers: Vec<&str> = columns.iter().map(|c| c.name).collect();
+    let records = rows.iter().map(|r| {
+        columns
+            .iter()
+            .map(|c| r.cell(c.name).into_export())
+            .collect::<Vec<_>>()
+    });
+    format::write_delimited(out.format, &headers, records, w)
+}
+
+/// Write `--raw-fields` output. It has no table form, so table output is
+/// JSON, and a template is applied to each item of a list.
+fn write_raw<W: Write>(out: &OutputOptions, value: &Value, w: &mut W) -> Result<()> {
+    if let Some(jq) = &out.jq {
+        return jq.write(value, out.format == OutputFormat::Ndjson, w);
+    }
+    if let Some(template) = &out.template {
+        return match value {
+            Value::Array(items) => items
+                .iter()
+                .try_for_each(|item| write_templated(template, item, w)),
+            value => write_templated(template, value, w),
+        };
+    }
+    let format = match out.format {
+        OutputFormat::Table => OutputFormat::Json,
+        format => format,
+    };
+    format::write_data(format, value, w)
+}
+
+/// Write one rendered item, ending it with a newline if the template doesn't.
+fn write_templated<T: Serialize, W: Write>(template: &Template, data: &T, w: &mut W) -> Result<()> {
+    let text = template.render(data)?;
+    if text.ends_with('\n') {
+        write!(w, "{}", text)?;
+    } else {
+        writeln!(w, "{}", text)?;
+    }
+    Ok(())
+}
+
+// ── Color helpers ──
+
+pub fn colorize_status(status: &str) -> String {
+    match status.to_lowercase().as_str() {
+        "active" | "open" | "new" => status.green().to_string(),
+        "on_hold" | "on hold" | "in_progress" | "in progress" => status.yellow().to_string(),
+        "archived" | "closed" | "completed" | "resolved" => status.red().to_string(),
+        "cancelled" | "rejected" => status.red().dimmed().to_string(),
+        _ => status.to_string(),
+    }
+}
+
+pub fn colorize_priority(priority: &str) -> String {
+    match priority.to_lowercase().as_str() {
+        "critical" => priority.red().bold().to_string(),
+        "high" => priority.red().to_string(),
+        "normal" | "medium" => priority.yellow().to_string(),
+        "low" => priority.green().to_string(),
+        _ => priority.to_string(),
+    }
+}
+
+pub fn colorize_ticket_type(ticket_type: &str) -> String {
+    match ticket_type.to_lowercase().as_str() {
+        "bug" => ticket_type.red().to_string(),
+        "enhancement" | "feature" => ticket_type.cyan().to_string(),
+        "task" => ticket_type.blue().to_string(),
+        _ => ticket_type.to_string(),
+    }
+}
+
+pub fn colorize_mr_status(status: &str) -> String {
+    match status.to_lowercase().as_str() {
+        "new" | "open" => status.green().to_string(),
+        "merged" => status.magenta().to_string(),
+        "closed" | "rejected" => status.red().to_string(),
+        _ => status.to_string(),
+    }
+}
+
+pub fn colorize_bool(val: bool, true_label: &str, false_label: &str) -> String {
+    if val {
+        true_label.green().to_string()
+    } else {
+        false_label.red().to_string()
+    }
+}
+
+pub fn dim(text: &str) -> String {
+    text.dimmed().to_string()
+}
+
+pub fn bold(text: &str) -> String {
+    text.bold().to_string()
+}
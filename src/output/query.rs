@@ -0,0 +1,122 @@
+//! `--jq`: jq filters applied to command output, using the pure-Rust jaq
+//! implementation so no `jq` binary is needed.
+
+use std::fmt;
+use std::io::Write;
+
+use anyhow::{Result, anyhow};
+use jaq_core::load::{Arena, File, Loader, lex, parse};
+use jaq_core::{Compiler, Ctx, Native, RcIter};
+use jaq_json::Val;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A compiled jq filter.
+#[derive(Clone)]
+pub struct JqFilter {
+    source: String,
+    filter: jaq_core::Filter<Native<Val>>,
+}
+
+impl fmt::Debug for JqFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JqFilter").field(&self.source).finish()
+    }
+}
+
+impl JqFilter {
+    /// Compile `source`, with jq's standard library available.
+    pub fn parse(source: &str) -> Result<Self> {
+        let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+        let arena = Arena::default();
+        let program = File {
+            code: source,
+            path: (),
+        };
+        let modules = loader.load(&arena, program).map_err(|errors| {
+            let messages: Vec<String> = errors
+                .into_iter()
+                .flat_map(|(_, e)| load_error_messages(e))
+                .collect();
+            anyhow!("Invalid jq filter: {}", messages.join("; "))
+        })?;
+        let filter = Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errors| {
+                let messages: Vec<String> = errors
+                    .into_iter()
+                    .flat_map(|(_, e)| e)
+                    .map(|(name, undefined)| format!("undefined {} `{}`", undefined.as_str(), name))
+                    .collect();
+                anyhow!("Invalid jq filter: {}", messages.join("; "))
+            })?;
+        Ok(JqFilter {
+            source: source.to_string(),
+            filter,
+        })
+    }
+
+    /// Run the filter on `data`'s serialized form, returning every output.
+    pub fn run<T: Serialize + ?Sized>(&self, data: &T) -> Result<Vec<Value>> {
+        let input = Val::from(serde_json::to_value(data)?);
+        let inputs = RcIter::new(core::iter::empty());
+        self.filter
+            .run((Ctx::new([], &inputs), input))
+            .map(|result| {
+                result
+                    .map(Value::from)
+                    .map_err(|e| anyhow!("jq: error: {}", e))
+            })
+            .collect()
+    }
+
+    /// Run the filter and write each output on its own: strings raw (like
+    /// `jq -r`), anything else as JSON, pretty unless `compact`.
+    pub fn write<T, W>(&self, data: &T, compact: bool, w: &mut W) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+        W: Write,
+    {
+        for value in self.run(data)? {
+            match value {
+                Value::String(s) => writeln!(w, "{}", s)?,
+                value if compact => writeln!(w, "{}", value)?,
+                value => writeln!(w, "{}", serde_json::to_string_pretty(&value)?)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn load_error_messages(error: jaq_core::load::Error<&str>) -> Vec<String> {
+    use jaq_core::load::Error;
+    match error {
+        Error::Io(errors) => errors
+            .into_iter()
+            .map(|(path, e)| format!("{}: {}", path, e))
+            .collect(),
+        Error::Lex(errors) => errors
+            .into_iter()
+            .map(|(expect, at): lex::Error<&str>| {
+                format!("expected {} {}", expect.as_str(), position(at))
+            })
+            .collect(),
+        Error::Parse(errors) => errors
+            .into_iter()
+            .map(|(expect, at): parse::Error<&str>| {
+                format!("expected {} {}", expect.as_str(), position(at))
+            })
+            .collect(),
+    }
+}
+
+/// Where in the filter an error was found, given the text from that point.
+fn position(rest: &str) -> String {
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return "at end of filter".to_string();
+    }
+    let snippet: String = rest.chars().take(20).collect();
+    format!("at `{}`", snippet)
+}
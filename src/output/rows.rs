@@ -0,0 +1,439 @@
+//! Table columns for each listed model.
+
+use colored::Colorize;
+
+use super::table::{Cell, Column, TableRow};
+use super::{colorize_bool, colorize_mr_status, colorize_priority, colorize_status};
+use super::{colorize_ticket_type, dim};
+use crate::api::lookup::ProjectLookup;
+use crate::api::models::*;
+
+fn bold(s: &str) -> String {
+    s.bold().to_string()
+}
+
+fn cyan(s: &str) -> String {
+    s.cyan().to_string()
+}
+
+fn green(s: &str) -> String {
+    s.green().to_string()
+}
+
+fn yellow(s: &str) -> String {
+    s.yellow().to_string()
+}
+
+fn full_name(first: Option<&str>, last: Option<&str>) -> String {
+    [first, last]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl TableRow for Project {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("permalink"),
+        Column::fixed("status"),
+        Column::fixed("open"),
+        Column::fixed("closed"),
+        Column::fixed("total"),
+        Column::flex("overview"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "permalink", "status", "open"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.project_id),
+            "name" => Cell::opt(self.name.as_deref()).styled(bold),
+            "permalink" => Cell::opt(self.permalink.as_deref()).styled(dim),
+            "status" => Cell::opt(self.status.as_deref()).styled(colorize_status),
+            "open" => Cell::num(self.open_tickets),
+            "closed" => Cell::num(self.closed_tickets),
+            "total" => Cell::num(self.total_tickets),
+            "overview" => Cell::opt(self.overview.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for ProjectGroup {
+    const COLUMNS: &'static [Column] = &[Column::fixed("id"), Column::flex("label")];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "label"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "label" => Cell::opt(self.label.as_deref()).styled(bold),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for ProjectUser {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("username"),
+        Column::fixed("email"),
+        Column::flex("company"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name", "username"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::new(full_name(
+                self.first_name.as_deref(),
+                self.last_name.as_deref(),
+            )),
+            "username" => Cell::opt(self.username.as_deref()).styled(dim),
+            "email" => Cell::opt(self.email_address.as_deref()),
+            "company" => Cell::opt(self.company.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Repository {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("name"),
+        Column::fixed("permalink"),
+        Column::fixed("disk-usage"),
+        Column::fixed("last-commit"),
+        Column::fixed("source"),
+        Column::flex("clone-url"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "permalink", "clone-url"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "name" => Cell::opt(self.name.as_deref()).styled(bold),
+            "permalink" => Cell::opt(self.permalink.as_deref()).styled(dim),
+            "disk-usage" => Cell::num(self.disk_usage),
+            "last-commit" => Cell::opt(self.last_commit_ref.as_deref()).styled(yellow),
+            "source" => Cell::opt(self.source.as_deref()),
+            "clone-url" => Cell::opt(self.clone_url.as_deref()).styled(cyan),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Commit {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("sha"),
+        Column::fixed("author"),
+        Column::fixed("email"),
+        Column::fixed("date"),
+        Column::flex("message"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["sha", "author", "date", "message"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "sha" => {
+                let sha = self.commit_ref.as_deref().unwrap_or("");
+                Cell::abbreviated(sha.get(..7).unwrap_or(sha), sha).styled(yellow)
+            }
+            "author" => Cell::opt(self.author_name.as_deref()).styled(bold),
+            "email" => Cell::opt(self.author_email.as_deref()).styled(dim),
+            "date" => Cell::opt(self.authored_at.as_deref()).styled(dim),
+            "message" => Cell::opt(self.message.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Hook {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("username"),
+        Column::flex("url"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "url"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "username" => Cell::opt(self.username.as_deref()),
+            "url" => Cell::opt(self.url.as_deref()).styled(cyan),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Branch {
+    const COLUMNS: &'static [Column] = &[Column::flex("name")];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "name" => Cell::opt(self.name.as_deref()).styled(cyan),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for MergeRequest {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("status"),
+        Column::fixed("source"),
+        Column::fixed("target"),
+        Column::fixed("user-id"),
+        Column::fixed("created"),
+        Column::fixed("updated"),
+        Column::flex("subject"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["id", "status", "source", "target", "subject"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id).styled(bold),
+            "status" => Cell::opt(self.status.as_deref()).styled(colorize_mr_status),
+            "source" => Cell::opt(self.source_ref.as_deref()).styled(cyan),
+            "target" => Cell::opt(self.target_ref.as_deref()).styled(green),
+            "user-id" => Cell::num(self.user_id),
+            "created" => Cell::opt(self.created_at.as_deref()).styled(dim),
+            "updated" => Cell::opt(self.updated_at.as_deref()).styled(dim),
+            "subject" => Cell::opt(self.subject.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+/// A ticket with its project's lookup tables, so status, priority and
+/// category show as names rather than IDs.
+pub struct TicketRow<'a> {
+    pub ticket: &'a Ticket,
+    pub lookup: &'a ProjectLookup,
+}
+
+impl TableRow for TicketRow<'_> {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("type"),
+        Column::fixed("status"),
+        Column::fixed("priority"),
+        Column::fixed("category"),
+        Column::fixed("assignee"),
+        Column::fixed("reporter"),
+        Column::fixed("milestone"),
+        Column::fixed("tags"),
+        Column::flex("summary"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["id", "type", "status", "priority", "assignee", "summary"];
+
+    fn cell(&self, column: &str) -> Cell {
+        let t = self.ticket;
+        let named = |id: Option<i64>, name: Option<&str>| match (id, name) {
+            (_, Some(name)) => name.to_string(),
+            (Some(id), None) => format!("#{}", id),
+            (None, None) => String::new(),
+        };
+        match column {
+            "id" => Cell::num(t.ticket_id).styled(bold),
+            "type" => Cell::opt(t.ticket_type.as_deref()).styled(colorize_ticket_type),
+            "status" => Cell::new(named(
+                t.status_id,
+                t.status_id.and_then(|id| self.lookup.status_name(id)),
+            ))
+            .styled(colorize_status),
+            "priority" => Cell::new(named(
+                t.priority_id,
+                t.priority_id.and_then(|id| self.lookup.priority_name(id)),
+            ))
+            .styled(colorize_priority),
+            "category" => Cell::new(named(
+                t.category_id,
+                t.category_id.and_then(|id| self.lookup.category_name(id)),
+            )),
+            "assignee" => Cell::opt(t.assignee.as_deref()),
+            "reporter" => Cell::opt(t.reporter.as_deref()),
+            "milestone" => Cell::num(t.milestone_id),
+            "tags" => Cell::opt(t.tags.as_deref()).styled(dim),
+            "summary" => Cell::opt(t.summary.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for TicketNote {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("time"),
+        Column::fixed("private"),
+        Column::flex("content"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "time", "content"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id).styled(bold),
+            "time" => Cell::opt(self.time_added.as_deref()).styled(dim),
+            "private" => Cell::new(if self.private.unwrap_or(false) {
+                "yes"
+            } else {
+                ""
+            }),
+            "content" => Cell::opt(self.content.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Watcher {
+    const COLUMNS: &'static [Column] = &[Column::fixed("user-id")];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["user-id"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "user-id" => Cell::num(self.watcher),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for TicketStatus {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("state"),
+        Column::fixed("order"),
+        Column::fixed("colour"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name", "state"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::opt(self.name.as_deref()).styled(bold),
+            "state" => {
+                if self.treat_as_closed.unwrap_or(false) {
+                    Cell::new("closed").styled(|s| colorize_bool(false, "", s))
+                } else {
+                    Cell::new("open").styled(|s| colorize_bool(true, s, ""))
+                }
+            }
+            "order" => Cell::num(self.order),
+            "colour" => Cell::opt(self.background_colour.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for TicketPriority {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("default"),
+        Column::fixed("position"),
+        Column::fixed("colour"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name", "default"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::opt(self.name.as_deref()).styled(colorize_priority),
+            "default" => Cell::new(if self.default.unwrap_or(false) {
+                "*"
+            } else {
+                ""
+            })
+            .styled(green),
+            "position" => Cell::num(self.position),
+            "colour" => Cell::opt(self.colour.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for TicketCategory {
+    const COLUMNS: &'static [Column] = &[Column::fixed("id"), Column::flex("name")];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::opt(self.name.as_deref()).styled(bold),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for TicketType {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("icon"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::opt(self.name.as_deref()).styled(colorize_ticket_type),
+            "icon" => Cell::opt(self.icon.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Milestone {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("id"),
+        Column::fixed("name"),
+        Column::fixed("status"),
+        Column::fixed("start"),
+        Column::fixed("deadline"),
+        Column::fixed("responsible"),
+        Column::fixed("parent"),
+        Column::fixed("estimate"),
+        Column::flex("description"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "name", "status", "start", "deadline"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "id" => Cell::num(self.id),
+            "name" => Cell::opt(self.name.as_deref()).styled(bold),
+            "status" => Cell::opt(self.status.as_deref()).styled(colorize_status),
+            "start" => Cell::opt(self.start_at.as_deref()).styled(dim),
+            "deadline" => Cell::opt(self.deadline.as_deref()).styled(dim),
+            "responsible" => Cell::num(self.responsible_user_id),
+            "parent" => Cell::num(self.parent_id),
+            "estimate" => Cell::num(self.estimated_time),
+            "description" => Cell::opt(self.description.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
+
+impl TableRow for Event {
+    const COLUMNS: &'static [Column] = &[
+        Column::fixed("type"),
+        Column::fixed("timestamp"),
+        Column::fixed("project"),
+        Column::flex("title"),
+        Column::flex("subject"),
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["type", "timestamp", "title"];
+
+    fn cell(&self, column: &str) -> Cell {
+        match column {
+            "type" => Cell::opt(self.event_type.as_deref()).styled(cyan),
+            "timestamp" => Cell::opt(self.timestamp.as_deref()).styled(dim),
+            "project" => Cell::opt(self.project_permalink.as_deref()),
+            "subject" => Cell::opt(self.subject.as_deref()),
+            "title" => Cell::opt(self.title.as_deref()),
+            _ => Cell::default(),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::io::IsTerminal;
+
+use colored::Colorize;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Space between columns.
+const GAP: usize = 2;
+
+/// Truncatable columns never shrink below this many characters.
+const MIN_FLEX_WIDTH: usize = 12;
+
+/// A column a row type can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// Name used with `--columns`, e.g. `summary`.
+    pub name: &'static str,
+    /// Whether the column may be truncated to fit the terminal.
+    pub flex: bool,
+}
+
+impl Column {
+    pub const fn fixed(name: &'static str) -> Self {
+        Column { name, flex: false }
+    }
+
+    /// A long free-text column (summary, subject, message) that gives up
+    /// width first when the table is too wide.
+    pub const fn flex(name: &'static str) -> Self {
+        Column { name, flex: true }
+    }
+}
+
+/// One table cell: plain text plus an optional colorizer applied after
+/// truncation and padding, so escape codes never affect alignment.
+#[derive(Clone, Default)]
+pub struct Cell {
+    pub text: String,
+    pub style: Option<fn(&str) -> String>,
+    /// The full value for CSV and TSV, when `text` is shortened for the
+    /// terminal.
+    pub export: Option<String>,
+}
+
+impl Cell {
+    pub fn new(text: impl Into<String>) -> Self {
+        Cell {
+            text: text.into(),
+            style: None,
+            export: None,
+        }
+    }
+
+    /// A cell showing `text` in the table but exporting `full` to CSV and
+    /// TSV.
+    pub fn abbreviated(text: impl Into<String>, full: impl Into<String>) -> Self {
+        Cell {
+            export: Some(full.into()),
+            ..Cell::new(text)
+        }
+    }
+
+    /// The value written to CSV and TSV.
+    pub fn into_export(self) -> String {
+        self.export.unwrap_or(self.text)
+    }
+
+    /// A cell for an optional string field, empty when absent.
+    pub fn opt(text: Option<&str>) -> Self {
+        Cell::new(text.unwrap_or(""))
+    }
+
+    /// A cell for an optional numeric field, empty when absent.
+    pub fn num<N: ToString>(n: Option<N>) -> Self {
+        Cell::new(n.map(|n| n.to_string()).unwrap_or_default())
+    }
+
+    pub fn styled(mut self, style: fn(&str) -> String) -> Self {
+        self.style = Some(style);
+        self
+    }
+}
+
+impl std::fmt::Debug for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cell").field(&self.text).finish()
+    }
+}
+
+/// A value that can be shown as one row of a table.
+pub trait TableRow {
+    /// Every column this row type can show, in display order.
+    const COLUMNS: &'static [Column];
+    /// Columns shown when `--columns` is not given.
+    const DEFAULT_COLUMNS: &'static [&'static str];
+
+    /// The cell for `column`, one of the names in `COLUMNS`.
+    fn cell(&self, column: &str) -> Cell;
+}
+
+impl<T: TableRow> TableRow for &T {
+    const COLUMNS: &'static [Column] = T::COLUMNS;
+    const DEFAULT_COLUMNS: &'static [&'static str] = T::DEFAULT_COLUMNS;
+
+    fn cell(&self, column: &str) -> Cell {
+        (**self).cell(column)
+    }
+}
+
+/// Resolve `--columns` against the columns `R` offers, defaulting to
+/// `R::DEFAULT_COLUMNS`. Unknown names are an error listing the valid ones.
+pub fn select_columns<R: TableRow>(requested: Option<&[String]>) -> anyhow::Result<Vec<Column>> {
+    let find = |name: &str| R::COLUMNS.iter().find(|c| c.name == name).copied();
+    let Some(requested) = requested else {
+        return Ok(R::DEFAULT_COLUMNS.iter().filter_map(|n| find(n)).collect());
+    };
+    requested
+        .iter()
+        .map(|name| {
+            let name = name.trim().to_lowercase();
+            find(&name).ok_or_else(|| {
+                UnknownColumn {
+                    name,
+                    available: R::COLUMNS.iter().map(|c| c.name).collect(),
+                }
+                .into()
+            })
+        })
+        .collect()
+}
+
+/// A `--columns` name that the command's rows don't have.
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown column '{name}'. Available columns: {}", available.join(", "))]
+pub struct UnknownColumn {
+    pub name: String,
+    pub available: Vec<&'static str>,
+}
+
+/// Width available for tables: `$COLUMNS`, else the terminal width when
+/// stdout is a terminal, else unlimited (so piped output is never truncated).
+pub fn output_width() -> Option<usize> {
+    if let Some(cols) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.trim().parse::<usize>().ok())
+        .filter(|&c| c > 0)
+    {
+        return Some(cols);
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(w, _)| w.0 as usize)
+}
+
+/// Lay out `rows` under `columns`, fitting within `width` when given by
+/// truncating flex columns. Returns the lines to print, header first.
+pub fn render<R: TableRow>(columns: &[Column], rows: &[R], width: Option<usize>) -> Vec<String> {
+    let cells: Vec<Vec<Cell>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|c| row.cell(c.name)).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            cells
+                .iter()
+                .map(|r| first_line(&r[i].text).width())
+                .chain(std::iter::once(c.name.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    if let Some(width) = width {
+        fit(columns, &mut widths, width);
+    }
+
+    let header: Vec<Cell> = columns
+        .iter()
+        .map(|c| Cell::new(c.name.to_uppercase()).styled(|s| s.bold().to_string()))
+        .collect();
+    std::iter::once(&header)
+        .chain(cells.iter())
+        .map(|row| format_row(row, &widths))
+        .collect()
+}
+
+/// Shrink flex columns (rightmost first) until the row fits in `width`.
+fn fit(columns: &[Column], widths: &mut [usize], width: usize) {
+    let total =
+        |widths: &[usize]| widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1);
+    let mut excess = total(widths).saturating_sub(width);
+    for (i, column) in columns.iter().enumerate().rev() {
+        if excess == 0 {
+            break;
+        }
+        if !column.flex || widths[i] <= MIN_FLEX_WIDTH {
+            continue;
+        }
+        let shrink = excess.min(widths[i] - MIN_FLEX_WIDTH);
+        widths[i] -= shrink;
+        excess -= shrink;
+    }
+}
+
+fn format_row(row: &[Cell], widths: &[usize]) -> String {
+    let last = row.len().saturating_sub(1);
+    let mut line = String::new();
+    for (i, (cell, &width)) in row.iter().zip(widths).enumerate() {
+        let text = truncate(first_line(&cell.text), width);
+        let pad = width.saturating_sub(text.width());
+        match cell.style {
+            Some(style) => line.push_str(&style(&text)),
+            None => line.push_str(&text),
+        }
+        if i < last {
+            line.push_str(&" ".repeat(pad + GAP));
+        }
+    }
+    line
+}
+
+/// Only the first line of multi-line text (commit messages, notes) fits a row.
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or("")
+}
+
+/// Cut `text` to at most `width` display columns, marking the cut with `…`.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width - 1 {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
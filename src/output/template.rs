@@ -0,0 +1,405 @@
+//! `--template` output: a small Go-template style language for scripting.
+//!
+//! ```text
+//! {{ticket_id}}\t{{assignee}}\t{{summary | truncate 40}}
+//! {{color "yellow" .sha}} {{.author-name}} {{timeago .authored-at}}
+//! ```
+//!
+//! An action is a pipeline of commands separated by `|`; each command after
+//! the first gets the previous result as its last argument, as in Go. Fields
+//! may be written `name` or `.name`, with `_` and `-` interchangeable, and
+//! `a.b` reaches into nested objects. `{{.}}` is the whole value. In literal
+//! text, `\t`, `\n` and `\\` are escapes so templates are easy to pass from a
+//! shell.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow, bail};
+use colored::{Color, Colorize};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::table::truncate;
+
+/// A parsed `--template`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Action(Vec<Command>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    func: Option<Func>,
+    args: Vec<Arg>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Arg {
+    /// A field path; empty for `.` itself.
+    Field(Vec<String>),
+    Str(String),
+    Int(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Color,
+    Truncate,
+    Timeago,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "color" => Some(Self::Color),
+            "truncate" => Some(Self::Truncate),
+            "timeago" => Some(Self::Timeago),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Color => "color",
+            Self::Truncate => "truncate",
+            Self::Timeago => "timeago",
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Self::Color | Self::Truncate => 2,
+            Self::Timeago => 1,
+        }
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Text(unescape(&rest[..start])));
+            }
+            let (action, after) = parse_action(&rest[start + 2..])?;
+            segments.push(Segment::Action(action));
+            rest = after;
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(unescape(rest)));
+        }
+        Ok(Template { segments })
+    }
+
+    /// Render the template against `data`'s serialized form.
+    pub fn render<T: Serialize + ?Sized>(&self, data: &T) -> Result<String> {
+        let value = serde_json::to_value(data)?;
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Action(pipeline) => out.push_str(&text(&run(pipeline, &value)?)),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Parse the inside of one `{{ … }}`, returning the pipeline and the text
+/// after the closing braces.
+fn parse_action(input: &str) -> Result<(Vec<Command>, &str)> {
+    let mut chars = input.char_indices().peekable();
+    let mut tokens: Vec<Token> = Vec::new();
+    let end = loop {
+        let Some((i, c)) = chars.next() else {
+            bail!("Invalid template: unclosed {{{{");
+        };
+        match c {
+            '}' if input[i..].starts_with("}}") => break i + 2,
+            c if c.is_whitespace() => {}
+            '|' => tokens.push(Token::Pipe),
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, 'n')) => s.push('\n'),
+                            Some((_, 't')) => s.push('\t'),
+                            Some((_, c)) => s.push(c),
+                            None => bail!("Invalid template: unterminated string"),
+                        },
+                        Some((_, c)) => s.push(c),
+                        None => bail!("Invalid template: unterminated string"),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ => {
+                let mut word = c.to_string();
+                while let Some(&(j, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '|' || c == '"' || input[j..].starts_with("}}") {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    };
+
+    let pipeline = tokens
+        .split(|t| *t == Token::Pipe)
+        .enumerate()
+        .map(|(i, words)| parse_command(words, i > 0))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((pipeline, &input[end..]))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Pipe,
+    Str(String),
+    Word(String),
+}
+
+fn parse_command(tokens: &[Token], piped: bool) -> Result<Command> {
+    let Some(first) = tokens.first() else {
+        bail!("Invalid template: empty action");
+    };
+    let func = match first {
+        Token::Word(w) => Func::from_name(w),
+        _ => None,
+    };
+    let args = tokens[usize::from(func.is_some())..]
+        .iter()
+        .map(|t| match t {
+            Token::Str(s) => Ok(Arg::Str(s.clone())),
+            Token::Word(w) => parse_operand(w),
+            Token::Pipe => unreachable!("split on pipes"),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let given = args.len() + usize::from(piped);
+    match func {
+        Some(func) if given != func.arity() => bail!(
+            "Invalid template: {} takes {} argument{}, got {}",
+            func.name(),
+            func.arity(),
+            if func.arity() == 1 { "" } else { "s" },
+            given
+        ),
+        None if piped => {
+            bail!("Invalid template: expected a function after '|' (color, truncate or timeago)")
+        }
+        None if args.len() != 1 => bail!(
+            "Invalid template: unknown function '{}' (expected color, truncate or timeago)",
+            match first {
+                Token::Word(w) | Token::Str(w) => w.as_str(),
+                Token::Pipe => "|",
+            }
+        ),
+        _ => {}
+    }
+    Ok(Command { func, args })
+}
+
+fn parse_operand(word: &str) -> Result<Arg> {
+    if let Ok(n) = word.parse::<i64>() {
+        return Ok(Arg::Int(n));
+    }
+    let path = word.strip_prefix('.').unwrap_or(word);
+    if path.is_empty() {
+        return Ok(Arg::Field(Vec::new()));
+    }
+    if path.split('.').any(str::is_empty) {
+        bail!("Invalid template: bad field '{}'", word);
+    }
+    Ok(Arg::Field(path.split('.').map(String::from).collect()))
+}
+
+fn run(pipeline: &[Command], data: &Value) -> Result<Value> {
+    let mut piped: Option<Value> = None;
+    for command in pipeline {
+        let mut args: Vec<Value> = command.args.iter().map(|a| eval(a, data)).collect();
+        args.extend(piped.take());
+        piped = Some(match command.func {
+            None => args.remove(0),
+            Some(func) => call(func, &args)?,
+        });
+    }
+    Ok(piped.unwrap_or(Value::Null))
+}
+
+fn eval(arg: &Arg, data: &Value) -> Value {
+    match arg {
+        Arg::Str(s) => Value::String(s.clone()),
+        Arg::Int(n) => Value::from(*n),
+        Arg::Field(path) => path
+            .iter()
+            .try_fold(data, |value, key| lookup(value, key))
+            .cloned()
+            .unwrap_or(Value::Null),
+    }
+}
+
+/// Look up `key` in an object, accepting `_` for `-` and vice versa since
+/// models serialize with the API's dashed names.
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    let map = value.as_object()?;
+    map.get(key)
+        .or_else(|| map.get(&key.replace('_', "-")))
+        .or_else(|| map.get(&key.replace('-', "_")))
+}
+
+fn call(func: Func, args: &[Value]) -> Result<Value> {
+    let s = match func {
+        Func::Color => {
+            let name = text(&args[0]);
+            let value = text(&args[1]);
+            match name.as_str() {
+                "bold" => value.bold().to_string(),
+                "dim" => value.dimmed().to_string(),
+                "underline" => value.underline().to_string(),
+                _ => {
+                    let color: Color = name
+                        .parse()
+                        .map_err(|_| anyhow!("Unknown color '{}' in template", name))?;
+                    value.color(color).to_string()
+                }
+            }
+        }
+        Func::Truncate => {
+            let width = args[0]
+                .as_u64()
+                .ok_or_else(|| anyhow!("truncate expects a width, got '{}'", text(&args[0])))?;
+            truncate(&text(&args[1]), width as usize)
+        }
+        Func::Timeago => {
+            let value = text(&args[0]);
+            relative_time(&value, SystemTime::now()).unwrap_or(value)
+        }
+    };
+    Ok(Value::String(s))
+}
+
+/// How a value prints: strings as-is, null as nothing, anything else as
+/// compact JSON.
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Describe an API timestamp relative to `now`, e.g. "3 hours ago".
+/// Returns `None` if `timestamp` is not a recognizable date and time.
+pub fn relative_time(timestamp: &str, now: SystemTime) -> Option<String> {
+    let then = parse_timestamp(timestamp)?;
+    let now = now.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+    let delta = now - then;
+    let secs = delta.unsigned_abs();
+    if secs < 60 {
+        return Some("just now".to_string());
+    }
+    let (n, unit) = [
+        (365 * 86400, "year"),
+        (30 * 86400, "month"),
+        (86400, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+    ]
+    .into_iter()
+    .find(|&(size, _)| secs >= size)
+    .map(|(size, unit)| (secs / size, unit))?;
+    let plural = if n == 1 { "" } else { "s" };
+    Some(if delta >= 0 {
+        format!("{} {}{} ago", n, unit, plural)
+    } else {
+        format!("in {} {}{}", n, unit, plural)
+    })
+}
+
+/// Seconds since the epoch for `YYYY-MM-DD[T ]HH:MM:SS[.fff][Z|±HH:MM| UTC]`.
+/// A timestamp without an offset is taken as UTC.
+fn parse_timestamp(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    let separators_ok = s.get(4..5) == Some("-")
+        && s.get(7..8) == Some("-")
+        && matches!(s.get(10..11), Some("T" | " "))
+        && s.get(13..14) == Some(":")
+        && s.get(16..17) == Some(":");
+    if !separators_ok || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        rest = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+    }
+    let offset = match rest.trim() {
+        "" | "Z" | "UTC" => 0,
+        zone => {
+            let sign = match zone.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let digits: String = zone[1..].chars().filter(|c| *c != ':').collect();
+            if digits.chars().count() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let hours: i64 = digits[..2].parse().ok()?;
+            let minutes: i64 = digits[2..].parse().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    Some(days * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
@@ -1,6 +1,5 @@
 use codebase_cli::api::activity::{account_activity, project_activity};
 use codebase_cli::api::client::CodebaseClient;
-use mockito;
 
 #[tokio::test]
 async fn test_account_activity_no_params() {
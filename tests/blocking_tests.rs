@@ -0,0 +1,118 @@
+use codebase_cli::api::client::CodebaseClient as AsyncClient;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::fake::FakeTransport;
+use codebase_cli::api::retry::RetryPolicy;
+use codebase_cli::blocking::CodebaseClient;
+use codebase_cli::mock_server::Store;
+
+fn fake_client() -> CodebaseClient {
+    CodebaseClient::try_from(FakeTransport::new(Store::sample()).client()).unwrap()
+}
+
+#[test]
+fn test_blocking_calls_mirror_the_async_api() {
+    let client = fake_client();
+
+    let projects = client.list_projects().unwrap();
+    assert_eq!(projects.len(), 2);
+    let ticket = client
+        .create_ticket(
+            "widgets",
+            "Search ignores accents",
+            "Bug",
+            None,
+            None,
+            None,
+            Some(2),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(ticket.ticket_id, Some(4));
+    assert_eq!(ticket.assignee.as_deref(), Some("bob"));
+    client.set_watchers("widgets", 4, &[1]).unwrap();
+    assert_eq!(client.list_watchers("widgets", 4).unwrap().len(), 1);
+    let readme = client
+        .get_file("widgets", "storefront", "main", "README.md")
+        .unwrap();
+    assert!(readme.starts_with("# Storefront"));
+
+    let err = client.show_ticket("widgets", 99).unwrap_err();
+    assert!(matches!(err, CodebaseError::NotFound { .. }), "{:?}", err);
+}
+
+#[test]
+fn test_blocking_paginator_iterates_items_across_pages() {
+    let client = fake_client();
+    for n in 0..25 {
+        client
+            .create_ticket(
+                "widgets",
+                &format!("Ticket {}", n),
+                "Task",
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    let ids: Vec<i64> = client
+        .paginate_tickets("widgets")
+        .map(|t| t.unwrap().ticket_id.unwrap())
+        .collect();
+    assert_eq!(ids.len(), 28);
+    assert_eq!(ids[20], 21);
+
+    let limited = client
+        .paginate_tickets("widgets")
+        .limit(22)
+        .collect_all()
+        .unwrap();
+    assert_eq!(limited.len(), 22);
+    let mut second = client.paginate_tickets("widgets").start_page(2);
+    assert_eq!(second.next_page().unwrap().unwrap().len(), 8);
+    assert!(second.next_page().unwrap().is_none());
+}
+
+#[test]
+fn test_blocking_paginator_stops_after_an_error() {
+    let client = fake_client();
+    let mut pager = client.paginate_tickets("nope");
+    assert!(matches!(
+        pager.next(),
+        Some(Err(CodebaseError::NotFound { .. }))
+    ));
+    assert!(pager.next().is_none());
+}
+
+#[test]
+fn test_blocking_client_over_http() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/widgets/milestones")
+        .with_status(200)
+        .with_body(
+            r#"<ticketing-milestones><ticketing-milestone><id type="integer">1</id><name>v1.0</name></ticketing-milestone></ticketing-milestones>"#,
+        )
+        .create();
+    let client = CodebaseClient::try_from(
+        AsyncClient::with_base_url(
+            server.url(),
+            "acme".to_string(),
+            "acme/alice".to_string(),
+            "key".to_string(),
+        )
+        .with_retry_policy(RetryPolicy::none()),
+    )
+    .unwrap();
+
+    let milestones = client.list_milestones("widgets").unwrap();
+    assert_eq!(milestones[0].name.as_deref(), Some("v1.0"));
+    mock.assert();
+}
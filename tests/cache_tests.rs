@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use codebase_cli::api::cache::ResponseCache;
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::projects::{assign_project_users, list_project_users};
+use codebase_cli::api::tickets::list_statuses;
+use tempfile::TempDir;
+
+const STATUSES: &str = r#"<ticketing-statuses>
+    <ticketing-status><id>1</id><name>Open</name></ticketing-status>
+</ticketing-statuses>"#;
+
+fn cached_client(server: &mockito::Server, dir: &TempDir) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_cache(ResponseCache::for_user(
+        dir.path(),
+        "test-account",
+        "test-user",
+    ))
+}
+
+#[tokio::test]
+async fn test_lookup_served_from_cache_on_second_call() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/proj/tickets/statuses")
+        .with_status(200)
+        .with_body(STATUSES)
+        .expect(1)
+        .create_async()
+        .await;
+    let dir = TempDir::new().unwrap();
+    let client = cached_client(&server, &dir);
+
+    let first = list_statuses(&client, "proj").await.unwrap();
+    let second = list_statuses(&client, "proj").await.unwrap();
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(second[0].name.as_deref(), Some("Open"));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_client_without_cache_always_fetches() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/proj/tickets/statuses")
+        .with_status(200)
+        .with_body(STATUSES)
+        .expect(2)
+        .create_async()
+        .await;
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    );
+
+    list_statuses(&client, "proj").await.unwrap();
+    list_statuses(&client, "proj").await.unwrap();
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_errors_are_not_cached() {
+    let mut server = mockito::Server::new_async().await;
+    let failing = server
+        .mock("GET", "/proj/tickets/statuses")
+        .with_status(500)
+        .expect(1)
+        .create_async()
+        .await;
+    let dir = TempDir::new().unwrap();
+    let client = cached_client(&server, &dir);
+
+    assert!(list_statuses(&client, "proj").await.is_err());
+    failing.remove_async().await;
+
+    let ok = server
+        .mock("GET", "/proj/tickets/statuses")
+        .with_status(200)
+        .with_body(STATUSES)
+        .expect(1)
+        .create_async()
+        .await;
+    assert_eq!(list_statuses(&client, "proj").await.unwrap().len(), 1);
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_assigning_users_invalidates_cached_users() {
+    let mut server = mockito::Server::new_async().await;
+    let list = server
+        .mock("GET", "/proj/assignments")
+        .with_status(200)
+        .with_body("<users><user><id>1</id><username>a</username></user></users>")
+        .expect(2)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/proj/assignments")
+        .with_status(200)
+        .create_async()
+        .await;
+    let dir = TempDir::new().unwrap();
+    let client = cached_client(&server, &dir);
+
+    list_project_users(&client, "proj").await.unwrap();
+    list_project_users(&client, "proj").await.unwrap();
+    assign_project_users(&client, "proj", &[1, 2])
+        .await
+        .unwrap();
+    list_project_users(&client, "proj").await.unwrap();
+
+    list.assert_async().await;
+}
+
+#[test]
+fn test_entries_expire_and_are_scoped_per_user() {
+    let dir = TempDir::new().unwrap();
+    let alice = ResponseCache::for_user(dir.path(), "acme", "alice");
+    let bob = ResponseCache::for_user(dir.path(), "acme", "bob");
+
+    alice.put("/proj/tickets/statuses", STATUSES);
+
+    assert_eq!(
+        alice
+            .get("/proj/tickets/statuses", Duration::from_secs(60))
+            .as_deref(),
+        Some(STATUSES)
+    );
+    assert_eq!(alice.get("/proj/tickets/statuses", Duration::ZERO), None);
+    assert_eq!(
+        bob.get("/proj/tickets/statuses", Duration::from_secs(60)),
+        None
+    );
+
+    ResponseCache::new(dir.path()).clear().unwrap();
+    assert_eq!(
+        alice.get("/proj/tickets/statuses", Duration::from_secs(60)),
+        None
+    );
+}
+
+#[test]
+fn test_other_endpoints_get_their_own_cache() {
+    let dir = TempDir::new().unwrap();
+    let codebase =
+        ResponseCache::for_endpoint(dir.path(), "https://api3.codebasehq.com/", "acme", "alice");
+    let mock = ResponseCache::for_endpoint(dir.path(), "http://localhost:4000", "acme", "alice");
+
+    assert_eq!(
+        codebase.dir(),
+        ResponseCache::for_user(dir.path(), "acme", "alice").dir()
+    );
+    assert!(mock.dir().starts_with(dir.path().join("endpoints")));
+
+    codebase.put("/projects", "<projects/>");
+    assert_eq!(mock.get("/projects", Duration::from_secs(60)), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cache_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = TempDir::new().unwrap();
+    let cache = ResponseCache::for_user(&root.path().join("cb"), "acme", "jdoe");
+    cache.put("/widgets/assignments", "<users></users>");
+
+    let mode =
+        |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(&root.path().join("cb")), 0o700);
+    assert_eq!(mode(cache.dir()), 0o700);
+    let entry = std::fs::read_dir(cache.dir())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(mode(&entry.path()), 0o600);
+}
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use codebase_cli::api::cassette::{Cassette, RECORD_ENV, REPLAY_ENV};
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::retry::RetryPolicy;
+use codebase_cli::api::tickets;
+
+const TICKETS_XML: &str = r#"<tickets type="array">
+  <ticket>
+    <ticket-id type="integer">42</ticket-id>
+    <summary>Login broken</summary>
+  </ticket>
+</tickets>"#;
+
+fn client(base_url: String, cassette: Cassette) -> CodebaseClient {
+    CodebaseClient::builder(
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .base_url(base_url)
+    .cassette(cassette)
+    .build()
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_recorded_session_replays_without_a_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    let mut server = mockito::Server::new_async().await;
+    let _list = server
+        .mock("GET", "/proj/tickets?query=status%3Aopen")
+        .with_status(200)
+        .with_body(TICKETS_XML)
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("GET", "/proj/tickets/9")
+        .with_status(404)
+        .with_body("Not found")
+        .create_async()
+        .await;
+
+    let recorder = client(server.url(), Cassette::record(&path).unwrap());
+    let live = tickets::search_tickets(&recorder, "proj", "status:open")
+        .await
+        .unwrap();
+    recorder.get("/proj/tickets/9").await.unwrap_err();
+    drop(recorder);
+    drop(server);
+
+    let replayer = client(
+        "http://api.codebase.invalid".to_string(),
+        Cassette::replay(&path).unwrap(),
+    );
+    let replayed = tickets::search_tickets(&replayer, "proj", "status:open")
+        .await
+        .unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].ticket_id, live[0].ticket_id);
+    assert_eq!(replayed[0].summary.as_deref(), Some("Login broken"));
+    let err = replayer.get("/proj/tickets/9").await.unwrap_err();
+    assert!(matches!(err, CodebaseError::NotFound { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_recordings_are_scrubbed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/proj/tickets")
+        .with_status(201)
+        .with_body("<ticket><ticket-id>9</ticket-id><api-key>s3cret</api-key></ticket>")
+        .create_async()
+        .await;
+
+    let recorder = client(server.url(), Cassette::record(&path).unwrap());
+    let body = recorder
+        .post(
+            "/proj/tickets",
+            "<ticket><summary>test-key</summary><password>hunter2</password></ticket>".to_string(),
+        )
+        .await
+        .unwrap();
+
+    // The caller still gets the real response.
+    assert!(body.contains("s3cret"));
+    drop(recorder);
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(!saved.contains("s3cret"), "{}", saved);
+    assert!(!saved.contains("hunter2"), "{}", saved);
+    assert!(!saved.contains("test-key"), "{}", saved);
+    assert!(!saved.contains(&server.url()), "{}", saved);
+    assert!(saved.contains("<summary>[REDACTED]</summary>"), "{}", saved);
+
+    let interactions = Cassette::replay(&path).unwrap().interactions();
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions[0].method, "POST");
+    assert_eq!(interactions[0].path, "/proj/tickets");
+    assert_eq!(interactions[0].status, 201);
+}
+
+#[tokio::test]
+async fn test_replay_answers_repeated_requests_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    std::fs::write(
+        &path,
+        r#"{"interactions": [
+            {"method": "GET", "path": "/projects", "status": 200, "response_body": "<projects type=\"array\"></projects>"},
+            {"method": "GET", "path": "/projects", "status": 500, "response_body": "oops"}
+        ]}"#,
+    )
+    .unwrap();
+    let replayer = client(
+        "http://api.codebase.invalid".to_string(),
+        Cassette::replay(&path).unwrap(),
+    );
+
+    assert_eq!(
+        replayer.get("/projects").await.unwrap(),
+        "<projects type=\"array\"></projects>"
+    );
+    let err = replayer.get("/projects").await.unwrap_err();
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(500));
+
+    let err = replayer.get("/projects").await.unwrap_err();
+    assert!(matches!(err, CodebaseError::Cassette(_)), "{:?}", err);
+    assert!(err.to_string().contains("GET /projects"), "{}", err);
+    assert_eq!(err.exit_code(), 1);
+}
+
+#[tokio::test]
+async fn test_replayed_retries_do_not_wait() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    std::fs::write(
+        &path,
+        r#"{"interactions": [
+            {"method": "GET", "path": "/projects", "status": 429, "retry_after": "30", "response_body": ""},
+            {"method": "GET", "path": "/projects", "status": 200, "response_body": "<projects/>"}
+        ]}"#,
+    )
+    .unwrap();
+    let replayer = client(
+        "http://api.codebase.invalid".to_string(),
+        Cassette::replay(&path).unwrap(),
+    )
+    .with_retry_policy(RetryPolicy {
+        max_delay: Duration::from_secs(60),
+        ..RetryPolicy::default()
+    });
+
+    let started = std::time::Instant::now();
+    assert_eq!(replayer.get("/projects").await.unwrap(), "<projects/>");
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_recording_is_written_on_flush_and_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/projects")
+        .with_status(200)
+        .with_body("<projects/>")
+        .create_async()
+        .await;
+    let saved = || Cassette::replay(&path).unwrap().interactions().len();
+
+    let cassette = Cassette::record(&path).unwrap();
+    let recorder = client(server.url(), cassette.clone());
+    recorder.get("/projects").await.unwrap();
+    assert_eq!(saved(), 0);
+    cassette.flush().unwrap();
+    assert_eq!(saved(), 1);
+
+    recorder.get("/projects").await.unwrap();
+    drop(cassette);
+    assert_eq!(saved(), 1);
+    drop(recorder);
+    assert_eq!(saved(), 2);
+}
+
+#[tokio::test]
+async fn test_transport_errors_are_recorded_and_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    // Nothing listens on a port that was just released.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let base_url = format!("http://127.0.0.1:{}", port);
+
+    let recorder = client(base_url.clone(), Cassette::record(&path).unwrap())
+        .with_retry_policy(RetryPolicy::none());
+    let live = recorder.get("/projects").await.unwrap_err();
+    assert!(
+        matches!(&live, CodebaseError::Transport(e) if e.is_connect()),
+        "{:?}",
+        live
+    );
+    drop(recorder);
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains(r#""kind": "connect""#), "{}", saved);
+    assert!(!saved.contains(&base_url), "{}", saved);
+    assert!(!saved.contains(r#""status""#), "{}", saved);
+
+    let replayer = client(
+        "http://api.codebase.invalid".to_string(),
+        Cassette::replay(&path).unwrap(),
+    )
+    .with_retry_policy(RetryPolicy::none());
+    let replayed = replayer.get("/projects").await.unwrap_err();
+    match &replayed {
+        CodebaseError::Transport(e) => assert!(e.is_connect(), "{:?}", e),
+        other => panic!("expected a transport error, got {:?}", other),
+    }
+    assert_eq!(replayed.exit_code(), live.exit_code());
+}
+
+#[test]
+fn test_cassette_from_env() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    unsafe {
+        std::env::remove_var(REPLAY_ENV);
+        std::env::set_var(RECORD_ENV, &path);
+    }
+    let cassette = Cassette::from_env().unwrap().unwrap();
+    assert!(!cassette.is_replay());
+    assert!(path.exists());
+
+    unsafe {
+        std::env::set_var(REPLAY_ENV, &path);
+    }
+    assert!(matches!(
+        Cassette::from_env(),
+        Err(CodebaseError::Cassette(_))
+    ));
+
+    unsafe {
+        std::env::remove_var(RECORD_ENV);
+    }
+    assert!(Cassette::from_env().unwrap().unwrap().is_replay());
+
+    unsafe {
+        std::env::remove_var(REPLAY_ENV);
+    }
+    assert!(Cassette::from_env().unwrap().is_none());
+    assert!(Cassette::replay(dir.path().join("missing.json")).is_err());
+}
@@ -0,0 +1,316 @@
+use codebase_cli::api::config::Config;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::fake::FakeTransport;
+use codebase_cli::commands::api::ApiArgs;
+use codebase_cli::commands::pr::PrCommand;
+use codebase_cli::commands::projects::{ProjectCommand, ProjectOutput};
+use codebase_cli::commands::repositories::{RepoCommand, RepoOutput};
+use codebase_cli::commands::tickets::{NewTicket, TicketCommand, TicketOutput};
+use codebase_cli::commands::{PageArgs, api, browse, pr, projects, repositories, status, tickets};
+use codebase_cli::git_context;
+use codebase_cli::mock_server::Store;
+
+const FIRST_PAGE: PageArgs = PageArgs {
+    all: false,
+    limit: None,
+};
+
+#[tokio::test]
+async fn test_project_commands_return_results() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+
+    let ProjectOutput::Projects(list) = projects::run(&client, ProjectCommand::List).await.unwrap()
+    else {
+        panic!("expected a project list");
+    };
+    assert_eq!(list.len(), 2);
+
+    let result = projects::run(
+        &client,
+        ProjectCommand::AssignUsers {
+            project: "widgets".to_string(),
+            user_ids: vec![1, 2],
+        },
+    )
+    .await
+    .unwrap();
+    match result {
+        ProjectOutput::Assigned { project, user_ids } => {
+            assert_eq!(project, "widgets");
+            assert_eq!(user_ids, [1, 2]);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_ticket_commands_resolve_names_and_return_results() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+
+    let created = tickets::run(
+        &client,
+        TicketCommand::Create(NewTicket {
+            project: "widgets".to_string(),
+            summary: Some("Coupon codes are case sensitive".to_string()),
+            ticket_type: Some("bug".to_string()),
+            priority: Some("high".to_string()),
+            assignee: Some("alice".to_string()),
+            ..NewTicket::default()
+        }),
+    )
+    .await
+    .unwrap();
+    let TicketOutput::Created(ticket) = created else {
+        panic!("unexpected result: {:?}", created);
+    };
+    assert_eq!(ticket.ticket_id, Some(4));
+    assert_eq!(ticket.assignee_id, Some(1));
+
+    let search = tickets::run(
+        &client,
+        TicketCommand::Search {
+            project: "widgets".to_string(),
+            query: "assignee:me status:open".to_string(),
+            pages: FIRST_PAGE,
+            names: false,
+        },
+    )
+    .await
+    .unwrap();
+    let TicketOutput::Tickets {
+        project, tickets, ..
+    } = search
+    else {
+        panic!("unexpected result: {:?}", search);
+    };
+    assert_eq!(project, "widgets");
+    let ids: Vec<_> = tickets.iter().filter_map(|t| t.ticket_id).collect();
+    assert_eq!(ids, [1, 4]);
+
+    let show = tickets::run(
+        &client,
+        TicketCommand::Show {
+            project: "widgets".to_string(),
+            ticket_id: 4,
+        },
+    )
+    .await
+    .unwrap();
+    let TicketOutput::Ticket { details, lookup } = show else {
+        panic!("unexpected result: {:?}", show);
+    };
+    assert_eq!(details.ticket.ticket_id, Some(4));
+    assert!(details.timeline.is_empty());
+    assert_eq!(
+        lookup.priority_name(details.ticket.priority_id.unwrap()),
+        Some("High")
+    );
+}
+
+#[tokio::test]
+async fn test_ticket_list_fills_the_lookup_when_asked_for_names() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+    let list = |names| TicketCommand::List {
+        project: "widgets".to_string(),
+        pages: FIRST_PAGE,
+        names,
+    };
+
+    let TicketOutput::Tickets { lookup, .. } = tickets::run(&client, list(false)).await.unwrap()
+    else {
+        panic!("expected a ticket list");
+    };
+    assert!(lookup.statuses.is_empty());
+    assert_eq!(fake.requests().len(), 1);
+
+    let output = tickets::run(&client, list(true)).await.unwrap();
+    let TicketOutput::Tickets { lookup, .. } = &output else {
+        panic!("expected a ticket list");
+    };
+    assert_eq!(lookup.status_name(1), Some("New"));
+    let json = serde_json::to_value(&output).unwrap();
+    assert_eq!(json["tickets"]["project"], "widgets");
+    assert_eq!(json["tickets"]["lookup"]["statuses"][0]["name"], "New");
+}
+
+#[tokio::test]
+async fn test_ticket_commands_fail_with_codebase_errors() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+    let create = |status: &str, summary: Option<&str>| {
+        TicketCommand::Create(NewTicket {
+            project: "widgets".to_string(),
+            summary: summary.map(str::to_string),
+            status: Some(status.to_string()),
+            ..NewTicket::default()
+        })
+    };
+
+    let err = tickets::run(&client, create("nonsense", Some("Hi")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CodebaseError::Resolve(_)), "{:?}", err);
+    assert_eq!(err.exit_code(), 2);
+
+    let err = tickets::run(&client, create("new", None))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CodebaseError::Input(_)), "{:?}", err);
+    assert_eq!(err.to_string(), "A ticket summary is required");
+}
+
+#[tokio::test]
+async fn test_numeric_ids_are_checked_whatever_else_is_given() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+    let create = |status: &str, priority: Option<&str>| {
+        TicketCommand::Create(NewTicket {
+            project: "widgets".to_string(),
+            summary: Some("Hi".to_string()),
+            status: Some(status.to_string()),
+            priority: priority.map(str::to_string),
+            ..NewTicket::default()
+        })
+    };
+
+    for priority in [None, Some("high")] {
+        let err = tickets::run(&client, create("999", priority))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CodebaseError::Resolve(_)), "{:?}", err);
+    }
+    assert_eq!(
+        fake.requests()
+            .iter()
+            .filter(|r| r.method == "POST")
+            .count(),
+        0
+    );
+
+    tickets::run(&client, create("1", None)).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pr_commands_run_as_repo_commands() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client();
+
+    let listed = pr::run(
+        &client,
+        PrCommand::List {
+            project: Some("widgets".to_string()),
+            repo: Some("storefront".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    let RepoOutput::MergeRequests(mrs) = listed else {
+        panic!("unexpected result: {:?}", listed);
+    };
+    assert_eq!(mrs.len(), 1);
+
+    let merged = pr::run(
+        &client,
+        PrCommand::Merge {
+            mr_id: 1,
+            project: Some("widgets".to_string()),
+            repo: Some("storefront".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(matches!(merged, RepoOutput::Merged { mr_id: 1 }));
+
+    let file = repositories::run(
+        &client,
+        RepoCommand::File {
+            project: "widgets".to_string(),
+            repo: "storefront".to_string(),
+            git_ref: "main".to_string(),
+            path: "README.md".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    let RepoOutput::File(content) = file else {
+        panic!("unexpected result: {:?}", file);
+    };
+    assert!(content.starts_with("# Storefront"));
+}
+
+#[tokio::test]
+async fn test_status_dashboard() {
+    let fake = FakeTransport::new(Store::sample());
+    let dashboard = status::run(&fake.client()).await.unwrap();
+
+    assert_eq!(dashboard.projects.len(), 2);
+    assert_eq!(dashboard.projects[0].permalink, "widgets");
+    assert_eq!(dashboard.projects[0].open_tickets, 2);
+    assert_eq!(dashboard.recent_activity.len(), 2);
+}
+
+#[tokio::test]
+async fn test_api_command_returns_the_response() {
+    let fake = FakeTransport::new(Store::sample());
+    let response = api::run(
+        &fake.client(),
+        ApiArgs {
+            method: "get".to_string(),
+            path: "/widgets/tickets".to_string(),
+            data: None,
+            fields: vec![("query".to_string(), "status:closed".to_string())],
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        response
+            .body
+            .contains("<ticket-id type=\"integer\">3</ticket-id>")
+    );
+    let json = response.json().unwrap();
+    assert_eq!(json[0]["ticket-id"], 3);
+    assert_eq!(
+        fake.requests()[0].path,
+        "/widgets/tickets?query=status%3Aclosed"
+    );
+}
+
+#[test]
+fn test_explicit_project_and_repo_skip_detection() {
+    let (project, repo) =
+        git_context::resolve_project_repo(Some("widgets".to_string()), Some("api".to_string()))
+            .unwrap();
+    assert_eq!(project, "widgets");
+    assert_eq!(repo, "api");
+}
+
+#[test]
+fn test_browse_urls() {
+    let config = Config {
+        api_username: "acme/alice".to_string(),
+        api_key: "key".to_string(),
+        base_url: None,
+    };
+    let url = |target: Option<&str>| {
+        browse::run(
+            &config,
+            Some("widgets".to_string()),
+            target.map(str::to_string),
+        )
+        .unwrap()
+    };
+    assert_eq!(url(None), "https://acme.codebasehq.com/projects/widgets");
+    assert_eq!(
+        url(Some("42")),
+        "https://acme.codebasehq.com/projects/widgets/tickets/42"
+    );
+    assert_eq!(
+        url(Some("storefront")),
+        "https://acme.codebasehq.com/projects/widgets/repositories/storefront"
+    );
+}
@@ -0,0 +1,89 @@
+use codebase_cli::compose::{Draft, NOTE_FIELDS, TICKET_FIELDS, edit_with, read_input};
+
+#[test]
+fn test_render_then_parse_round_trips() {
+    let mut draft = Draft::new([
+        ("summary", Some("Login fails".to_string())),
+        ("type", Some("bug".to_string())),
+        ("priority", None),
+    ]);
+    draft.body = "Steps:\n\n1. Open the app\n---\nnot front matter".to_string();
+
+    let text = draft.render(&["Help line"]);
+    assert!(text.starts_with("---\n# Help line\nsummary: Login fails\n"));
+
+    let parsed = Draft::parse(&text, TICKET_FIELDS).unwrap();
+    assert_eq!(parsed.field("summary").as_deref(), Some("Login fails"));
+    assert_eq!(parsed.field("type").as_deref(), Some("bug"));
+    assert_eq!(parsed.field("priority"), None);
+    assert_eq!(
+        parsed.body().as_deref(),
+        Some("Steps:\n\n1. Open the app\n---\nnot front matter")
+    );
+}
+
+#[test]
+fn test_parse_without_front_matter_is_all_body() {
+    let parsed = Draft::parse("Just a note\n\nwith paragraphs\n", NOTE_FIELDS).unwrap();
+    assert!(parsed.fields.is_empty());
+    assert_eq!(
+        parsed.body().as_deref(),
+        Some("Just a note\n\nwith paragraphs")
+    );
+}
+
+#[test]
+fn test_parse_values_may_contain_colons() {
+    let parsed = Draft::parse("---\nsubject: Re: login\n---\n", NOTE_FIELDS).unwrap();
+    assert_eq!(parsed.field("subject").as_deref(), Some("Re: login"));
+    assert_eq!(parsed.body(), None);
+}
+
+#[test]
+fn test_parse_rejects_unknown_fields_and_unclosed_front_matter() {
+    let err = Draft::parse("---\nmilestone: v2\n---\n", NOTE_FIELDS)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("milestone"), "{}", err);
+
+    assert!(Draft::parse("---\nstatus: Open\n", NOTE_FIELDS).is_err());
+    assert!(Draft::parse("---\nno colon here\n---\n", NOTE_FIELDS).is_err());
+}
+
+#[test]
+fn test_read_input_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("note.md");
+    std::fs::write(&path, "from a file").unwrap();
+
+    assert_eq!(read_input(path.to_str().unwrap()).unwrap(), "from a file");
+    assert!(read_input(dir.path().join("missing").to_str().unwrap()).is_err());
+}
+
+// GNU sed: `-i` takes no suffix argument.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_edit_runs_editor_with_arguments() {
+    let edited = edit_with("sed -i s/blank/filled/", "---\nstatus: blank\n---\n").unwrap();
+
+    assert_eq!(edited, "---\nstatus: filled\n---\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_aborts_when_unchanged_or_emptied() {
+    let err = edit_with("true", "---\nstatus: blank\n---\n").unwrap_err();
+    assert!(err.to_string().contains("not changed"), "{err}");
+
+    let err = edit_with("truncate -s 0", "---\nstatus: blank\n---\n").unwrap_err();
+    assert!(err.to_string().contains("empty"), "{err}");
+}
+
+// GNU stat: `-c` sets the output format.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_edit_file_is_private() {
+    let mode = edit_with(r#"mode() { stat -c %a "$1" > "$1"; }; mode"#, "draft").unwrap();
+
+    assert_eq!(mode.trim(), "600");
+}
@@ -0,0 +1,293 @@
+use codebase_cli::api::config::{
+    API_KEY_ENV, API_URL_ENV, Config, ConfigFile, Profile, USERNAME_ENV,
+};
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+// These tests repoint HOME and set credential variables, so they run serially.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn with_home<T>(config: Option<&str>, f: impl FnOnce(&TempDir) -> T) -> T {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let home = TempDir::new().unwrap();
+    if let Some(config) = config {
+        let dir = home.path().join(".config").join("cb");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.toml"), config).unwrap();
+    }
+    unsafe {
+        std::env::set_var("HOME", home.path());
+        std::env::remove_var(USERNAME_ENV);
+        std::env::remove_var(API_KEY_ENV);
+        std::env::remove_var(API_URL_ENV);
+    }
+    let result = f(&home);
+    unsafe {
+        std::env::remove_var(USERNAME_ENV);
+        std::env::remove_var(API_KEY_ENV);
+        std::env::remove_var(API_URL_ENV);
+    }
+    result
+}
+
+#[test]
+fn test_env_credentials_need_no_config_file() {
+    with_home(None, |_| {
+        unsafe {
+            std::env::set_var(USERNAME_ENV, "acme/ci");
+            std::env::set_var(API_KEY_ENV, "env-key");
+        }
+        let config = Config::load().unwrap();
+        assert_eq!(config.api_username, "acme/ci");
+        assert_eq!(config.api_key, "env-key");
+    });
+}
+
+#[test]
+fn test_env_key_overrides_file_key() {
+    let file = r#"
+api_username = "acme/jdoe"
+api_key = "file-key"
+"#;
+    with_home(Some(file), |_| {
+        unsafe { std::env::set_var(API_KEY_ENV, "env-key") };
+        let config = Config::load().unwrap();
+        assert_eq!(config.api_username, "acme/jdoe");
+        assert_eq!(config.api_key, "env-key");
+    });
+}
+
+#[test]
+fn test_missing_config_mentions_env_vars() {
+    with_home(None, |_| {
+        let err = Config::load().unwrap_err().to_string();
+        assert!(err.contains(USERNAME_ENV));
+        assert!(err.contains(API_KEY_ENV));
+    });
+}
+
+#[test]
+fn test_credential_command_supplies_key() {
+    let file = r#"
+[profiles.work]
+api_username = "acme/jdoe"
+credential_command = "printf '  from-command\n'"
+"#;
+    with_home(Some(file), |_| {
+        let config = Config::load_profile(Some("work")).unwrap();
+        assert_eq!(config.api_key, "from-command");
+    });
+}
+
+#[test]
+fn test_failing_credential_command_is_an_error() {
+    let profile = Profile {
+        api_username: "acme/jdoe".to_string(),
+        api_key: Some("unused".to_string()),
+        credential_command: Some("echo locked >&2; exit 3".to_string()),
+        base_url: None,
+    };
+
+    let err = profile.resolve_api_key().unwrap_err().to_string();
+    assert!(err.contains("locked"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_saved_config_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    with_home(None, |home| {
+        let path = home.path().join(".config").join("cb").join("config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        Config {
+            api_username: "acme/jdoe".to_string(),
+            api_key: "secret".to_string(),
+            base_url: None,
+        }
+        .save()
+        .unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    });
+}
+
+#[test]
+fn test_base_url_from_profile_and_env() {
+    let file = r#"
+api_username = "acme/jdoe"
+api_key = "key"
+
+[profiles.staging]
+api_username = "acme/jdoe"
+api_key = "staging-key"
+base_url = "http://localhost:4000"
+"#;
+    with_home(Some(file), |_| {
+        let config = Config::load_profile(Some("default")).unwrap();
+        assert_eq!(config.base_url, None);
+        assert_eq!(config.base_url(), "https://api3.codebasehq.com");
+
+        let config = Config::load_profile(Some("staging")).unwrap();
+        assert_eq!(config.base_url(), "http://localhost:4000");
+
+        unsafe { std::env::set_var(API_URL_ENV, "http://127.0.0.1:9999") };
+        let config = Config::load_profile(Some("staging")).unwrap();
+        assert_eq!(config.base_url(), "http://127.0.0.1:9999");
+
+        unsafe {
+            std::env::set_var(USERNAME_ENV, "acme/ci");
+            std::env::set_var(API_KEY_ENV, "env-key");
+        }
+        let config = Config::load().unwrap();
+        assert_eq!(config.base_url(), "http://127.0.0.1:9999");
+    });
+}
+
+#[test]
+fn test_login_keeps_profile_base_url() {
+    let file = r#"
+[profiles.staging]
+api_username = "acme/jdoe"
+api_key = "old-key"
+base_url = "http://localhost:4000"
+"#;
+    with_home(Some(file), |_| {
+        Config {
+            api_username: "acme/jdoe".to_string(),
+            api_key: "new-key".to_string(),
+            base_url: None,
+        }
+        .save_profile("staging")
+        .unwrap();
+
+        let profile = ConfigFile::load().unwrap().profile("staging").unwrap();
+        assert_eq!(profile.api_key.as_deref(), Some("new-key"));
+        assert_eq!(profile.base_url.as_deref(), Some("http://localhost:4000"));
+    });
+}
+
+#[test]
+fn test_explicit_profile_overrides_env_credentials() {
+    let file = r#"
+[profiles.work]
+api_username = "acme/jdoe"
+api_key = "work-key"
+"#;
+    with_home(Some(file), |_| {
+        unsafe {
+            std::env::set_var(USERNAME_ENV, "acme/ci");
+            std::env::set_var(API_KEY_ENV, "env-key");
+        }
+        let config = Config::load().unwrap();
+        assert_eq!(config.api_username, "acme/ci");
+
+        let config = Config::load_profile(Some("work")).unwrap();
+        assert_eq!(config.api_username, "acme/jdoe");
+        assert_eq!(config.api_key, "work-key");
+    });
+}
+
+#[test]
+fn test_save_keeps_comments() {
+    let file = r#"# Work and home accounts
+default_profile = "work"
+
+# The day job
+[profiles.work]
+api_username = "acme/jdoe" # shared with the team
+api_key = "old-key"
+"#;
+    with_home(Some(file), |home| {
+        Config {
+            api_username: "acme/jdoe".to_string(),
+            api_key: "new-key".to_string(),
+            base_url: None,
+        }
+        .save_profile("work")
+        .unwrap();
+
+        let path = home.path().join(".config").join("cb").join("config.toml");
+        let saved = std::fs::read_to_string(path).unwrap();
+        assert!(saved.contains("# Work and home accounts"), "{saved}");
+        assert!(saved.contains("# The day job"), "{saved}");
+        assert!(saved.contains("# shared with the team"), "{saved}");
+        assert!(saved.contains(r#"api_key = "new-key""#), "{saved}");
+        assert!(!saved.contains("old-key"), "{saved}");
+    });
+}
+
+#[test]
+fn test_save_keeps_unknown_keys() {
+    let file = r#"default_profile = "work"
+editor_theme = "dark" # added by hand
+
+[profiles.work]
+api_username = "acme/jdoe"
+api_key = "old-key"
+signature = "-- J"
+
+[plugins.jira]
+url = "https://jira.example.com"
+"#;
+    with_home(Some(file), |home| {
+        let mut config = ConfigFile::load().unwrap();
+        config.default_profile = None;
+        config.profiles.get_mut("work").unwrap().api_key = Some("new-key".to_string());
+        config.save().unwrap();
+
+        let path = home.path().join(".config").join("cb").join("config.toml");
+        let saved = std::fs::read_to_string(path).unwrap();
+        assert!(
+            saved.contains(r#"editor_theme = "dark" # added by hand"#),
+            "{saved}"
+        );
+        assert!(saved.contains(r#"signature = "-- J""#), "{saved}");
+        assert!(
+            saved.contains(r#"url = "https://jira.example.com""#),
+            "{saved}"
+        );
+        assert!(!saved.contains("default_profile"), "{saved}");
+        assert!(saved.contains(r#"api_key = "new-key""#), "{saved}");
+
+        let reloaded = ConfigFile::load().unwrap();
+        assert_eq!(reloaded.default_profile, None);
+        assert_eq!(
+            reloaded.profiles["work"].api_key.as_deref(),
+            Some("new-key")
+        );
+    });
+}
+
+#[test]
+fn test_replay_needs_no_key_but_reports_config_errors() {
+    with_home(None, |_| {
+        let config = Config::load_for_replay(None).unwrap();
+        assert_eq!(config.api_username, "replay/replay");
+    });
+
+    let file = r#"
+[profiles.work]
+api_username = "acme/jdoe"
+credential_command = "exit 1"
+"#;
+    with_home(Some(file), |_| {
+        let config = Config::load_for_replay(None).unwrap();
+        assert_eq!(config.api_username, "acme/jdoe");
+        assert_eq!(config.api_key, "");
+        let err = Config::load_for_replay(Some("nope")).unwrap_err();
+        assert!(
+            err.to_string().contains("No profile named 'nope'"),
+            "{}",
+            err
+        );
+    });
+
+    with_home(Some("api_username = "), |_| {
+        assert!(Config::load_for_replay(None).is_err());
+    });
+}
@@ -0,0 +1,180 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::projects::list_projects;
+use codebase_cli::api::tickets::{create_ticket, list_tickets};
+
+fn client_for(server: &mockito::Server) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_401_maps_to_unauthorized() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/projects")
+        .with_status(401)
+        .with_body("HTTP Basic: Access denied.")
+        .create_async()
+        .await;
+
+    let err = list_projects(&client_for(&server)).await.unwrap_err();
+
+    assert!(matches!(err, CodebaseError::Unauthorized { .. }));
+    assert_eq!(err.exit_code(), 3);
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(401));
+    assert!(err.to_string().contains("401"));
+}
+
+#[tokio::test]
+async fn test_404_maps_to_not_found() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/missing/tickets")
+        .with_status(404)
+        .with_body("Not found")
+        .create_async()
+        .await;
+
+    let err = list_tickets(&client_for(&server), "missing")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, CodebaseError::NotFound { .. }));
+    assert_eq!(err.exit_code(), 4);
+}
+
+#[tokio::test]
+async fn test_422_parses_validation_errors() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/proj/tickets")
+        .with_status(422)
+        .with_body(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <errors>
+                <error>Summary can't be blank</error>
+                <error>Ticket type is not included in the list</error>
+            </errors>"#,
+        )
+        .create_async()
+        .await;
+
+    let err = create_ticket(
+        &client_for(&server),
+        "proj",
+        "",
+        "nonsense",
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    match &err {
+        CodebaseError::Validation { errors, .. } => {
+            assert_eq!(
+                errors,
+                &vec![
+                    "Summary can't be blank".to_string(),
+                    "Ticket type is not included in the list".to_string(),
+                ]
+            );
+        }
+        other => panic!("expected Validation, got {:?}", other),
+    }
+    assert_eq!(err.exit_code(), 5);
+    assert!(err.to_string().contains("Summary can't be blank"));
+}
+
+#[tokio::test]
+async fn test_422_with_unstructured_body_keeps_raw_message() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/proj/tickets")
+        .with_status(422)
+        .with_body("Something went wrong")
+        .create_async()
+        .await;
+
+    let err = create_ticket(
+        &client_for(&server),
+        "proj",
+        "Summary",
+        "bug",
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    match err {
+        CodebaseError::Validation { errors, .. } => {
+            assert_eq!(errors, vec!["Something went wrong".to_string()]);
+        }
+        other => panic!("expected Validation, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_other_status_maps_to_api_error() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/projects")
+        .with_status(500)
+        .with_body("Internal Server Error")
+        .create_async()
+        .await;
+
+    let err = list_projects(&client_for(&server)).await.unwrap_err();
+
+    assert!(matches!(err, CodebaseError::Api { .. }));
+    assert_eq!(err.exit_code(), 1);
+    assert!(err.to_string().contains("500"));
+}
+
+#[tokio::test]
+async fn test_malformed_xml_maps_to_decode() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/projects")
+        .with_status(200)
+        .with_body("<projects><project><name>Unclosed")
+        .create_async()
+        .await;
+
+    let err = list_projects(&client_for(&server)).await.unwrap_err();
+
+    assert!(matches!(err, CodebaseError::Decode(_)));
+    assert_eq!(err.exit_code(), 8);
+}
+
+#[tokio::test]
+async fn test_connection_failure_maps_to_transport() {
+    // Nothing listens on port 9 (discard) on the loopback interface in CI.
+    let client = CodebaseClient::with_base_url(
+        "http://127.0.0.1:9".to_string(),
+        "a".to_string(),
+        "u".to_string(),
+        "k".to_string(),
+    );
+
+    let err = list_projects(&client).await.unwrap_err();
+
+    assert!(matches!(err, CodebaseError::Transport(_)));
+    assert_eq!(err.exit_code(), 7);
+}
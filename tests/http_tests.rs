@@ -0,0 +1,158 @@
+use std::io::Read;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::config::ConfigFile;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::http::HttpSettings;
+use codebase_cli::api::retry::RetryPolicy;
+use reqwest::Proxy;
+
+/// A self-signed CA certificate.
+const CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBgTCCASegAwIBAgIUOBZ1keAq3fQGbxmOQZCGEq6BWygwCgYIKoZIzj0EAwIw
+FTETMBEGA1UEAwwKY2IgdGVzdCBDQTAgFw0yNjEwMTcwOTMyNDBaGA8yMTI2MDky
+MzA5MzI0MFowFTETMBEGA1UEAwwKY2IgdGVzdCBDQTBZMBMGByqGSM49AgEGCCqG
+SM49AwEHA0IABID6WpPtY4EHLXceMo9DphTwFrz0mumWI4FRaI/VtLOMxiy9gzii
+Chf5AmmxIvBs8xW3omEI6hiQwcvXIt+++sWjUzBRMB0GA1UdDgQWBBROrvmsvVTc
+SW/TsDnmJbHDtLjNNzAfBgNVHSMEGDAWgBROrvmsvVTcSW/TsDnmJbHDtLjNNzAP
+BgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCIDRe38gWygOmgzu86MlT
+h98GibBlQG8tkmi7djYKFJfoAiEA6l2Cg0ovQr/kCNFS2A8EfGsfa5ZufLJh6Fkk
+bBNlf0A=
+-----END CERTIFICATE-----
+";
+
+fn builder() -> codebase_cli::api::client::CodebaseClientBuilder {
+    CodebaseClient::builder(
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_read_timeout_stops_a_stalled_request() {
+    // Accepts the connection and reads the request, but never answers.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0; 1024];
+        let _ = stream.read(&mut buf);
+        std::thread::sleep(Duration::from_secs(5));
+    });
+    let client = builder()
+        .base_url(url)
+        .read_timeout(Some(Duration::from_millis(200)))
+        .build()
+        .unwrap()
+        .with_retry_policy(RetryPolicy::none());
+
+    let started = std::time::Instant::now();
+    let err = client.get("/projects").await.unwrap_err();
+
+    match err {
+        CodebaseError::Transport(e) => assert!(e.is_timeout(), "{:?}", e),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_requests_go_through_the_proxy() {
+    let mut proxy = mockito::Server::new_async().await;
+    let mock = proxy
+        .mock("GET", "/projects")
+        .match_header("host", "api.codebase.invalid")
+        .with_status(200)
+        .with_body("<projects type=\"array\"></projects>")
+        .create_async()
+        .await;
+    let client = builder()
+        .base_url("http://api.codebase.invalid".to_string())
+        .proxy(Proxy::all(proxy.url()).unwrap())
+        .build()
+        .unwrap();
+
+    let body = client.get("/projects").await.unwrap();
+
+    assert_eq!(body, "<projects type=\"array\"></projects>");
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_http_settings_from_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = dir.path().join("ca.pem");
+    std::fs::write(&ca, CA_PEM).unwrap();
+    let file: ConfigFile = toml::from_str(&format!(
+        r#"
+        [http]
+        connect_timeout_secs = 5
+        timeout_secs = 0
+        proxy = "http://proxy.example:3128"
+        ca_certs = [{:?}]
+        "#,
+        ca.display().to_string()
+    ))
+    .unwrap();
+
+    assert_eq!(file.http.connect_timeout_secs, Some(5));
+    assert_eq!(file.http.timeout_secs, Some(0));
+    file.http.apply(builder()).unwrap().build().unwrap();
+
+    let empty: ConfigFile = toml::from_str("").unwrap();
+    assert!(empty.http.is_empty());
+}
+
+#[test]
+fn test_invalid_http_settings_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.pem");
+    let garbage = dir.path().join("garbage.pem");
+    std::fs::write(&garbage, "not a certificate").unwrap();
+
+    let settings = HttpSettings {
+        ca_certs: vec![missing],
+        ..Default::default()
+    };
+    let err = settings.apply(builder()).unwrap_err().to_string();
+    assert!(err.starts_with("Failed to read CA certificate"), "{}", err);
+
+    let settings = HttpSettings {
+        ca_certs: vec![garbage],
+        ..Default::default()
+    };
+    assert!(settings.apply(builder()).is_err());
+
+    let settings = HttpSettings {
+        proxy: Some("::not a url::".to_string()),
+        ..Default::default()
+    };
+    let err = settings.apply(builder()).unwrap_err().to_string();
+    assert!(err.starts_with("Invalid proxy URL"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_builder_sets_base_url_and_client_options() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/projects")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let client = builder()
+        .base_url(format!("{}/", server.url()))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        client.url("/projects"),
+        format!("{}/projects", server.url())
+    );
+    client.get("/projects").await.unwrap_err();
+    mock.assert_async().await;
+}
@@ -0,0 +1,100 @@
+use codebase_cli::api::lookup::ProjectLookup;
+use codebase_cli::api::models::*;
+use codebase_cli::output::{self, JqFilter, OutputFormat, OutputOptions, TicketRow};
+use serde_json::json;
+
+fn tickets() -> Vec<Ticket> {
+    quick_xml::de::from_str::<Tickets>(
+        r#"<tickets>
+            <ticket>
+                <ticket-id>1</ticket-id>
+                <summary>Broken login</summary>
+                <ticket-type>bug</ticket-type>
+                <assignee>jdoe</assignee>
+            </ticket>
+            <ticket>
+                <ticket-id>2</ticket-id>
+                <summary>Add export</summary>
+                <ticket-type>enhancement</ticket-type>
+            </ticket>
+        </tickets>"#,
+    )
+    .unwrap()
+    .tickets
+}
+
+fn list_output(filter: &str, format: OutputFormat) -> String {
+    let out = OutputOptions {
+        format,
+        jq: Some(JqFilter::parse(filter).unwrap()),
+        ..Default::default()
+    };
+    assert!(!out.uses_columns());
+    let tickets = tickets();
+    let lookup = ProjectLookup::default();
+    let mut buf = Vec::new();
+    output::write_list_with(
+        &out,
+        &tickets,
+        |ticket| TicketRow {
+            ticket,
+            lookup: &lookup,
+        },
+        &mut buf,
+    )
+    .unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn test_filter_runs_over_the_whole_list() {
+    assert_eq!(
+        list_output(".[].summary", OutputFormat::Table),
+        "Broken login\nAdd export\n"
+    );
+}
+
+#[test]
+fn test_non_string_results_are_pretty_json() {
+    assert_eq!(
+        list_output(
+            r#"map(select(."ticket-type" == "bug")) | .[0] | {id: ."ticket-id", assignee}"#,
+            OutputFormat::Json
+        ),
+        "{\n  \"id\": 1,\n  \"assignee\": \"jdoe\"\n}\n"
+    );
+}
+
+#[test]
+fn test_ndjson_makes_results_compact() {
+    assert_eq!(
+        list_output(".[] | {id: .\"ticket-id\"}", OutputFormat::Ndjson),
+        "{\"id\":1}\n{\"id\":2}\n"
+    );
+}
+
+#[test]
+fn test_standard_library_is_available() {
+    let filter = JqFilter::parse("map(.n) | add, length, (sort_by(-.) | first)").unwrap();
+    let results = filter.run(&json!([{"n": 1}, {"n": 5}, {"n": 3}])).unwrap();
+    assert_eq!(results, vec![json!(9), json!(3), json!(5)]);
+}
+
+#[test]
+fn test_invalid_filters_are_rejected() {
+    let err = JqFilter::parse(".[] |").unwrap_err().to_string();
+    assert!(err.starts_with("Invalid jq filter: expected"), "{}", err);
+
+    let err = JqFilter::parse("no_such_function").unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "Invalid jq filter: undefined filter `no_such_function`"
+    );
+}
+
+#[test]
+fn test_runtime_errors_are_reported() {
+    let filter = JqFilter::parse(".summary + 1").unwrap();
+    let err = filter.run(&json!({"summary": "x"})).unwrap_err();
+    assert!(err.to_string().starts_with("jq: error:"), "{}", err);
+}
@@ -0,0 +1,334 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::models::NoteChanges;
+use codebase_cli::api::retry::RetryPolicy;
+use codebase_cli::api::throttle::RateLimiter;
+use codebase_cli::api::{activity, milestones, projects, repositories, tickets};
+use codebase_cli::mock_server::{MockServer, Store};
+
+/// Start a server on a free port and return a client for it, signed in as
+/// `acme/alice`.
+async fn serve(store: Store) -> CodebaseClient {
+    let server = MockServer::bind("127.0.0.1:0".parse().unwrap(), store)
+        .await
+        .unwrap();
+    let url = server.url();
+    tokio::spawn(server.run());
+    CodebaseClient::with_base_url(
+        url,
+        "acme".to_string(),
+        "acme/alice".to_string(),
+        "any-key".to_string(),
+    )
+    .with_retry_policy(RetryPolicy::none())
+    .with_rate_limiter(RateLimiter::unlimited())
+}
+
+#[tokio::test]
+async fn test_serves_sample_projects() {
+    let client = serve(Store::sample()).await;
+
+    let list = projects::list_projects(&client).await.unwrap();
+    assert_eq!(list.len(), 2);
+    let widgets = projects::show_project(&client, "widgets").await.unwrap();
+    assert_eq!(widgets.name.as_deref(), Some("Widgets"));
+    assert_eq!(widgets.total_tickets, Some(3));
+    assert_eq!(widgets.open_tickets, Some(2));
+    assert_eq!(widgets.closed_tickets, Some(1));
+
+    let groups = projects::list_project_groups(&client).await.unwrap();
+    assert_eq!(groups[0].label.as_deref(), Some("Products"));
+    let users = projects::list_project_users(&client, "gadgets")
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].username.as_deref(), Some("alice"));
+    let statuses = tickets::list_statuses(&client, "widgets").await.unwrap();
+    assert_eq!(statuses.len(), 4);
+}
+
+#[tokio::test]
+async fn test_ticket_workflow() {
+    let client = serve(Store::sample()).await;
+
+    let ticket = tickets::create_ticket(
+        &client,
+        "widgets",
+        "Cart total & tax <wrong>",
+        "Bug",
+        None,
+        None,
+        Some("Tax is added twice."),
+        Some(1),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(ticket.ticket_id, Some(4));
+    assert_eq!(ticket.summary.as_deref(), Some("Cart total & tax <wrong>"));
+    assert_eq!(ticket.assignee.as_deref(), Some("alice"));
+    assert_eq!(ticket.reporter_id, Some(1));
+    assert_eq!(ticket.priority_id, Some(3));
+    assert_eq!(ticket.status_id, Some(1));
+
+    let mine = tickets::search_tickets(&client, "widgets", "assignee:me status:open")
+        .await
+        .unwrap();
+    let ids: Vec<_> = mine.iter().filter_map(|t| t.ticket_id).collect();
+    assert_eq!(ids, [1, 4]);
+
+    let changes = NoteChanges {
+        status_id: Some(3),
+        priority_id: None,
+        category_id: None,
+        assignee_id: Some(2),
+        milestone_id: None,
+        subject: None,
+    };
+    let note = tickets::create_ticket_note(
+        &client,
+        "widgets",
+        4,
+        Some("Fixed in checkout refactor"),
+        Some(&changes),
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(note.content.as_deref(), Some("Fixed in checkout refactor"));
+    assert_eq!(note.changes.and_then(|c| c.status_id), Some(3));
+
+    let ticket = tickets::show_ticket(&client, "widgets", 4).await.unwrap();
+    assert_eq!(ticket.status_id, Some(3));
+    assert_eq!(ticket.assignee.as_deref(), Some("bob"));
+    let closed = tickets::search_tickets(&client, "widgets", "status:closed")
+        .await
+        .unwrap();
+    assert_eq!(closed.len(), 2);
+    let notes = tickets::list_ticket_notes(&client, "widgets", 4)
+        .await
+        .unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(
+        notes[0].updates.as_deref(),
+        Some(r#"{"status_id":[1,3],"assignee_id":[1,2]}"#)
+    );
+
+    tickets::set_watchers(&client, "widgets", 4, &[1, 2])
+        .await
+        .unwrap();
+    let watchers = tickets::list_watchers(&client, "widgets", 4).await.unwrap();
+    assert_eq!(watchers.len(), 2);
+    assert_eq!(watchers[1].watcher, Some(2));
+    let xml = client.get("/widgets/tickets/4/watchers").await.unwrap();
+    assert!(
+        xml.contains(r#"<watchers type="array"><watcher type="integer">1</watcher>"#),
+        "{}",
+        xml
+    );
+
+    let events = activity::project_activity(&client, "widgets", false, None, None)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].number, Some(4));
+    assert_eq!(events[0].event_type.as_deref(), Some("ticketing_note"));
+    assert_eq!(events[1].event_type.as_deref(), Some("ticketing_ticket"));
+}
+
+#[tokio::test]
+async fn test_pages_end_with_an_empty_page() {
+    let client = serve(Store::sample()).await;
+    for n in 0..25 {
+        tickets::create_ticket(
+            &client,
+            "widgets",
+            &format!("Ticket {}", n),
+            "Task",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    let first_page = tickets::list_tickets(&client, "widgets").await.unwrap();
+    assert_eq!(first_page.len(), 20);
+    let all = tickets::paginate_tickets(&client, "widgets")
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 28);
+    let feed = activity::paginate_account_activity(&client, false, None)
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(feed.len(), 27);
+}
+
+#[tokio::test]
+async fn test_projects_and_milestones_can_be_created_and_updated() {
+    let client = serve(Store::default()).await;
+
+    let project = projects::create_project(&client, "Mobile App")
+        .await
+        .unwrap();
+    assert_eq!(project.permalink.as_deref(), Some("mobile-app"));
+    assert_eq!(project.account_name.as_deref(), Some("acme"));
+    let project = projects::update_project(&client, "mobile-app", None, Some("archived"))
+        .await
+        .unwrap();
+    assert_eq!(project.status.as_deref(), Some("archived"));
+
+    let milestone = milestones::create_milestone(
+        &client,
+        "mobile-app",
+        "Beta",
+        None,
+        None,
+        Some("2026-12-01"),
+        Some(1),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(milestone.id, Some(1));
+    assert_eq!(milestone.status.as_deref(), Some("active"));
+    milestones::update_milestone(
+        &client,
+        "mobile-app",
+        1,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some("completed"),
+    )
+    .await
+    .unwrap();
+    let list = milestones::list_milestones(&client, "mobile-app")
+        .await
+        .unwrap();
+    assert_eq!(list[0].status.as_deref(), Some("completed"));
+    assert_eq!(list[0].deadline.as_deref(), Some("2026-12-01"));
+
+    projects::delete_project(&client, "mobile-app")
+        .await
+        .unwrap();
+    assert!(projects::list_projects(&client).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_repository_endpoints() {
+    let client = serve(Store::sample()).await;
+
+    let commits = repositories::list_commits(&client, "widgets", "storefront", "main")
+        .await
+        .unwrap();
+    assert_eq!(commits.len(), 2);
+    let readme = repositories::get_file(&client, "widgets", "storefront", "main", "README.md")
+        .await
+        .unwrap();
+    assert!(readme.starts_with("# Storefront"));
+    let branches = repositories::list_branches(&client, "widgets", "storefront")
+        .await
+        .unwrap();
+    assert_eq!(branches.len(), 2);
+
+    let mr = repositories::create_merge_request(
+        &client,
+        "widgets",
+        "storefront",
+        "gift-wrap",
+        "main",
+        "Gift wrapping",
+    )
+    .await
+    .unwrap();
+    assert_eq!(mr.id, Some(2));
+    repositories::merge_merge_request(&client, "widgets", "storefront", 2)
+        .await
+        .unwrap();
+    let mr = repositories::show_merge_request(&client, "widgets", "storefront", 2)
+        .await
+        .unwrap();
+    assert_eq!(mr.status.as_deref(), Some("merged"));
+
+    let repo = repositories::create_repository(&client, "widgets", "Mobile API", "git")
+        .await
+        .unwrap();
+    assert_eq!(repo.permalink.as_deref(), Some("mobile-api"));
+    let hook = repositories::create_hook(
+        &client,
+        "widgets",
+        "mobile-api",
+        "https://ci.example.com/hook",
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(hook.id, Some(1));
+    repositories::delete_repository(&client, "widgets", "mobile-api")
+        .await
+        .unwrap();
+    let repos = repositories::list_repositories(&client, "widgets")
+        .await
+        .unwrap();
+    assert_eq!(repos.len(), 1);
+}
+
+#[tokio::test]
+async fn test_errors_match_the_api() {
+    let client = serve(Store::sample()).await;
+
+    let err = projects::show_project(&client, "nope").await.unwrap_err();
+    assert!(matches!(err, CodebaseError::NotFound { .. }), "{:?}", err);
+    let err = tickets::create_ticket(
+        &client, "widgets", "  ", "Bug", None, None, None, None, None, None, None,
+    )
+    .await
+    .unwrap_err();
+    match err {
+        CodebaseError::Validation { errors, .. } => {
+            assert_eq!(errors, ["Summary can't be blank"])
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let unauthenticated = reqwest::get(format!("{}/projects", client.base_url))
+        .await
+        .unwrap();
+    assert_eq!(unauthenticated.status(), 401);
+}
+
+#[tokio::test]
+async fn test_yaml_fixture_gets_default_ticket_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fixture.yaml");
+    std::fs::write(
+        &path,
+        "projects:\n  - name: Docs\n    permalink: docs\n    tickets:\n      - ticket-id: 7\n        summary: Typo\n",
+    )
+    .unwrap();
+    let client = serve(Store::load(&path).unwrap()).await;
+
+    let ticket = tickets::show_ticket(&client, "docs", 7).await.unwrap();
+    assert_eq!(ticket.summary.as_deref(), Some("Typo"));
+    let priorities = tickets::list_priorities(&client, "docs").await.unwrap();
+    assert_eq!(priorities.len(), 4);
+
+    std::fs::write(&path, "projects: [").unwrap();
+    let err = Store::load(&path).unwrap_err().to_string();
+    assert!(err.starts_with("Invalid fixture"), "{}", err);
+}
@@ -26,6 +26,7 @@ fn make_config(api_username: &str) -> Config {
     Config {
         api_username: api_username.to_string(),
         api_key: "test-api-key".to_string(),
+        base_url: None,
     }
 }
 
@@ -1,5 +1,13 @@
+use codebase_cli::api::lookup::ProjectLookup;
 use codebase_cli::api::models::*;
-use codebase_cli::output;
+use codebase_cli::output::{self, OutputFormat, OutputOptions, TicketRow};
+
+fn json_opts() -> OutputOptions {
+    OutputOptions {
+        format: OutputFormat::Json,
+        ..Default::default()
+    }
+}
 
 // ── JSON serialization tests ──
 
@@ -518,7 +526,7 @@ fn test_print_output_json_mode_does_not_panic() {
         closed_tickets: None,
     };
 
-    let result = output::print_output(true, &project, || {});
+    let result = output::print_output(&json_opts(), &project, || {});
     assert!(result.is_ok());
 }
 
@@ -541,7 +549,7 @@ fn test_print_output_human_mode_does_not_panic() {
     };
 
     let mut called = false;
-    let result = output::print_output(false, &project, || {
+    let result = output::print_output(&OutputOptions::default(), &project, || {
         called = true;
     });
     assert!(result.is_ok());
@@ -566,17 +574,147 @@ fn test_print_list_json_mode_does_not_panic() {
         tags: None,
     }];
 
-    let result = output::print_list(true, &tickets, |_| {});
+    let lookup = ProjectLookup::default();
+    let result = output::print_list_with(&json_opts(), &tickets, |ticket| TicketRow {
+        ticket,
+        lookup: &lookup,
+    });
     assert!(result.is_ok());
 }
 
 #[test]
-fn test_print_list_human_mode_does_not_panic() {
+fn test_print_list_table_mode_does_not_panic() {
     let tickets: Vec<Ticket> = vec![];
-    let mut called = false;
-    let result = output::print_list(false, &tickets, |_| {
-        called = true;
+    let lookup = ProjectLookup::default();
+    let result = output::print_list_with(&OutputOptions::default(), &tickets, |ticket| TicketRow {
+        ticket,
+        lookup: &lookup,
     });
     assert!(result.is_ok());
-    assert!(called);
+}
+
+#[test]
+fn test_print_list_rejects_unknown_column() {
+    let out = OutputOptions {
+        format: OutputFormat::Table,
+        columns: Some(vec!["nope".to_string()]),
+        ..Default::default()
+    };
+    let err = output::print_list::<Branch>(&out, &[]).unwrap_err();
+    assert!(err.to_string().contains("Available columns: name"));
+}
+
+// ── Data formats ──
+
+fn formatted<T: serde::Serialize + output::TableRow>(
+    format: OutputFormat,
+    columns: Option<&[&str]>,
+    data: &[T],
+) -> String {
+    let out = OutputOptions {
+        format,
+        columns: columns.map(|c| c.iter().map(|s| s.to_string()).collect()),
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    output::write_list_with(&out, data, |item| item, &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+fn sample_commits() -> Vec<Commit> {
+    quick_xml::de::from_str::<Commits>(
+        r#"<commits>
+            <commit>
+                <ref>abcdef1234567</ref>
+                <message>Fix "quoted", with comma</message>
+                <author-name>Jane Doe</author-name>
+            </commit>
+            <commit>
+                <ref>1234567abcdef</ref>
+                <message>Second
+with body</message>
+            </commit>
+        </commits>"#,
+    )
+    .unwrap()
+    .commits
+}
+
+#[test]
+fn test_ndjson_writes_one_object_per_line() {
+    let text = formatted(OutputFormat::Ndjson, None, &sample_commits());
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(first["ref"], "abcdef1234567");
+    assert_eq!(first["author-name"], "Jane Doe");
+}
+
+#[test]
+fn test_csv_uses_columns_and_quotes_fields() {
+    let text = formatted(
+        OutputFormat::Csv,
+        Some(&["sha", "author", "message"]),
+        &sample_commits(),
+    );
+    assert_eq!(
+        text,
+        "sha,author,message\n\
+         abcdef1234567,Jane Doe,\"Fix \"\"quoted\"\", with comma\"\n\
+         1234567abcdef,,\"Second\nwith body\"\n"
+    );
+}
+
+#[test]
+fn test_tsv_uses_tabs() {
+    let text = formatted(
+        OutputFormat::Tsv,
+        Some(&["sha", "author"]),
+        &sample_commits(),
+    );
+    assert_eq!(
+        text,
+        "sha\tauthor\nabcdef1234567\tJane Doe\n1234567abcdef\t\n"
+    );
+}
+
+#[test]
+fn test_csv_empty_list_still_has_header() {
+    let text = formatted::<Commit>(OutputFormat::Csv, None, &[]);
+    assert_eq!(text, "sha,author,date,message\n");
+}
+
+#[test]
+fn test_yaml_list() {
+    let text = formatted(OutputFormat::Yaml, None, &sample_commits());
+    let parsed: Vec<serde_json::Value> = serde_yaml::from_str(&text).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[1]["ref"], "1234567abcdef");
+}
+
+#[test]
+fn test_write_data_csv_flattens_single_object() {
+    #[derive(serde::Serialize)]
+    struct Detail {
+        id: i64,
+        name: Option<String>,
+        tags: Vec<&'static str>,
+    }
+    let detail = Detail {
+        id: 3,
+        name: None,
+        tags: vec!["a", "b"],
+    };
+    let mut buf = Vec::new();
+    output::write_data(OutputFormat::Csv, &detail, &mut buf).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "id,name,tags\n3,,\"[\"\"a\"\",\"\"b\"\"]\"\n"
+    );
+}
+
+#[test]
+fn test_write_data_rejects_table() {
+    let mut buf = Vec::new();
+    assert!(output::write_data(OutputFormat::Table, &1, &mut buf).is_err());
 }
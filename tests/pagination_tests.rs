@@ -0,0 +1,212 @@
+use codebase_cli::api::activity::paginate_account_activity;
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::repositories::paginate_commits;
+use codebase_cli::api::tickets::{paginate_search_tickets, paginate_tickets};
+use futures::TryStreamExt;
+
+fn client_for(server: &mockito::Server) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+}
+
+fn tickets_page(ids: &[i64]) -> String {
+    let tickets: String = ids
+        .iter()
+        .map(|id| {
+            format!(
+                "<ticket><ticket-id>{}</ticket-id><summary>Ticket {}</summary></ticket>",
+                id, id
+            )
+        })
+        .collect();
+    format!("<tickets type=\"array\">{}</tickets>", tickets)
+}
+
+#[tokio::test]
+async fn test_collect_all_follows_pages_until_empty() {
+    let mut server = mockito::Server::new_async().await;
+
+    let page1 = server
+        .mock("GET", "/proj/tickets")
+        .with_status(200)
+        .with_body(tickets_page(&[1, 2]))
+        .create_async()
+        .await;
+    let page2 = server
+        .mock("GET", "/proj/tickets?page=2")
+        .with_status(200)
+        .with_body(tickets_page(&[3, 4]))
+        .create_async()
+        .await;
+    let page3 = server
+        .mock("GET", "/proj/tickets?page=3")
+        .with_status(200)
+        .with_body(tickets_page(&[]))
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let tickets = paginate_tickets(&client, "proj")
+        .collect_all()
+        .await
+        .expect("pagination should succeed");
+
+    let ids: Vec<i64> = tickets.iter().filter_map(|t| t.ticket_id).collect();
+    assert_eq!(ids, vec![1, 2, 3, 4]);
+
+    page1.assert_async().await;
+    page2.assert_async().await;
+    page3.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_limit_stops_fetching_once_reached() {
+    let mut server = mockito::Server::new_async().await;
+
+    let page1 = server
+        .mock("GET", "/proj/tickets")
+        .with_status(200)
+        .with_body(tickets_page(&[1, 2]))
+        .create_async()
+        .await;
+    let page2 = server
+        .mock("GET", "/proj/tickets?page=2")
+        .with_status(200)
+        .with_body(tickets_page(&[3, 4]))
+        .create_async()
+        .await;
+    let page3 = server
+        .mock("GET", "/proj/tickets?page=3")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let tickets = paginate_tickets(&client, "proj")
+        .limit(3)
+        .collect_all()
+        .await
+        .expect("pagination should succeed");
+
+    let ids: Vec<i64> = tickets.iter().filter_map(|t| t.ticket_id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    page1.assert_async().await;
+    page2.assert_async().await;
+    page3.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_next_page_returns_single_page() {
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("GET", "/proj/tickets?query=status%3Aopen&page=2")
+        .with_status(200)
+        .with_body(tickets_page(&[21]))
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let mut pager = paginate_search_tickets(&client, "proj", "status:open").start_page(2);
+    let page = pager
+        .next_page()
+        .await
+        .expect("request should succeed")
+        .expect("page should not be empty");
+
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].ticket_id, Some(21));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_into_stream_yields_items_across_pages() {
+    let mut server = mockito::Server::new_async().await;
+
+    server
+        .mock("GET", "/proj/repo/commits/main")
+        .with_status(200)
+        .with_body(
+            "<commits><commit><ref>aaa</ref></commit><commit><ref>bbb</ref></commit></commits>",
+        )
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/proj/repo/commits/main?page=2")
+        .with_status(200)
+        .with_body("<commits><commit><ref>ccc</ref></commit></commits>")
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/proj/repo/commits/main?page=3")
+        .with_status(200)
+        .with_body("<commits></commits>")
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let refs: Vec<String> = paginate_commits(&client, "proj", "repo", "main", None)
+        .into_stream()
+        .map_ok(|c| c.commit_ref.unwrap_or_default())
+        .try_collect()
+        .await
+        .expect("stream should succeed");
+
+    assert_eq!(refs, vec!["aaa", "bbb", "ccc"]);
+}
+
+#[tokio::test]
+async fn test_activity_pagination_keeps_existing_query() {
+    let mut server = mockito::Server::new_async().await;
+
+    server
+        .mock("GET", "/activity?raw=true")
+        .with_status(200)
+        .with_body("<events><event><title>First</title></event></events>")
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/activity?raw=true&page=2")
+        .with_status(200)
+        .with_body("<events></events>")
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let events = paginate_account_activity(&client, true, None)
+        .collect_all()
+        .await
+        .expect("pagination should succeed");
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].title, Some("First".to_string()));
+}
+
+#[tokio::test]
+async fn test_pagination_propagates_errors() {
+    let mut server = mockito::Server::new_async().await;
+
+    server
+        .mock("GET", "/proj/tickets")
+        .with_status(200)
+        .with_body(tickets_page(&[1]))
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/proj/tickets?page=2")
+        .with_status(500)
+        .with_body("boom")
+        .create_async()
+        .await;
+
+    let client = client_for(&server);
+    let result = paginate_tickets(&client, "proj").collect_all().await;
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("500"));
+}
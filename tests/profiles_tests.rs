@@ -0,0 +1,142 @@
+use codebase_cli::api::config::{ConfigFile, DEFAULT_PROFILE, PROFILE_ENV, Profile};
+use std::sync::Mutex;
+
+// Selection reads CB_PROFILE, so tests touching the environment run serially.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn parse(toml: &str) -> ConfigFile {
+    toml::from_str(toml).expect("config should parse")
+}
+
+const MULTI: &str = r#"
+default_profile = "work"
+
+[profiles.work]
+api_username = "acme/jdoe"
+api_key = "work-key"
+
+[profiles.personal]
+api_username = "jdoe-home/jdoe"
+api_key = "home-key"
+"#;
+
+#[test]
+fn test_legacy_config_is_default_profile() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(
+        r#"
+api_username = "acme/jdoe"
+api_key = "legacy-key"
+"#,
+    );
+
+    assert_eq!(file.profile_names(), vec![DEFAULT_PROFILE.to_string()]);
+    let (name, profile) = file.select_profile(None).unwrap();
+    assert_eq!(name, DEFAULT_PROFILE);
+    assert_eq!(profile.api_key.as_deref(), Some("legacy-key"));
+}
+
+#[test]
+fn test_explicit_profile_wins() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(MULTI);
+
+    let (name, profile) = file.select_profile(Some("personal")).unwrap();
+    assert_eq!(name, "personal");
+    assert_eq!(profile.api_username, "jdoe-home/jdoe");
+}
+
+#[test]
+fn test_default_profile_used_when_nothing_requested() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(MULTI);
+
+    let (name, profile) = file.select_profile(None).unwrap();
+    assert_eq!(name, "work");
+    assert_eq!(profile.api_key.as_deref(), Some("work-key"));
+}
+
+#[test]
+fn test_env_var_selects_profile() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(MULTI);
+
+    unsafe { std::env::set_var(PROFILE_ENV, "personal") };
+    let selected = file.select_profile(None);
+    unsafe { std::env::remove_var(PROFILE_ENV) };
+
+    assert_eq!(selected.unwrap().0, "personal");
+}
+
+#[test]
+fn test_unknown_profile_lists_available() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(MULTI);
+
+    let err = file.select_profile(Some("nope")).unwrap_err().to_string();
+    assert!(err.contains("nope"));
+    assert!(err.contains("personal"));
+    assert!(err.contains("work"));
+}
+
+#[test]
+fn test_ambiguous_profiles_without_default_is_an_error() {
+    let _guard = ENV_LOCK.lock().unwrap();
+    let file = parse(
+        r#"
+[profiles.a]
+api_username = "a/u"
+api_key = "k"
+
+[profiles.b]
+api_username = "b/u"
+api_key = "k"
+"#,
+    );
+
+    assert!(file.select_profile(None).is_err());
+}
+
+#[test]
+fn test_profile_for_account_matches_username_prefix() {
+    let file = parse(MULTI);
+
+    let (name, _) = file.profile_for_account("jdoe-home").unwrap();
+    assert_eq!(name, "personal");
+    let (name, _) = file.profile_for_account("ACME").unwrap();
+    assert_eq!(name, "work");
+    assert!(file.profile_for_account("other").is_none());
+}
+
+#[test]
+fn test_set_profile_keeps_existing_entries() {
+    let mut file = parse(MULTI);
+    file.set_profile(
+        "ci",
+        Profile {
+            api_username: "acme/bot".to_string(),
+            api_key: Some("ci-key".to_string()),
+            credential_command: None,
+            base_url: None,
+        },
+    );
+    file.set_profile(
+        DEFAULT_PROFILE,
+        Profile {
+            api_username: "acme/jdoe".to_string(),
+            api_key: Some("top-level".to_string()),
+            credential_command: None,
+            base_url: None,
+        },
+    );
+
+    let toml = toml::to_string_pretty(&file).unwrap();
+    let reparsed = parse(&toml);
+    assert_eq!(reparsed.profiles.len(), 3);
+    assert_eq!(reparsed.api_key.as_deref(), Some("top-level"));
+    assert_eq!(
+        reparsed.profile("ci").unwrap().api_key.as_deref(),
+        Some("ci-key")
+    );
+    assert_eq!(reparsed.default_profile.as_deref(), Some("work"));
+}
@@ -0,0 +1,178 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::raw::{fields_to_xml, parse_field, request, with_query, xml_to_json};
+use reqwest::Method;
+use serde_json::json;
+
+fn client_for(server: &mockito::Server) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+}
+
+fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_request_returns_raw_body() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/proj/time_sessions?from=2024-01-01")
+        .match_header("authorization", mockito::Matcher::Any)
+        .with_status(200)
+        .with_body("<time-sessions type=\"array\"></time-sessions>")
+        .create_async()
+        .await;
+
+    let body = request(
+        &client_for(&server),
+        Method::GET,
+        "proj/time_sessions?from=2024-01-01",
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(body, "<time-sessions type=\"array\"></time-sessions>");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_sends_body() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/proj/time_sessions")
+        .match_body("<time-session><minutes>30</minutes></time-session>")
+        .with_status(201)
+        .with_body("<time-session><id>9</id></time-session>")
+        .create_async()
+        .await;
+
+    let body = request(
+        &client_for(&server),
+        Method::POST,
+        "/proj/time_sessions",
+        Some("<time-session><minutes>30</minutes></time-session>".to_string()),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(body, "<time-session><id>9</id></time-session>");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_maps_error_status() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("DELETE", "/proj/nothing")
+        .with_status(404)
+        .with_body("Not found")
+        .create_async()
+        .await;
+
+    let err = request(&client_for(&server), Method::DELETE, "/proj/nothing", None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, CodebaseError::NotFound { .. }));
+}
+
+#[test]
+fn test_parse_field() {
+    assert_eq!(
+        parse_field("ticket[summary]=a=b").unwrap(),
+        ("ticket[summary]".to_string(), "a=b".to_string())
+    );
+    assert_eq!(parse_field("empty=").unwrap().1, "");
+    assert!(parse_field("novalue").is_err());
+    assert!(parse_field("=x").is_err());
+}
+
+#[test]
+fn test_with_query_encodes_fields() {
+    assert_eq!(
+        with_query(
+            "/proj/tickets",
+            &fields(&[("query", "status:open sort:priority")])
+        ),
+        "/proj/tickets?query=status%3Aopen%20sort%3Apriority"
+    );
+    assert_eq!(
+        with_query("/proj/tickets?page=2", &fields(&[("a", "&")])),
+        "/proj/tickets?page=2&a=%26"
+    );
+    assert_eq!(with_query("/proj", &[]), "/proj");
+}
+
+#[test]
+fn test_fields_to_xml_nests_and_escapes() {
+    let xml = fields_to_xml(&fields(&[
+        ("ticket[summary]", "Fix <login> & SSO"),
+        ("ticket[ticket-type]", "bug"),
+        ("ticket[tags][tag]", "a"),
+        ("ticket[tags][tag]", "b"),
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        xml,
+        "<ticket><summary>Fix &lt;login&gt; &amp; SSO</summary><ticket-type>bug</ticket-type>\
+         <tags><tag>a</tag><tag>b</tag></tags></ticket>"
+    );
+}
+
+#[test]
+fn test_fields_to_xml_rejects_bad_names() {
+    for key in ["ticket[summary", "ticket[]", "a b", "ticket]x[", "<x>"] {
+        assert!(fields_to_xml(&fields(&[(key, "v")])).is_err(), "{}", key);
+    }
+}
+
+#[test]
+fn test_xml_to_json_keeps_every_element() {
+    let value = xml_to_json(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <ticket>
+            <ticket-id type="integer">42</ticket-id>
+            <summary>Fish &amp; chips</summary>
+            <not-modelled>kept</not-modelled>
+            <notes><![CDATA[<b>raw</b>]]></notes>
+            <watchers>
+                <watcher>1</watcher>
+                <watcher>2</watcher>
+            </watchers>
+            <empty/>
+        </ticket>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "ticket-id": 42,
+            "summary": "Fish & chips",
+            "not-modelled": "kept",
+            "notes": "<b>raw</b>",
+            "watchers": {"watcher": ["1", "2"]},
+            "empty": "",
+        })
+    );
+}
+
+#[test]
+fn test_xml_to_json_rejects_malformed_xml() {
+    assert!(matches!(
+        xml_to_json("<a><b></a>"),
+        Err(CodebaseError::Decode(_))
+    ));
+    assert!(xml_to_json("<a>").is_err());
+    assert!(xml_to_json("").is_err());
+}
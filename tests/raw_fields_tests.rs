@@ -0,0 +1,256 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::fake::FakeTransport;
+use codebase_cli::api::lookup::ProjectLookup;
+use codebase_cli::api::raw::{RawCapture, xml_to_json};
+use codebase_cli::api::tickets::{list_tickets, paginate_tickets, show_ticket};
+use codebase_cli::commands::PageArgs;
+use codebase_cli::commands::tickets::{self, TicketCommand};
+use codebase_cli::mock_server::Store;
+use codebase_cli::output::{self, OutputFormat, OutputOptions, TicketRow};
+use serde_json::json;
+
+fn client_for(server: &mockito::Server, capture: &RawCapture) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_raw_capture(capture.clone())
+}
+
+const TICKETS: &str = r#"<tickets type="array">
+    <ticket>
+        <ticket-id type="integer">7</ticket-id>
+        <summary>Broken login</summary>
+        <estimated-time type="integer" nil="true"></estimated-time>
+        <updated-at type="datetime">2024-01-01T10:00:00Z</updated-at>
+    </ticket>
+</tickets>"#;
+
+#[test]
+fn test_typed_values() {
+    let value = xml_to_json(
+        r#"<project>
+            <id type="integer">12</id>
+            <bad-id type="integer">twelve</bad-id>
+            <disk-usage type="float">1.5</disk-usage>
+            <archived type="boolean">false</archived>
+            <overview nil="true"/>
+            <deadline type="date" nil="true"></deadline>
+            <groups type="array"/>
+            <users type="array">
+                <user><username>jdoe</username></user>
+            </users>
+        </project>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "id": 12,
+            "bad-id": "twelve",
+            "disk-usage": 1.5,
+            "archived": false,
+            "overview": null,
+            "deadline": null,
+            "groups": [],
+            "users": [{"username": "jdoe"}],
+        })
+    );
+}
+
+#[test]
+fn test_take_json_joins_pages_of_a_list() {
+    let capture = RawCapture::new();
+    capture.record_page(TICKETS, 1);
+    capture.record_page(TICKETS, 1);
+    capture.record_page(r#"<tickets type="array"></tickets>"#, 0);
+
+    let value = capture.take_json().unwrap();
+
+    assert_eq!(value.as_array().unwrap().len(), 2);
+    assert!(capture.take().is_empty());
+}
+
+#[test]
+fn test_take_json_single_and_mixed_responses() {
+    let capture = RawCapture::new();
+    capture.record("<ticket><id type=\"integer\">1</id></ticket>");
+    assert_eq!(capture.take_json().unwrap(), json!({"id": 1}));
+
+    capture.record("<ticket><id type=\"integer\">1</id></ticket>");
+    capture.record("");
+    capture.record(TICKETS);
+    let value = capture.take_json().unwrap();
+    assert_eq!(value[0], json!({"id": 1}));
+    assert_eq!(value[1][0]["summary"], "Broken login");
+}
+
+#[tokio::test]
+async fn test_client_records_responses_but_not_lookups() {
+    let mut server = mockito::Server::new_async().await;
+    let _ticket = server
+        .mock("GET", "/proj/tickets/7")
+        .with_status(200)
+        .with_body("<ticket><ticket-id type=\"integer\">7</ticket-id></ticket>")
+        .create_async()
+        .await;
+    let mut lookups = Vec::new();
+    for path in [
+        "/proj/tickets/statuses",
+        "/proj/tickets/priorities",
+        "/proj/tickets/categories",
+        "/proj/tickets/types",
+        "/proj/assignments",
+    ] {
+        lookups.push(
+            server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body("<list type=\"array\"></list>")
+                .create_async()
+                .await,
+        );
+    }
+    let capture = RawCapture::new();
+    let client = client_for(&server, &capture);
+
+    show_ticket(&client, "proj", 7).await.unwrap();
+    ProjectLookup::fetch(&client, "proj").await.unwrap();
+
+    assert_eq!(
+        capture.take(),
+        vec!["<ticket><ticket-id type=\"integer\">7</ticket-id></ticket>"]
+    );
+}
+
+#[tokio::test]
+async fn test_raw_fields_output_replaces_modelled_data() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/proj/tickets")
+        .with_status(200)
+        .with_body(TICKETS)
+        .create_async()
+        .await;
+    let capture = RawCapture::new();
+    let tickets = list_tickets(&client_for(&server, &capture), "proj")
+        .await
+        .unwrap();
+    let out = OutputOptions {
+        format: OutputFormat::Table,
+        raw_fields: Some(capture),
+        ..Default::default()
+    };
+    assert!(!out.uses_columns());
+    assert!(out.prints_data());
+
+    let lookup = ProjectLookup::default();
+    let mut buf = Vec::new();
+    output::write_list_with(
+        &out,
+        &tickets,
+        |ticket| TicketRow {
+            ticket,
+            lookup: &lookup,
+        },
+        &mut buf,
+    )
+    .unwrap();
+
+    let printed: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(
+        printed,
+        json!([{
+            "ticket-id": 7,
+            "summary": "Broken login",
+            "estimated-time": null,
+            "updated-at": "2024-01-01T10:00:00Z",
+        }])
+    );
+}
+
+#[tokio::test]
+async fn test_raw_fields_respect_limit() {
+    let fake = FakeTransport::new(Store::sample());
+    let capture = RawCapture::new();
+    let client = fake.client().with_raw_capture(capture.clone());
+
+    tickets::run(
+        &client,
+        TicketCommand::List {
+            project: "widgets".to_string(),
+            pages: PageArgs {
+                all: false,
+                limit: Some(1),
+            },
+            names: false,
+        },
+    )
+    .await
+    .unwrap();
+
+    let value = capture.take_json().unwrap();
+    assert_eq!(value.as_array().unwrap().len(), 1);
+    assert_eq!(value[0]["ticket-id"], 1);
+}
+
+#[tokio::test]
+async fn test_raw_fields_flatten_pages_without_array_type() {
+    let mut server = mockito::Server::new_async().await;
+    let _first = server
+        .mock("GET", "/proj/tickets")
+        .with_status(200)
+        .with_body(
+            "<tickets><ticket><ticket-id>1</ticket-id></ticket>\
+             <ticket><ticket-id>2</ticket-id></ticket></tickets>",
+        )
+        .create_async()
+        .await;
+    let _second = server
+        .mock("GET", "/proj/tickets?page=2")
+        .with_status(200)
+        .with_body("<tickets><ticket><ticket-id>3</ticket-id></ticket></tickets>")
+        .create_async()
+        .await;
+    let _last = server
+        .mock("GET", "/proj/tickets?page=3")
+        .with_status(200)
+        .with_body("<tickets></tickets>")
+        .create_async()
+        .await;
+    let capture = RawCapture::new();
+
+    paginate_tickets(&client_for(&server, &capture), "proj")
+        .collect_all()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        capture.take_json().unwrap(),
+        json!([{"ticket-id": "1"}, {"ticket-id": "2"}, {"ticket-id": "3"}])
+    );
+}
+
+#[tokio::test]
+async fn test_raw_fields_show_only_the_ticket() {
+    let fake = FakeTransport::new(Store::sample());
+    let capture = RawCapture::new();
+    let client = fake.client().with_raw_capture(capture.clone());
+
+    tickets::run(
+        &client,
+        TicketCommand::Show {
+            project: "widgets".to_string(),
+            ticket_id: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    let value = capture.take_json().unwrap();
+    assert!(value.is_object(), "{value}");
+    assert_eq!(value["ticket-id"], 1);
+}
@@ -0,0 +1,189 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::error::to_xml;
+use codebase_cli::api::models::*;
+use codebase_cli::api::tickets::create_ticket;
+use quick_xml::de::from_str;
+
+const HOSTILE: &[&str] = &[
+    "a < b && c > d",
+    "</summary><status-id>99</status-id><summary>",
+    "ends with ]]> terminator",
+    "<![CDATA[nested]]>",
+    "quotes \"double\" and 'single'",
+    "&amp; already escaped",
+];
+
+#[test]
+fn test_new_ticket_round_trips_hostile_strings() {
+    for &text in HOSTILE {
+        let body = to_xml(&NewTicket {
+            summary: text.to_string(),
+            ticket_type: "bug".to_string(),
+            description: Some(text.to_string()),
+            tags: Some(text.to_string()),
+            ..Default::default()
+        })
+        .expect("ticket should serialize");
+
+        let parsed: Ticket = from_str(&body).expect("serialized body must be well-formed XML");
+        assert_eq!(parsed.summary.as_deref(), Some(text), "body: {}", body);
+        assert_eq!(parsed.description.as_deref(), Some(text));
+        assert_eq!(parsed.tags.as_deref(), Some(text));
+        assert_eq!(parsed.status_id, None, "injected element leaked: {}", body);
+    }
+}
+
+#[test]
+fn test_new_ticket_omits_unset_fields() {
+    let body = to_xml(&NewTicket {
+        summary: "Fix bug".to_string(),
+        ticket_type: "bug".to_string(),
+        priority_id: Some(2),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(
+        body,
+        "<ticket><summary>Fix bug</summary><ticket-type>bug</ticket-type><priority-id>2</priority-id></ticket>"
+    );
+}
+
+#[test]
+fn test_ticket_note_round_trips_hostile_content_and_changes() {
+    for &text in HOSTILE {
+        let body = to_xml(&NewTicketNote {
+            content: Some(text.to_string()),
+            private: true,
+            changes: Some(NoteChanges {
+                status_id: Some(3),
+                priority_id: None,
+                category_id: None,
+                assignee_id: None,
+                milestone_id: None,
+                subject: Some(text.to_string()),
+            }),
+        })
+        .expect("note should serialize");
+
+        let parsed: TicketNote = from_str(&body).expect("serialized body must be well-formed XML");
+        assert_eq!(parsed.content.as_deref(), Some(text));
+        assert_eq!(parsed.private, Some(true));
+        let changes = parsed.changes.expect("changes should round-trip");
+        assert_eq!(changes.status_id, Some(3));
+        assert_eq!(changes.priority_id, None);
+        assert_eq!(changes.subject.as_deref(), Some(text));
+    }
+}
+
+#[test]
+fn test_public_note_omits_private_flag() {
+    let body = to_xml(&NewTicketNote {
+        content: Some("Hello".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(body, "<ticket-note><content>Hello</content></ticket-note>");
+}
+
+#[test]
+fn test_merge_request_round_trips_hostile_subject() {
+    for &text in HOSTILE {
+        let body = to_xml(&NewMergeRequest {
+            source_ref: "feature/<x>".to_string(),
+            target_ref: "main".to_string(),
+            subject: text.to_string(),
+        })
+        .unwrap();
+
+        let parsed: MergeRequest =
+            from_str(&body).expect("serialized body must be well-formed XML");
+        assert_eq!(parsed.subject.as_deref(), Some(text));
+        assert_eq!(parsed.source_ref.as_deref(), Some("feature/<x>"));
+    }
+}
+
+#[test]
+fn test_milestone_and_hook_round_trip_hostile_strings() {
+    for &text in HOSTILE {
+        let body = to_xml(&MilestoneRequest {
+            name: Some(text.to_string()),
+            description: Some(text.to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let parsed: Milestone = from_str(&body).expect("milestone body must be well-formed");
+        assert_eq!(parsed.name.as_deref(), Some(text));
+        assert_eq!(parsed.description.as_deref(), Some(text));
+
+        let body = to_xml(&NewHook {
+            url: format!("https://example.com/?q={}", text),
+            username: Some(text.to_string()),
+            password: Some(text.to_string()),
+        })
+        .unwrap();
+        let parsed: Hook = from_str(&body).expect("hook body must be well-formed");
+        assert_eq!(parsed.username.as_deref(), Some(text));
+        assert_eq!(parsed.password.as_deref(), Some(text));
+    }
+}
+
+#[test]
+fn test_watchers_and_assignments_serialize_as_repeated_elements() {
+    let body = to_xml(&WatchersRequest {
+        watchers: vec![1, 2],
+    })
+    .unwrap();
+    assert_eq!(
+        body,
+        "<watchers><watcher>1</watcher><watcher>2</watcher></watchers>"
+    );
+
+    let body = to_xml(&ProjectAssignmentsRequest {
+        users: vec![UserRef { id: 7 }],
+    })
+    .unwrap();
+    assert_eq!(body, "<users><user><id>7</id></user></users>");
+}
+
+#[tokio::test]
+async fn test_create_ticket_sends_escaped_body() {
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/proj/tickets")
+        .match_body(
+            "<ticket><summary>a &lt; b &amp;&amp; c &gt; d</summary><ticket-type>bug</ticket-type></ticket>",
+        )
+        .with_status(201)
+        .with_body("<ticket><ticket-id>1</ticket-id></ticket>")
+        .create_async()
+        .await;
+
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    );
+
+    let ticket = create_ticket(
+        &client,
+        "proj",
+        "a < b && c > d",
+        "bug",
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("request should match escaped body");
+
+    assert_eq!(ticket.ticket_id, Some(1));
+    mock.assert_async().await;
+}
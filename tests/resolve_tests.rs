@@ -0,0 +1,132 @@
+use codebase_cli::api::lookup::{ProjectLookup, ResolveError, resolve};
+use codebase_cli::api::models::{ProjectUser, TicketStatus, TicketType};
+
+fn statuses() -> Vec<(i64, Vec<String>)> {
+    [
+        "New",
+        "Open",
+        "In Progress",
+        "On Hold",
+        "Closed",
+        "Closed - Duplicate",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, name)| (i as i64 + 1, vec![name.to_string()]))
+    .collect()
+}
+
+#[test]
+fn test_exact_match_ignores_case_and_punctuation() {
+    assert_eq!(resolve("status", "in progress", statuses()).unwrap(), 3);
+    assert_eq!(resolve("status", "in-progress", statuses()).unwrap(), 3);
+    assert_eq!(resolve("status", "ON_HOLD", statuses()).unwrap(), 4);
+}
+
+#[test]
+fn test_exact_match_beats_prefix_match() {
+    // "Closed" is also a prefix of "Closed - Duplicate".
+    assert_eq!(resolve("status", "closed", statuses()).unwrap(), 5);
+}
+
+#[test]
+fn test_prefix_substring_and_typo_matches() {
+    assert_eq!(resolve("status", "prog", statuses()).unwrap(), 3);
+    assert_eq!(resolve("status", "dup", statuses()).unwrap(), 6);
+    assert_eq!(resolve("status", "in progess", statuses()).unwrap(), 3);
+}
+
+#[test]
+fn test_numeric_query_is_an_id() {
+    assert_eq!(resolve("status", "4", statuses()).unwrap(), 4);
+}
+
+#[test]
+fn test_unknown_numeric_id_is_not_found() {
+    let err = resolve("status", "999", statuses()).unwrap_err();
+    assert!(matches!(err, ResolveError::NotFound { .. }));
+    assert!(err.to_string().contains("In Progress"));
+}
+
+#[test]
+fn test_numeric_query_matches_status_named_that() {
+    let mut candidates = statuses();
+    candidates.push((7, vec!["2".to_string()]));
+    assert_eq!(resolve("status", "2", candidates).unwrap(), 7);
+}
+
+#[test]
+fn test_ambiguous_match_lists_candidates() {
+    let err = resolve("status", "o", statuses()).unwrap_err();
+    match &err {
+        ResolveError::Ambiguous { matches, .. } => {
+            assert_eq!(matches, &vec!["Open".to_string(), "On Hold".to_string()]);
+        }
+        other => panic!("expected Ambiguous, got {:?}", other),
+    }
+    assert!(err.to_string().contains("Open, On Hold"));
+}
+
+#[test]
+fn test_no_match_lists_available() {
+    let err = resolve("status", "wontfix", statuses()).unwrap_err();
+    assert!(matches!(err, ResolveError::NotFound { .. }));
+    assert!(err.to_string().contains("In Progress"));
+}
+
+#[test]
+fn test_resolve_user_by_username_name_or_email() {
+    let user = |id, first: &str, last: &str, username: &str| ProjectUser {
+        id: Some(id),
+        first_name: Some(first.to_string()),
+        last_name: Some(last.to_string()),
+        username: Some(username.to_string()),
+        email_address: Some(format!("{}@example.com", username)),
+        company: None,
+    };
+    let lookup = ProjectLookup {
+        users: vec![
+            user(101, "John", "Doe", "jdoe"),
+            user(102, "Jane", "Doe", "janed"),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(lookup.resolve_user("jdoe").unwrap(), 101);
+    assert_eq!(lookup.resolve_user("Jane Doe").unwrap(), 102);
+    assert_eq!(lookup.resolve_user("janed@example.com").unwrap(), 102);
+    assert!(matches!(
+        lookup.resolve_user("doe"),
+        Err(ResolveError::Ambiguous { .. })
+    ));
+}
+
+#[test]
+fn test_resolve_type_returns_project_spelling() {
+    let lookup = ProjectLookup {
+        types: vec![
+            TicketType {
+                id: Some(1),
+                name: Some("Bug".to_string()),
+                icon: None,
+            },
+            TicketType {
+                id: Some(2),
+                name: Some("Enhancement".to_string()),
+                icon: None,
+            },
+        ],
+        statuses: vec![TicketStatus {
+            id: Some(7),
+            name: Some("Open".to_string()),
+            background_colour: None,
+            order: None,
+            treat_as_closed: None,
+        }],
+        ..Default::default()
+    };
+
+    assert_eq!(lookup.resolve_type("bug").unwrap(), "Bug");
+    assert_eq!(lookup.resolve_type("enh").unwrap(), "Enhancement");
+    assert_eq!(lookup.resolve_status("open").unwrap(), 7);
+}
@@ -0,0 +1,300 @@
+use std::time::{Duration, SystemTime};
+
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::config::ConfigFile;
+use codebase_cli::api::error::CodebaseError;
+use codebase_cli::api::retry::{RetryPolicy, is_idempotent, parse_retry_after};
+use reqwest::{Method, StatusCode};
+
+/// A policy with millisecond delays and no jitter, so tests run quickly.
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(50),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+fn client_for(server: &mockito::Server, policy: RetryPolicy) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_retry_policy(policy)
+}
+
+#[tokio::test]
+async fn test_get_is_retried_until_success() {
+    let mut server = mockito::Server::new_async().await;
+    let throttled = server
+        .mock("GET", "/projects")
+        .with_status(429)
+        .expect(2)
+        .create_async()
+        .await;
+    let ok = server
+        .mock("GET", "/projects")
+        .with_status(200)
+        .with_body("<projects type=\"array\"></projects>")
+        .create_async()
+        .await;
+
+    let body = client_for(&server, fast_policy())
+        .get("/projects")
+        .await
+        .unwrap();
+
+    assert_eq!(body, "<projects type=\"array\"></projects>");
+    throttled.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_attempts() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/projects")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+
+    let err = client_for(&server, fast_policy())
+        .get("/projects")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, CodebaseError::RateLimited { .. }));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_post_is_not_retried_by_default() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/proj/tickets")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let err = client_for(&server, fast_policy())
+        .post("/proj/tickets", "<ticket/>".to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_post_is_retried_after_429() {
+    let mut server = mockito::Server::new_async().await;
+    let throttled = server
+        .mock("POST", "/proj/tickets")
+        .with_status(429)
+        .with_header("Retry-After", "0")
+        .expect(1)
+        .create_async()
+        .await;
+    let created = server
+        .mock("POST", "/proj/tickets")
+        .with_status(201)
+        .with_body("<ticket/>")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let body = client_for(&server, fast_policy())
+        .post("/proj/tickets", "<ticket/>".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(body, "<ticket/>");
+    throttled.assert_async().await;
+    created.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_post_is_retried_when_allowed() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/proj/tickets")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+    let policy = RetryPolicy {
+        retry_non_idempotent: true,
+        ..fast_policy()
+    };
+
+    client_for(&server, policy)
+        .post("/proj/tickets", "<ticket/>".to_string())
+        .await
+        .unwrap_err();
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_after_is_honored() {
+    let mut server = mockito::Server::new_async().await;
+    let throttled = server
+        .mock("GET", "/projects")
+        .with_status(429)
+        .with_header("Retry-After", "1")
+        .expect(1)
+        .create_async()
+        .await;
+    let _ok = server
+        .mock("GET", "/projects")
+        .with_status(200)
+        .create_async()
+        .await;
+    let policy = RetryPolicy {
+        max_delay: Duration::from_secs(5),
+        ..fast_policy()
+    };
+
+    let started = std::time::Instant::now();
+    client_for(&server, policy).get("/projects").await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    throttled.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_after_beyond_max_delay_is_not_waited_for() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/projects")
+        .with_status(429)
+        .with_header("Retry-After", "3600")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let err = client_for(&server, fast_policy())
+        .get("/projects")
+        .await
+        .unwrap_err();
+
+    match err {
+        CodebaseError::RateLimited { retry_after, .. } => {
+            assert_eq!(retry_after, Some(Duration::from_secs(3600)))
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_connect_errors_are_retried_then_reported() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let client = CodebaseClient::with_base_url(
+        url,
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_retry_policy(fast_policy());
+
+    let err = client.get("/projects").await.unwrap_err();
+
+    assert!(matches!(err, CodebaseError::Transport(_)));
+}
+
+#[test]
+fn test_backoff_doubles_up_to_max_delay() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(500),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    };
+    let delays: Vec<u128> = (0..5).map(|n| policy.backoff(n).as_millis()).collect();
+    assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+
+    let jittered = RetryPolicy {
+        jitter: 0.5,
+        ..policy
+    };
+    for _ in 0..20 {
+        let delay = jittered.backoff(1);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn test_idempotent_methods() {
+    assert!(is_idempotent(&Method::GET));
+    assert!(is_idempotent(&Method::PUT));
+    assert!(is_idempotent(&Method::DELETE));
+    assert!(!is_idempotent(&Method::POST));
+    assert!(!is_idempotent(&Method::PATCH));
+}
+
+#[test]
+fn test_parse_retry_after() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777);
+    assert_eq!(
+        parse_retry_after("120", now),
+        Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+        parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+        Some(Duration::from_secs(0))
+    );
+    assert_eq!(
+        parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(parse_retry_after("soon", now), None);
+}
+
+#[test]
+fn test_retry_settings_from_config_file() {
+    let file: ConfigFile = toml::from_str(
+        r#"
+        [retry]
+        max_attempts = 2
+        base_delay_ms = 250
+        statuses = [429, 502]
+        retry_non_idempotent = true
+        "#,
+    )
+    .unwrap();
+    let policy = file.retry.policy().unwrap();
+
+    assert_eq!(policy.max_attempts, 2);
+    assert_eq!(policy.base_delay, Duration::from_millis(250));
+    assert_eq!(policy.max_delay, RetryPolicy::default().max_delay);
+    assert_eq!(
+        policy.statuses,
+        vec![StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY]
+    );
+    assert!(policy.retry_non_idempotent);
+
+    let empty: ConfigFile = toml::from_str("").unwrap();
+    assert_eq!(empty.retry.policy().unwrap(), RetryPolicy::default());
+}
+
+#[test]
+fn test_invalid_retry_settings_are_rejected() {
+    for toml in [
+        "[retry]\nmax_attempts = 0",
+        "[retry]\njitter = 2.0",
+        "[retry]\nstatuses = [42]",
+    ] {
+        let file: ConfigFile = toml::from_str(toml).unwrap();
+        assert!(file.retry.policy().is_err(), "{}", toml);
+    }
+    assert!(toml::from_str::<ConfigFile>("[retry]\nmax_retries = 3").is_err());
+}
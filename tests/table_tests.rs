@@ -0,0 +1,181 @@
+use codebase_cli::api::lookup::ProjectLookup;
+use codebase_cli::api::models::*;
+use codebase_cli::output::{
+    Column, TableRow, TicketRow, UnknownColumn, render, select_columns, truncate,
+};
+
+fn plain() {
+    colored::control::set_override(false);
+}
+
+fn branches(names: &[&str]) -> Vec<Branch> {
+    names
+        .iter()
+        .map(|n| Branch {
+            name: Some(n.to_string()),
+        })
+        .collect()
+}
+
+fn tickets() -> Vec<Ticket> {
+    quick_xml::de::from_str::<Tickets>(
+        r#"<tickets>
+            <ticket>
+                <ticket-id>7</ticket-id>
+                <summary>Login fails when the SSO provider returns an expired token</summary>
+                <ticket-type>bug</ticket-type>
+                <status-id>2</status-id>
+                <priority-id>11</priority-id>
+                <assignee>jdoe</assignee>
+            </ticket>
+            <ticket>
+                <ticket-id>123</ticket-id>
+                <summary>Tidy up</summary>
+                <ticket-type>task</ticket-type>
+                <status-id>99</status-id>
+            </ticket>
+        </tickets>"#,
+    )
+    .unwrap()
+    .tickets
+}
+
+fn lookup() -> ProjectLookup {
+    quick_xml::de::from_str::<TicketStatuses>(
+        r#"<ticketing-statuses>
+            <ticketing-status><id>2</id><name>In Progress</name></ticketing-status>
+        </ticketing-statuses>"#,
+    )
+    .map(|s| ProjectLookup {
+        statuses: s.statuses,
+        priorities: quick_xml::de::from_str::<TicketPriorities>(
+            r#"<ticketing-priorities>
+                <ticketing-priority><id>11</id><name>High</name></ticketing-priority>
+            </ticketing-priorities>"#,
+        )
+        .unwrap()
+        .priorities,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_select_columns_defaults() {
+    let columns = select_columns::<Project>(None).unwrap();
+    let names: Vec<&str> = columns.iter().map(|c| c.name).collect();
+    assert_eq!(names, Project::DEFAULT_COLUMNS);
+}
+
+#[test]
+fn test_select_columns_keeps_requested_order() {
+    let requested = vec!["summary".to_string(), " ID ".to_string()];
+    let columns = select_columns::<TicketRow>(Some(&requested)).unwrap();
+    assert_eq!(columns, vec![Column::flex("summary"), Column::fixed("id")]);
+}
+
+#[test]
+fn test_select_columns_unknown_lists_available() {
+    let requested = vec!["sha".to_string(), "bogus".to_string()];
+    let err = select_columns::<Commit>(Some(&requested)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown column 'bogus'. Available columns: sha, author, email, date, message"
+    );
+    assert!(err.is::<UnknownColumn>());
+}
+
+#[test]
+fn test_render_aligns_columns() {
+    plain();
+    let lookup = lookup();
+    let tickets = tickets();
+    let rows: Vec<TicketRow> = tickets
+        .iter()
+        .map(|ticket| TicketRow {
+            ticket,
+            lookup: &lookup,
+        })
+        .collect();
+    let columns = select_columns::<TicketRow>(Some(&[
+        "id".to_string(),
+        "status".to_string(),
+        "summary".to_string(),
+    ]))
+    .unwrap();
+
+    let lines = render(&columns, &rows, None);
+
+    assert_eq!(
+        lines,
+        vec![
+            "ID   STATUS       SUMMARY",
+            "7    In Progress  Login fails when the SSO provider returns an expired token",
+            "123  #99          Tidy up",
+        ]
+    );
+}
+
+#[test]
+fn test_render_truncates_flex_columns_to_width() {
+    plain();
+    let lookup = lookup();
+    let tickets = tickets();
+    let rows: Vec<TicketRow> = tickets
+        .iter()
+        .map(|ticket| TicketRow {
+            ticket,
+            lookup: &lookup,
+        })
+        .collect();
+    let columns = select_columns::<TicketRow>(None).unwrap();
+
+    let lines = render(&columns, &rows, Some(60));
+
+    for line in &lines {
+        assert!(line.chars().count() <= 60, "too wide: {:?}", line);
+    }
+    assert!(lines[1].starts_with("7    bug   In Progress  High      jdoe      Login fails"));
+    assert!(lines[1].ends_with('…'));
+    assert!(lines[2].ends_with("Tidy up"));
+}
+
+#[test]
+fn test_render_never_shrinks_below_minimum() {
+    plain();
+    let rows = branches(&["feature/a-rather-long-branch-name"]);
+    let columns = select_columns::<Branch>(None).unwrap();
+
+    let lines = render(&columns, &rows, Some(5));
+
+    assert_eq!(lines[1], "feature/a-r…");
+}
+
+#[test]
+fn test_render_uses_first_line_of_multiline_text() {
+    plain();
+    let commit: Commit = quick_xml::de::from_str(
+        r#"<commit>
+            <ref>0123456789abcdef</ref>
+            <message>Fix the thing
+
+Longer explanation.</message>
+        </commit>"#,
+    )
+    .unwrap();
+    let columns =
+        select_columns::<Commit>(Some(&["sha".to_string(), "message".to_string()])).unwrap();
+
+    let lines = render(&columns, &[commit], None);
+
+    assert_eq!(lines, vec!["SHA      MESSAGE", "0123456  Fix the thing"]);
+}
+
+#[test]
+fn test_truncate() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("exactly10!", 10), "exactly10!");
+    assert_eq!(truncate("a longer string", 6), "a lon…");
+    assert_eq!(truncate("日本語テキスト", 5), "日本…");
+    assert_eq!(truncate("anything", 0), "");
+}
@@ -0,0 +1,153 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use codebase_cli::api::lookup::ProjectLookup;
+use codebase_cli::api::models::*;
+use codebase_cli::output::{self, OutputFormat, OutputOptions, Template, TicketRow, relative_time};
+
+fn ticket() -> Ticket {
+    quick_xml::de::from_str(
+        r#"<ticket>
+            <ticket-id>42</ticket-id>
+            <summary>Login fails when the SSO provider returns an expired token</summary>
+            <ticket-type>bug</ticket-type>
+            <assignee>jdoe</assignee>
+        </ticket>"#,
+    )
+    .unwrap()
+}
+
+fn render(template: &str) -> String {
+    Template::parse(template)
+        .unwrap()
+        .render(&ticket())
+        .unwrap()
+}
+
+#[test]
+fn test_fields_with_underscores_dots_and_escapes() {
+    assert_eq!(
+        render(r"{{ticket_id}}\t{{ .assignee }}\t{{ticket-type}}"),
+        "42\tjdoe\tbug"
+    );
+}
+
+#[test]
+fn test_missing_and_null_fields_render_empty() {
+    assert_eq!(render("[{{reporter}}][{{no_such_field}}]"), "[][]");
+}
+
+#[test]
+fn test_truncate_as_call_and_pipe() {
+    assert_eq!(render("{{truncate 10 .summary}}"), "Login fai…");
+    assert_eq!(render("{{ summary | truncate 10 }}"), "Login fai…");
+}
+
+#[test]
+fn test_color_helper() {
+    colored::control::set_override(true);
+    let rendered = Template::parse(r#"{{color "red" ticket_type}}"#)
+        .unwrap()
+        .render(&ticket())
+        .unwrap();
+    assert_eq!(rendered, "\u{1b}[31mbug\u{1b}[0m");
+}
+
+#[test]
+fn test_nested_fields_and_whole_value() {
+    let data = serde_json::json!({"user": {"name": "Ann"}, "n": 3});
+    let template = Template::parse("{{user.name}} {{.n}}").unwrap();
+    assert_eq!(template.render(&data).unwrap(), "Ann 3");
+    let whole = Template::parse("{{.}}").unwrap();
+    assert_eq!(whole.render(&"plain").unwrap(), "plain");
+}
+
+#[test]
+fn test_parse_errors() {
+    for (template, message) in [
+        ("{{summary", "unclosed"),
+        ("{{}}", "empty action"),
+        ("{{truncate .summary}}", "truncate takes 2 arguments, got 1"),
+        ("{{summary | upper}}", "expected a function after '|'"),
+        ("{{upper summary}}", "unknown function 'upper'"),
+        (r#"{{color "red}}"#, "unterminated string"),
+    ] {
+        let err = Template::parse(template).unwrap_err().to_string();
+        assert!(err.contains(message), "{}: {}", template, err);
+    }
+}
+
+#[test]
+fn test_unknown_color_is_a_render_error() {
+    let template = Template::parse(r#"{{color "puce" summary}}"#).unwrap();
+    assert!(template.render(&ticket()).is_err());
+}
+
+#[test]
+fn test_relative_time() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_704_103_200); // 2024-01-01T10:00:00Z
+    let cases = [
+        ("2024-01-01T09:59:30Z", "just now"),
+        ("2024-01-01T09:00:00Z", "1 hour ago"),
+        ("2024-01-01 07:00:00 UTC", "3 hours ago"),
+        ("2024-01-01T12:00:00+02:00", "just now"),
+        ("2023-12-30T10:00:00.123Z", "2 days ago"),
+        ("2022-06-01T10:00:00Z", "1 year ago"),
+        ("2024-01-01T10:05:00Z", "in 5 minutes"),
+    ];
+    for (timestamp, expected) in cases {
+        assert_eq!(
+            relative_time(timestamp, now).as_deref(),
+            Some(expected),
+            "{}",
+            timestamp
+        );
+    }
+    assert_eq!(relative_time("yesterday", SystemTime::now()), None);
+}
+
+#[test]
+fn test_relative_time_rejects_non_ascii_zones() {
+    for timestamp in [
+        "2024-01-01T10:00:00+é12",
+        "2024-01-01T10:00:00+1é2",
+        "2024-01-01T10:00:00-٠١٠٠",
+    ] {
+        assert_eq!(
+            relative_time(timestamp, SystemTime::now()),
+            None,
+            "{}",
+            timestamp
+        );
+    }
+}
+
+#[test]
+fn test_timeago_leaves_unparseable_values_alone() {
+    let data = serde_json::json!({"when": "sometime"});
+    let template = Template::parse("{{timeago .when}}").unwrap();
+    assert_eq!(template.render(&data).unwrap(), "sometime");
+}
+
+#[test]
+fn test_list_renders_one_line_per_item() {
+    let out = OutputOptions {
+        format: OutputFormat::Table,
+        template: Some(Template::parse(r"{{ticket_id}}:{{assignee}}").unwrap()),
+        ..Default::default()
+    };
+    assert!(!out.uses_columns());
+    let tickets = vec![ticket(), ticket()];
+    let mut buf = Vec::new();
+    let lookup = ProjectLookup::default();
+    output::write_list_with(
+        &out,
+        &tickets,
+        |ticket| TicketRow {
+            ticket,
+            lookup: &lookup,
+        },
+        &mut buf,
+    )
+    .unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "42:jdoe\n42:jdoe\n");
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::config::ConfigFile;
+use codebase_cli::api::fake::FakeTransport;
+use codebase_cli::api::lookup::ProjectLookup;
+use codebase_cli::api::throttle::{RateLimiter, map_concurrent};
+use codebase_cli::mock_server::Store;
+
+#[test]
+fn test_burst_then_steady_rate() {
+    let limiter = RateLimiter::new(10.0, 3);
+    let start = Instant::now();
+
+    let waits: Vec<Duration> = (0..5).map(|_| limiter.reserve(start)).collect();
+
+    assert_eq!(&waits[..3], &[Duration::ZERO; 3]);
+    assert_eq!(waits[3], Duration::from_millis(100));
+    assert_eq!(waits[4], Duration::from_millis(200));
+}
+
+#[test]
+fn test_tokens_refill_over_time() {
+    let limiter = RateLimiter::new(10.0, 2);
+    let start = Instant::now();
+    limiter.reserve(start);
+    limiter.reserve(start);
+
+    assert_eq!(
+        limiter.reserve(start + Duration::from_millis(100)),
+        Duration::ZERO
+    );
+    // Refilling never exceeds the burst size.
+    let later = start + Duration::from_secs(60);
+    for _ in 0..2 {
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+    }
+    assert!(limiter.reserve(later) > Duration::ZERO);
+}
+
+#[test]
+fn test_clones_share_one_bucket() {
+    let limiter = RateLimiter::new(1.0, 1);
+    let clone = limiter.clone();
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve(now), Duration::ZERO);
+    assert_eq!(clone.reserve(now), Duration::from_secs(1));
+}
+
+#[test]
+fn test_zero_rate_is_unlimited() {
+    let limiter = RateLimiter::new(0.0, 1);
+    let now = Instant::now();
+    for _ in 0..100 {
+        assert_eq!(limiter.reserve(now), Duration::ZERO);
+    }
+}
+
+#[tokio::test]
+async fn test_client_requests_are_paced() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/projects")
+        .with_status(200)
+        .with_body("<projects type=\"array\"></projects>")
+        .expect(3)
+        .create_async()
+        .await;
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_rate_limiter(RateLimiter::new(20.0, 1));
+    let clone = client.clone();
+
+    let start = Instant::now();
+    client.get("/projects").await.unwrap();
+    clone.get("/projects").await.unwrap();
+    client.get("/projects").await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_map_concurrent_bounds_requests_in_flight() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let results = map_concurrent(0..10u64, 3, |n| {
+        let in_flight = in_flight.clone();
+        let peak = peak.clone();
+        async move {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            // Finish out of order to check that results keep input order.
+            tokio::time::sleep(Duration::from_millis(10 - n)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok::<_, String>(n * 2)
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(results, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_map_concurrent_returns_first_error() {
+    let result = map_concurrent(1..=5, 2, |n| async move {
+        if n == 3 {
+            Err(format!("failed on {}", n))
+        } else {
+            Ok(n)
+        }
+    })
+    .await;
+
+    assert_eq!(result, Err("failed on 3".to_string()));
+}
+
+#[tokio::test]
+async fn test_lookup_fetch_shares_the_rate_limit() {
+    let fake = FakeTransport::new(Store::sample());
+    let client = fake.client().with_rate_limiter(RateLimiter::new(20.0, 1));
+
+    let start = Instant::now();
+    let lookup = ProjectLookup::fetch(&client, "widgets").await.unwrap();
+
+    // Five requests at 20 per second after a burst of one.
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(fake.requests().len(), 5);
+    assert!(!lookup.statuses.is_empty());
+    assert!(!lookup.users.is_empty());
+}
+
+#[test]
+fn test_rate_limit_from_config_file() {
+    let file: ConfigFile = toml::from_str(
+        r#"
+        [rate_limit]
+        requests_per_second = 1
+        burst = 2
+        "#,
+    )
+    .unwrap();
+    let limiter = file.rate_limit.limiter();
+    let now = Instant::now();
+
+    assert_eq!(limiter.reserve(now), Duration::ZERO);
+    assert_eq!(limiter.reserve(now), Duration::ZERO);
+    assert_eq!(limiter.reserve(now), Duration::from_secs(1));
+
+    let off: ConfigFile = toml::from_str("[rate_limit]\nrequests_per_second = 0").unwrap();
+    let unlimited = off.rate_limit.limiter();
+    for _ in 0..50 {
+        assert_eq!(unlimited.reserve(now), Duration::ZERO);
+    }
+}
@@ -0,0 +1,251 @@
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::lookup::{ProjectLookup, Transition, timeline};
+use codebase_cli::api::models::TicketNote;
+use codebase_cli::api::tickets::show_ticket;
+use codebase_cli::commands::tickets::{self, TicketCommand, TicketOutput};
+
+fn client_for(server: &mockito::Server) -> CodebaseClient {
+    CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+}
+
+async fn mock_lookup(server: &mut mockito::Server) -> Vec<mockito::Mock> {
+    let routes = [
+        (
+            "/proj/tickets/statuses",
+            r#"<ticketing-statuses>
+                <ticketing-status><id>1</id><name>Open</name></ticketing-status>
+                <ticketing-status><id>2</id><name>In Progress</name></ticketing-status>
+                <ticketing-status><id>3</id><name>Closed</name></ticketing-status>
+            </ticketing-statuses>"#,
+        ),
+        (
+            "/proj/tickets/priorities",
+            r#"<ticketing-priorities>
+                <ticketing-priority><id>10</id><name>Normal</name></ticketing-priority>
+                <ticketing-priority><id>11</id><name>High</name></ticketing-priority>
+            </ticketing-priorities>"#,
+        ),
+        (
+            "/proj/tickets/categories",
+            r#"<ticketing-categories>
+                <ticketing-category><id>5</id><name>Backend</name></ticketing-category>
+            </ticketing-categories>"#,
+        ),
+        (
+            "/proj/tickets/types",
+            r#"<ticketing-types>
+                <ticketing-type><id>1</id><name>Bug</name></ticketing-type>
+                <ticketing-type><id>2</id><name>Task</name></ticketing-type>
+            </ticketing-types>"#,
+        ),
+        (
+            "/proj/assignments",
+            r#"<users>
+                <user><id>101</id><first-name>John</first-name><last-name>Doe</last-name><username>jdoe</username></user>
+                <user><id>102</id><username>bot</username></user>
+            </users>"#,
+        ),
+    ];
+    let mut mocks = Vec::new();
+    for (path, body) in routes {
+        mocks.push(
+            server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await,
+        );
+    }
+    mocks
+}
+
+fn notes() -> Vec<TicketNote> {
+    quick_xml::de::from_str::<codebase_cli::api::models::TicketNotes>(
+        r#"<ticket-notes>
+            <ticket-note>
+                <id>1</id>
+                <content>Looking into it</content>
+                <time-added>2024-01-01T10:00:00Z</time-added>
+                <changes><status-id>2</status-id><assignee-id>101</assignee-id></changes>
+            </ticket-note>
+            <ticket-note>
+                <id>2</id>
+                <content>Bumping priority</content>
+                <time-added>2024-01-02T10:00:00Z</time-added>
+                <changes><priority-id>11</priority-id><category-id>5</category-id></changes>
+            </ticket-note>
+            <ticket-note>
+                <id>3</id>
+                <content>Fixed</content>
+                <time-added>2024-01-03T10:00:00Z</time-added>
+                <changes><status-id>3</status-id><assignee-id>102</assignee-id><subject>Renamed</subject></changes>
+            </ticket-note>
+        </ticket-notes>"#,
+    )
+    .unwrap()
+    .notes
+}
+
+#[tokio::test]
+async fn test_show_ticket() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/proj/tickets/42")
+        .with_status(200)
+        .with_body(
+            r#"<ticket>
+                <ticket-id>42</ticket-id>
+                <summary>Broken login</summary>
+                <ticket-type>bug</ticket-type>
+                <status-id>3</status-id>
+                <assignee>bot</assignee>
+            </ticket>"#,
+        )
+        .create_async()
+        .await;
+
+    let ticket = show_ticket(&client_for(&server), "proj", 42).await.unwrap();
+
+    assert_eq!(ticket.ticket_id, Some(42));
+    assert_eq!(ticket.summary.as_deref(), Some("Broken login"));
+    assert_eq!(ticket.status_id, Some(3));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_lookup_fetch_resolves_names() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = mock_lookup(&mut server).await;
+
+    let lookup = ProjectLookup::fetch(&client_for(&server), "proj")
+        .await
+        .unwrap();
+
+    assert_eq!(lookup.status_name(2), Some("In Progress"));
+    assert_eq!(lookup.priority_name(11), Some("High"));
+    assert_eq!(lookup.category_name(5), Some("Backend"));
+    assert_eq!(lookup.user_name(101).as_deref(), Some("John Doe"));
+    assert_eq!(lookup.user_name(102).as_deref(), Some("bot"));
+    assert_eq!(lookup.status_name(99), None);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_timeline_renders_transitions_with_previous_values() {
+    let mut server = mockito::Server::new_async().await;
+    let _mocks = mock_lookup(&mut server).await;
+    let lookup = ProjectLookup::fetch(&client_for(&server), "proj")
+        .await
+        .unwrap();
+
+    let entries = timeline(&notes(), &lookup);
+
+    let rendered: Vec<Vec<String>> = entries
+        .iter()
+        .map(|e| e.transitions.iter().map(|t| t.to_string()).collect())
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            vec!["status → In Progress", "assignee → John Doe"],
+            vec!["priority → High", "category → Backend"],
+            vec![
+                "status: In Progress → Closed",
+                "assignee: John Doe → bot",
+                "subject → Renamed",
+            ],
+        ]
+    );
+    assert_eq!(entries[2].note.content.as_deref(), Some("Fixed"));
+}
+
+#[test]
+fn test_timeline_falls_back_to_ids_for_unknown_values() {
+    let entries = timeline(&notes(), &ProjectLookup::default());
+
+    assert_eq!(
+        entries[2].transitions[0],
+        Transition {
+            field: "status",
+            from: Some("#2".to_string()),
+            to: "#3".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_timeline_first_transition_uses_updates_old_value() {
+    let mut notes = notes();
+    notes[0].updates = Some(r#"{"status_id":[1,2],"assignee_id":[null,101]}"#.to_string());
+    notes[2].updates = Some(r#"{"subject":["Broken login","Renamed"]}"#.to_string());
+    let lookup = ProjectLookup {
+        statuses: quick_xml::de::from_str::<codebase_cli::api::models::TicketStatuses>(
+            r#"<ticketing-statuses>
+                <ticketing-status><id>1</id><name>Open</name></ticketing-status>
+                <ticketing-status><id>2</id><name>In Progress</name></ticketing-status>
+            </ticketing-statuses>"#,
+        )
+        .unwrap()
+        .statuses,
+        ..ProjectLookup::default()
+    };
+
+    let entries = timeline(&notes, &lookup);
+
+    assert_eq!(
+        entries[0].transitions[0].to_string(),
+        "status: Open → In Progress"
+    );
+    assert_eq!(entries[0].transitions[1].to_string(), "assignee → #101");
+    assert_eq!(
+        entries[2].transitions[2].to_string(),
+        "subject: Broken login → Renamed"
+    );
+}
+
+#[tokio::test]
+async fn test_show_falls_back_to_ids_when_lookup_fails() {
+    let mut server = mockito::Server::new_async().await;
+    let _ticket = server
+        .mock("GET", "/proj/tickets/42")
+        .with_status(200)
+        .with_body("<ticket><ticket-id>42</ticket-id><status-id>3</status-id></ticket>")
+        .create_async()
+        .await;
+    let _notes = server
+        .mock("GET", "/proj/tickets/42/notes")
+        .with_status(200)
+        .with_body("<ticket-notes></ticket-notes>")
+        .create_async()
+        .await;
+    let _forbidden = server
+        .mock("GET", mockito::Matcher::Any)
+        .with_status(403)
+        .with_body("Forbidden")
+        .create_async()
+        .await;
+
+    let show = tickets::run(
+        &client_for(&server),
+        TicketCommand::Show {
+            project: "proj".to_string(),
+            ticket_id: 42,
+        },
+    )
+    .await
+    .unwrap();
+
+    let TicketOutput::Ticket { details, lookup } = show else {
+        panic!("unexpected result: {:?}", show);
+    };
+    assert_eq!(details.ticket.status_id, Some(3));
+    assert_eq!(lookup.status_name(3), None);
+}
@@ -1,7 +1,6 @@
 use codebase_cli::api::client::CodebaseClient;
 use codebase_cli::api::models::NoteChanges;
 use codebase_cli::api::tickets::*;
-use mockito;
 
 #[tokio::test]
 async fn test_list_tickets() {
@@ -290,6 +289,36 @@ async fn test_list_watchers() {
     mock.assert_async().await;
 }
 
+#[tokio::test]
+async fn test_list_watchers_as_typed_integers() {
+    let mut server = mockito::Server::new_async().await;
+
+    let _mock = server
+        .mock("GET", "/test-project/tickets/123/watchers")
+        .with_status(200)
+        .with_header("content-type", "application/xml")
+        .with_body(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <watchers type="array">
+                <watcher type="integer">42</watcher>
+                <watcher type="integer">43</watcher>
+            </watchers>"#,
+        )
+        .create_async()
+        .await;
+
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    );
+
+    let watchers = list_watchers(&client, "test-project", 123).await.unwrap();
+    let ids: Vec<_> = watchers.iter().map(|w| w.watcher).collect();
+    assert_eq!(ids, [Some(42), Some(43)]);
+}
+
 #[tokio::test]
 async fn test_set_watchers() {
     let mut server = mockito::Server::new_async().await;
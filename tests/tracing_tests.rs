@@ -0,0 +1,182 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use codebase_cli::api::client::CodebaseClient;
+use codebase_cli::api::redact::{self, for_log, is_sensitive, redact_xml};
+use codebase_cli::api::retry::RetryPolicy;
+use tracing::Level;
+
+/// Collects formatted log output in memory.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Captured {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+async fn logged_request(level: Level) -> String {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/proj/tickets")
+        .with_status(201)
+        .with_body("<ticket><ticket-id>9</ticket-id><api-key>s3cret</api-key></ticket>")
+        .create_async()
+        .await;
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_retry_policy(RetryPolicy::none());
+
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    client
+        .post(
+            "/proj/tickets",
+            "<ticket><summary>Hi</summary><password>hunter2</password></ticket>".to_string(),
+        )
+        .await
+        .unwrap();
+    captured.text()
+}
+
+#[tokio::test]
+async fn test_info_logs_each_response() {
+    let log = logged_request(Level::INFO).await;
+
+    assert!(log.contains("response"), "{}", log);
+    assert!(log.contains("method=POST"), "{}", log);
+    assert!(log.contains("/proj/tickets"), "{}", log);
+    assert!(log.contains("status=201"), "{}", log);
+    assert!(log.contains("attempt=1"), "{}", log);
+    assert!(log.contains("elapsed_ms="), "{}", log);
+    assert!(!log.contains("body"), "{}", log);
+    assert!(!log.contains("test-key"), "{}", log);
+}
+
+#[tokio::test]
+async fn test_debug_logs_redacted_bodies() {
+    let log = logged_request(Level::DEBUG).await;
+
+    assert!(log.contains("request body"), "{}", log);
+    assert!(log.contains("<summary>Hi</summary>"), "{}", log);
+    assert!(log.contains("<password>[REDACTED]</password>"), "{}", log);
+    assert!(log.contains("response body"), "{}", log);
+    assert!(log.contains("<api-key>[REDACTED]</api-key>"), "{}", log);
+    assert!(!log.contains("hunter2"), "{}", log);
+    assert!(!log.contains("s3cret"), "{}", log);
+}
+
+#[tokio::test]
+async fn test_retries_are_warnings_without_query_values() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/widgets/tickets")
+        .match_query(mockito::Matcher::Any)
+        .with_status(503)
+        .create_async()
+        .await;
+    let client = CodebaseClient::with_base_url(
+        server.url(),
+        "test-account".to_string(),
+        "test-user".to_string(),
+        "test-key".to_string(),
+    )
+    .with_retry_policy(RetryPolicy {
+        max_attempts: 2,
+        base_delay: Duration::from_millis(1),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    });
+
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    client
+        .get("/widgets/tickets?query=assignee:jdoe")
+        .await
+        .unwrap_err();
+    let log = captured.text();
+
+    assert_eq!(log.matches("retrying").count(), 1, "{}", log);
+    assert!(log.contains("WARN"), "{}", log);
+    assert!(log.contains("attempt=1 max_attempts=2"), "{}", log);
+    assert!(log.contains("delay="), "{}", log);
+    assert!(log.contains("status=503"), "{}", log);
+    assert!(log.contains("/widgets/tickets?query=[REDACTED]"), "{}", log);
+    assert!(!log.contains("jdoe"), "{}", log);
+}
+
+#[test]
+fn test_query_values_are_redacted_in_urls() {
+    assert_eq!(
+        redact::url("https://x.example/p/tickets?query=status:open&page=2&raw"),
+        "https://x.example/p/tickets?query=[REDACTED]&page=[REDACTED]&raw"
+    );
+    assert_eq!(redact::url("/projects"), "/projects");
+}
+
+#[test]
+fn test_sensitive_names() {
+    for name in [
+        "api-key",
+        "api_key",
+        "password",
+        "access-token",
+        "client-secret",
+    ] {
+        assert!(is_sensitive(name), "{}", name);
+    }
+    for name in ["summary", "username", "ticket-id", "key-name"] {
+        assert!(!is_sensitive(name), "{}", name);
+    }
+}
+
+#[test]
+fn test_redact_xml() {
+    assert_eq!(
+        redact_xml(
+            r#"<user><username>jdoe</username><api-key type="string">abc</api-key><token/></user>"#
+        ),
+        r#"<user><username>jdoe</username><api-key type="string">[REDACTED]</api-key><token/></user>"#
+    );
+    assert_eq!(redact_xml("not xml at all"), "not xml at all");
+    assert_eq!(redact_xml("<password>unclosed"), "<password>unclosed");
+}
+
+#[test]
+fn test_long_bodies_are_shortened_for_logs() {
+    let body = "x".repeat(5000);
+    let logged = for_log(&body);
+    assert!(logged.starts_with(&"x".repeat(4096)));
+    assert!(logged.ends_with("… (904 more bytes)"), "{}", logged);
+    assert_eq!(for_log("<a>short</a>"), "<a>short</a>");
+}